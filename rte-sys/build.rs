@@ -209,6 +209,7 @@ fn gen_cargo_config(base_dir: &PathBuf) {
                     "rte_meter",
                     "rte_pipeline",
                     "rte_port",
+                    "rte_reorder",
                     "rte_ring",
                     "rte_table",
                     "rte_timer",
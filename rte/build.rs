@@ -2,6 +2,8 @@ extern crate gcc;
 
 use std::env;
 use std::env::consts::*;
+use std::fs::File;
+use std::io::prelude::*;
 use std::path::PathBuf;
 
 fn rte_config(base_dir: &PathBuf) -> gcc::Config {
@@ -56,6 +58,57 @@ fn gen_cargo_config(base_dir: &PathBuf) {
              base_dir.join("include").to_str().unwrap());
 }
 
+// The DPDK releases that can be checked with `#[cfg(dpdk_ver_<year>_<month>)]`.
+static DPDK_RELEASES: &'static [(u32, u32)] = &[(16, 4), (16, 7), (16, 11), (17, 2), (17, 5),
+                                                (17, 8), (17, 11), (18, 2), (18, 5), (18, 8),
+                                                (18, 11), (19, 2), (19, 5), (19, 8), (19, 11),
+                                                (20, 2), (20, 5), (20, 8), (20, 11), (21, 2),
+                                                (21, 5), (21, 8), (21, 11), (22, 3), (22, 7),
+                                                (22, 11), (23, 3), (23, 7), (23, 11)];
+
+fn dpdk_version(base_dir: &PathBuf) -> (u32, u32, u32) {
+    let path = base_dir.join("include").join("rte_version.h");
+    let mut header = String::new();
+
+    File::open(&path)
+        .and_then(|mut f| f.read_to_string(&mut header))
+        .unwrap_or_else(|err| panic!("fail to read {}, {}", path.to_str().unwrap(), err));
+
+    let define = |name: &str| {
+        header.lines()
+              .filter_map(|line| {
+                  let mut tokens = line.split_whitespace();
+
+                  match (tokens.next(), tokens.next(), tokens.next()) {
+                      (Some("#define"), Some(key), Some(value)) if key == name => {
+                          value.parse::<u32>().ok()
+                      }
+                      _ => None,
+                  }
+              })
+              .next()
+              .unwrap_or_else(|| panic!("missing {} in rte_version.h", name))
+    };
+
+    (define("RTE_VER_YEAR"), define("RTE_VER_MONTH"), define("RTE_VER_MINOR"))
+}
+
+fn gen_version_cfg(base_dir: &PathBuf) {
+    let (year, month, minor) = dpdk_version(base_dir);
+
+    println!("cargo:rustc-env=DPDK_VERSION_YEAR={}", year);
+    println!("cargo:rustc-env=DPDK_VERSION_MONTH={}", month);
+    println!("cargo:rustc-env=DPDK_VERSION_MINOR={}", minor);
+
+    for &(y, m) in DPDK_RELEASES {
+        println!("cargo:rustc-check-cfg=cfg(dpdk_ver_{}_{:02})", y, m);
+
+        if (y, m) <= (year, month) {
+            println!("cargo:rustc-cfg=dpdk_ver_{}_{:02}", y, m);
+        }
+    }
+}
+
 fn main() {
     let root_dir = env::var("RTE_SDK")
                        .expect("RTE_SDK - Points to the DPDK installation directory.");
@@ -64,12 +117,67 @@ fn main() {
 
     let base_dir = PathBuf::from(root_dir).join(target);
 
-    rte_config(&base_dir)
-        .file("src/rte_helpers.c")
-        .compile("librte_helpers.a");
+    let mut helpers = rte_config(&base_dir);
+
+    if env::var("CARGO_FEATURE_TM").is_ok() {
+        helpers.define("RTE_LIBRTE_TM", None);
+    }
+
+    if env::var("CARGO_FEATURE_MTR").is_ok() {
+        helpers.define("RTE_LIBRTE_MTR", None);
+    }
+
+    if env::var("CARGO_FEATURE_TRACE").is_ok() {
+        helpers.define("ALLOW_EXPERIMENTAL_API", None);
+        helpers.define("RTE_LIBRTE_TRACE", None);
+    }
+
+    if env::var("CARGO_FEATURE_EVENTDEV").is_ok() {
+        helpers.define("RTE_LIBRTE_EVENTDEV", None);
+    }
+
+    if env::var("CARGO_FEATURE_GRAPH").is_ok() {
+        helpers.define("ALLOW_EXPERIMENTAL_API", None);
+        helpers.define("RTE_LIBRTE_GRAPH", None);
+    }
+
+    if env::var("CARGO_FEATURE_SECURITY").is_ok() {
+        helpers.define("ALLOW_EXPERIMENTAL_API", None);
+        helpers.define("RTE_LIBRTE_SECURITY", None);
+    }
+
+    helpers.file("src/rte_helpers.c")
+           .compile("librte_helpers.a");
 
     gen_cargo_config(&base_dir);
 
+    gen_version_cfg(&base_dir);
+
+    if env::var("CARGO_FEATURE_EVENTDEV").is_ok() {
+        println!("cargo:rustc-link-lib=static=rte_eventdev");
+    }
+
+    if env::var("CARGO_FEATURE_GRAPH").is_ok() {
+        println!("cargo:rustc-link-lib=static=rte_graph");
+        println!("cargo:rustc-link-lib=static=rte_node");
+    }
+
+    if env::var("CARGO_FEATURE_METRICS").is_ok() {
+        println!("cargo:rustc-link-lib=static=rte_metrics");
+    }
+
+    if env::var("CARGO_FEATURE_PDUMP").is_ok() {
+        println!("cargo:rustc-link-lib=static=rte_pdump");
+    }
+
+    if env::var("CARGO_FEATURE_SECURITY").is_ok() {
+        println!("cargo:rustc-link-lib=static=rte_security");
+    }
+
+    if env::var("CARGO_FEATURE_TELEMETRY").is_ok() {
+        println!("cargo:rustc-link-lib=static=rte_telemetry");
+    }
+
     rte_config(&base_dir)
         .file("examples/l2fwd/l2fwd_core.c")
         .compile("libl2fwd_core.a");
@@ -128,7 +128,7 @@ fn bond_port_init(slave_count: u8,
         .expect(&format!("fail to setup device tx queue: port={}", bonded_port_id));
 
     for slave_port_id in 0..slave_count {
-        dev.add_slave(slave_port_id)
+        dev.add_slave(ethdev::PortId::new_unchecked(slave_port_id))
             .expect(&format!("Oooops! adding slave {} to bond {} failed!",
                              slave_port_id,
                              bonded_port_id));
@@ -503,7 +503,7 @@ fn main() {
 
     // initialize all ports
     for portid in 0..nb_ports {
-        slave_port_init(portid, &port_conf, pktmbuf_pool);
+        slave_port_init(ethdev::PortId::new_unchecked(portid), &port_conf, pktmbuf_pool);
     }
 
     let bonded_dev = bond_port_init(nb_ports, &port_conf, pktmbuf_pool);
@@ -60,7 +60,7 @@ struct CmdIntParams {
 
 impl CmdIntParams {
     fn dev(&self) -> ethdev::PortId {
-        self.port as ethdev::PortId
+        ethdev::PortId::new_unchecked(self.port as u8)
     }
 
     fn open(&mut self, cl: &cmdline::CmdLine, app_cfg: Option<&AppConfig>) {
@@ -165,14 +165,14 @@ struct CmdIntMtuParams {
 
 impl CmdIntMtuParams {
     fn dev(&self) -> ethdev::PortId {
-        self.port as ethdev::PortId
+        ethdev::PortId::new_unchecked(self.port as u8)
     }
 
     fn mtu_list(&mut self, cl: &cmdline::CmdLine, app_cfg: Option<&AppConfig>) {
         debug!("execute list `{}` command for port {}", self.cmd, self.port);
 
         for portid in 0..app_cfg.unwrap().ports.len() {
-            let dev = portid as ethdev::PortId;
+            let dev = ethdev::PortId::new_unchecked(portid as u8);
 
             cl.println(format!("Port {} MTU: {}", portid, dev.mtu().unwrap()))
                 .unwrap();
@@ -197,7 +197,7 @@ impl CmdIntMtuParams {
 
         let dev = self.dev();
 
-        cl.println(if let Err(err) = dev.set_mtu(self.mtu) {
+        cl.println(if let Err(err) = dev.set_mtu_checked(self.mtu) {
                 format!("Error: Fail to change mac address of port {}, {}",
                         self.port,
                         err)
@@ -216,14 +216,14 @@ struct CmdIntMacParams {
 
 impl CmdIntMacParams {
     fn dev(&self) -> ethdev::PortId {
-        self.port as ethdev::PortId
+        ethdev::PortId::new_unchecked(self.port as u8)
     }
 
     fn list(&mut self, cl: &cmdline::CmdLine, app_cfg: Option<&AppConfig>) {
         debug!("execute list `{}` command for port {}", self.cmd, self.port);
 
         for portid in 0..app_cfg.unwrap().ports.len() {
-            let dev = portid as ethdev::PortId;
+            let dev = ethdev::PortId::new_unchecked(portid as u8);
 
             cl.println(format!("Port {} MAC Address: {}", portid, dev.mac_addr())).unwrap();
         }
@@ -281,7 +281,7 @@ struct CmdVlanParams {
 
 impl CmdVlanParams {
     fn dev(&self) -> ethdev::PortId {
-        self.port as ethdev::PortId
+        ethdev::PortId::new_unchecked(self.port as u8)
     }
 
     fn change(&mut self, cl: &cmdline::CmdLine, _: Option<&c_void>) {
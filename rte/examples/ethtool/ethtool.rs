@@ -51,7 +51,7 @@ impl AppConfig {
     pub fn lock_port<T, F>(&self, port: ethdev::PortId, callback: F) -> result::Result<T, String>
         where F: Fn(&mut AppPort, ethdev::PortId) -> result::Result<T, String>
     {
-        match self.ports.iter().nth(port as usize) {
+        match self.ports.iter().nth(u8::from(port) as usize) {
             Some(mutex) => {
                 if !port.is_valid() {
                     Err(format!("port {} is invalid", port))
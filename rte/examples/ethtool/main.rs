@@ -33,7 +33,7 @@ fn setup_ports(app_cfg: &mut AppConfig) {
         if let Ok(mut guard) = mutex.lock() {
             let app_port: &mut AppPort = &mut *guard;
 
-            let dev = portid as ethdev::PortId;
+            let dev = ethdev::PortId::new_unchecked(portid as u8);
             let dev_info = dev.info();
 
             let size_pktpool = dev_info.rx_desc_lim.nb_max + dev_info.tx_desc_lim.nb_max +
@@ -94,7 +94,7 @@ extern "C" fn slave_main(app_cfg: &mut AppConfig) -> i32 {
                     continue;
                 }
 
-                let dev = portid as ethdev::PortId;
+                let dev = ethdev::PortId::new_unchecked(portid as u8);
 
                 // MAC address was updated
                 if app_port.port_dirty {
@@ -329,7 +329,7 @@ extern "C" fn kni_change_mtu(port_id: u8, new_mtu: libc::c_uint) -> libc::c_int
     }
 
     if new_mtu > ETHER_MAX_LEN {
-        let dev = port_id as ethdev::PortId;
+        let dev = ethdev::PortId::new_unchecked(port_id);
 
         dev.stop();
 
@@ -379,7 +379,7 @@ extern "C" fn kni_config_network_interface(port_id: u8, if_up: u8) -> libc::c_in
         return -libc::EINVAL;
     }
 
-    let dev = port_id as ethdev::PortId;
+    let dev = ethdev::PortId::new_unchecked(port_id);
 
     dev.stop();
 
@@ -397,7 +397,7 @@ extern "C" fn kni_config_network_interface(port_id: u8, if_up: u8) -> libc::c_in
 }
 
 fn kni_alloc(conf: &Conf, dev: ethdev::PortId, pktmbuf_pool: &mut mempool::RawMemoryPool) {
-    let portid = dev.portid();
+    let portid = u8::from(dev.portid());
 
     let param = unsafe {
         if conf.port_params[portid as usize].is_null() {
@@ -626,7 +626,7 @@ fn main() {
         .expect("fail to initial mbuf pool");
 
     let enabled_devices: Vec<ethdev::PortId> = ethdev::devices()
-        .filter(|dev| ((1 << dev.portid()) & conf.enabled_port_mask) != 0)
+        .filter(|dev| ((1 << u8::from(dev.portid())) & conf.enabled_port_mask) != 0)
         .collect();
 
     if enabled_devices.is_empty() {
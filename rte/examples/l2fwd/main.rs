@@ -297,7 +297,7 @@ fn main() {
         .unwrap();
 
     let enabled_devices: Vec<ethdev::PortId> = ethdev::devices()
-        .filter(|dev| ((1 << dev.portid()) & enabled_port_mask) != 0)
+        .filter(|dev| ((1 << u8::from(dev.portid())) & enabled_port_mask) != 0)
         .collect();
 
     if enabled_devices.is_empty() {
@@ -310,7 +310,7 @@ fn main() {
 
     // Each logical core is assigned a dedicated TX queue on each port.
     for dev in &enabled_devices {
-        let portid = dev.portid();
+        let portid = u8::from(dev.portid());
 
         if (nb_ports_in_mask % 2) != 0 {
             unsafe {
@@ -342,7 +342,7 @@ fn main() {
 
     // Initialize the port/queue configuration of each logical core
     for dev in &enabled_devices {
-        let portid = dev.portid();
+        let portid = u8::from(dev.portid());
 
         while !lcore::is_enabled(rx_lcore_id) ||
               conf.queue_conf[rx_lcore_id as usize].n_rx_port == rx_queue_per_lcore {
@@ -366,7 +366,7 @@ fn main() {
 
     // Initialise each port
     for dev in &enabled_devices {
-        let portid = dev.portid() as usize;
+        let portid = u8::from(dev.portid()) as usize;
 
         // init port
         print!("Initializing port {}... ", portid);
@@ -389,15 +389,14 @@ fn main() {
             .expect(&format!("fail to setup device tx queue: port={}", portid));
 
         // Initialize TX buffers
-        let buf = ethdev::alloc_buffer(MAX_PKT_BURST, dev.socket_id())
-            .as_mut_ref()
+        let mut buf = ethdev::alloc_buffer_owned(MAX_PKT_BURST, dev.socket_id())
             .expect(&format!("fail to allocate buffer for tx: port={}", portid));
 
         buf.count_err_packets()
             .expect(&format!("failt to set error callback for tx buffer: port={}", portid));
 
         unsafe {
-            l2fwd_tx_buffers[portid] = buf;
+            l2fwd_tx_buffers[portid] = buf.into_raw();
         }
 
         // Start device
@@ -433,7 +432,7 @@ fn main() {
         dev.close();
         println!(" Done");
 
-        if let Some(buf) = (unsafe { l2fwd_tx_buffers[dev.portid() as usize] }).as_mut_ref() {
+        if let Some(buf) = (unsafe { l2fwd_tx_buffers[u8::from(dev.portid()) as usize] }).as_mut_ref() {
             buf.free();
         }
     }
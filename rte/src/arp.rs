@@ -1,7 +1,243 @@
+//! ARP packet construction and parsing.
+//!
+//! A simple ARP proxy answers the requests for the addresses it owns.
+//!
+//! ```no_run
+//! use rte::*;
+//! use rte::mbuf::PktMbuf;
+//! use rte::ether::EtherAddr;
+//!
+//! fn proxy_arp(m: &mut mbuf::RawMbuf,
+//!              pool: &mut mempool::RawMemoryPool,
+//!              our_hw: EtherAddr,
+//!              our_ip: u32)
+//!              -> Option<mbuf::RawMbufPtr> {
+//!     let reply = match arp::parse(m) {
+//!         Some(ref req) if req.is_request() && req.target_proto_addr() == our_ip => {
+//!             Some(arp::ArpReply::new(req, our_hw))
+//!         }
+//!         _ => None,
+//!     };
+//!
+//!     m.free();
+//!
+//!     reply.and_then(|reply| reply.build(pool).ok())
+//! }
+//! ```
+
+use std::mem;
+use std::ptr;
+use std::marker::PhantomData;
+
 use ffi;
 
+use errors::{Error, Result};
+use ether::{EtherAddr, EtherHdr, ETHER_TYPE_ARP_BE};
+use mbuf::{self, PktMbuf, PktMbufPool};
+use mempool;
+
 /// ARP header IPv4 payload.
 pub type ArpIpv4 = ffi::Struct_arp_ipv4;
 
 /// ARP header.
 pub type ArpHdr = ffi::Struct_arp_hdr;
+
+/// Ethernet hardware format.
+pub const ARP_HRD_ETHER: u16 = 1;
+
+/// Request to resolve an address.
+pub const ARP_OP_REQUEST: u16 = 1;
+/// Response to the previous request.
+pub const ARP_OP_REPLY: u16 = 2;
+/// Request to resolve the reverse address.
+pub const ARP_OP_REVREQUEST: u16 = 3;
+/// Response to the previous reverse request.
+pub const ARP_OP_REVREPLY: u16 = 4;
+
+const ETHER_TYPE_IPV4: u16 = 0x0800;
+const ETHER_HDR_LEN: usize = 14;
+const ARP_PKT_LEN: usize = ETHER_HDR_LEN + 28;
+
+/// A view of the ARP packet in the mbuf, the addresses are in the host byte order.
+pub struct ArpPacket<'a> {
+    hdr: ArpHdr,
+    _marker: PhantomData<&'a mbuf::RawMbuf>,
+}
+
+impl<'a> ArpPacket<'a> {
+    /// The ARP operation code.
+    pub fn operation(&self) -> u16 {
+        u16::from_be(self.hdr.arp_op)
+    }
+
+    pub fn is_request(&self) -> bool {
+        self.operation() == ARP_OP_REQUEST
+    }
+
+    pub fn is_reply(&self) -> bool {
+        self.operation() == ARP_OP_REPLY
+    }
+
+    pub fn sender_hw_addr(&self) -> EtherAddr {
+        EtherAddr::from(self.hdr.arp_data.arp_sha)
+    }
+
+    pub fn sender_proto_addr(&self) -> u32 {
+        u32::from_be(self.hdr.arp_data.arp_sip)
+    }
+
+    pub fn target_hw_addr(&self) -> EtherAddr {
+        EtherAddr::from(self.hdr.arp_data.arp_tha)
+    }
+
+    pub fn target_proto_addr(&self) -> u32 {
+        u32::from_be(self.hdr.arp_data.arp_tip)
+    }
+
+    /// Extract the raw ARP header.
+    pub fn as_raw(&self) -> &ArpHdr {
+        &self.hdr
+    }
+}
+
+/// Parse the Ethernet/IPv4 ARP packet in the mbuf.
+pub fn parse(m: &mbuf::RawMbuf) -> Option<ArpPacket> {
+    if (m.data_len as usize) < ARP_PKT_LEN {
+        return None;
+    }
+
+    let m = m as *const mbuf::RawMbuf;
+    let eth = pktmbuf_mtod!(m, *const EtherHdr);
+
+    let hdr = unsafe {
+        if (*eth).ether_type != ETHER_TYPE_ARP_BE {
+            return None;
+        }
+
+        ptr::read_unaligned(pktmbuf_mtod_offset!(m, *const ArpHdr, ETHER_HDR_LEN))
+    };
+
+    if u16::from_be(hdr.arp_hrd) != ARP_HRD_ETHER || u16::from_be(hdr.arp_pro) != ETHER_TYPE_IPV4 ||
+       hdr.arp_hln as usize != mem::size_of::<ffi::Struct_ether_addr>() ||
+       hdr.arp_pln as usize != mem::size_of::<u32>() {
+        return None;
+    }
+
+    Some(ArpPacket {
+        hdr: hdr,
+        _marker: PhantomData,
+    })
+}
+
+/// Build the Ethernet frame of the ARP packet in a new mbuf.
+fn build(pool: &mut mempool::RawMemoryPool,
+         op: u16,
+         dst: EtherAddr,
+         sender_hw: EtherAddr,
+         sender_ip: u32,
+         target_hw: EtherAddr,
+         target_ip: u32)
+         -> Result<mbuf::RawMbufPtr> {
+    let m = pool.alloc();
+
+    if m.is_null() {
+        return Err(Error::rte_error());
+    }
+
+    let p = match unsafe { (*m).append(ARP_PKT_LEN) } {
+        Ok(p) => p,
+        Err(err) => {
+            unsafe { (*m).free() };
+
+            return Err(err);
+        }
+    };
+
+    let eth = ffi::Struct_ether_hdr {
+        d_addr: ffi::Struct_ether_addr { addr_bytes: *dst.octets() },
+        s_addr: ffi::Struct_ether_addr { addr_bytes: *sender_hw.octets() },
+        ether_type: ETHER_TYPE_ARP_BE,
+    };
+
+    let hdr = ArpHdr {
+        arp_hrd: ARP_HRD_ETHER.to_be(),
+        arp_pro: ETHER_TYPE_IPV4.to_be(),
+        arp_hln: mem::size_of::<ffi::Struct_ether_addr>() as u8,
+        arp_pln: mem::size_of::<u32>() as u8,
+        arp_op: op.to_be(),
+        arp_data: ArpIpv4 {
+            arp_sha: ffi::Struct_ether_addr { addr_bytes: *sender_hw.octets() },
+            arp_sip: sender_ip.to_be(),
+            arp_tha: ffi::Struct_ether_addr { addr_bytes: *target_hw.octets() },
+            arp_tip: target_ip.to_be(),
+        },
+    };
+
+    unsafe {
+        ptr::write_unaligned(p as *mut EtherHdr, eth);
+        ptr::write_unaligned(p.offset(ETHER_HDR_LEN as isize) as *mut ArpHdr, hdr);
+    }
+
+    Ok(m)
+}
+
+/// An ARP request to resolve the hardware address of the target.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ArpRequest {
+    pub sender_hw: EtherAddr,
+    pub sender_ip: u32,
+    pub target_ip: u32,
+}
+
+impl ArpRequest {
+    pub fn new(sender_hw: EtherAddr, sender_ip: u32, target_ip: u32) -> ArpRequest {
+        ArpRequest {
+            sender_hw: sender_hw,
+            sender_ip: sender_ip,
+            target_ip: target_ip,
+        }
+    }
+
+    /// Build the broadcast request in a new mbuf allocated from the pool.
+    pub fn build(&self, pool: &mut mempool::RawMemoryPool) -> Result<mbuf::RawMbufPtr> {
+        build(pool,
+              ARP_OP_REQUEST,
+              EtherAddr::broadcast(),
+              self.sender_hw,
+              self.sender_ip,
+              EtherAddr::zeroed(),
+              self.target_ip)
+    }
+}
+
+/// An ARP reply to the sender of a request.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ArpReply {
+    pub sender_hw: EtherAddr,
+    pub sender_ip: u32,
+    pub target_hw: EtherAddr,
+    pub target_ip: u32,
+}
+
+impl ArpReply {
+    /// Reply to the request with our hardware address.
+    pub fn new(request: &ArpPacket, our_hw: EtherAddr) -> ArpReply {
+        ArpReply {
+            sender_hw: our_hw,
+            sender_ip: request.target_proto_addr(),
+            target_hw: request.sender_hw_addr(),
+            target_ip: request.sender_proto_addr(),
+        }
+    }
+
+    /// Build the unicast reply in a new mbuf allocated from the pool.
+    pub fn build(&self, pool: &mut mempool::RawMemoryPool) -> Result<mbuf::RawMbufPtr> {
+        build(pool,
+              ARP_OP_REPLY,
+              self.target_hw,
+              self.sender_hw,
+              self.sender_ip,
+              self.target_hw,
+              self.target_ip)
+    }
+}
@@ -0,0 +1,184 @@
+//! Asynchronous receive of the RX queues in interrupt mode, for the tokio runtime.
+//!
+//! The port must be configured with `intr_conf.rxq` set. The queue is polled until it is
+//! empty, then the RX queue interrupt is enabled and the task sleeps until it fires.
+
+use std::mem;
+use std::ptr;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::os::unix::io::{AsRawFd, RawFd};
+
+use libc;
+use futures::stream::Stream;
+use tokio::io::unix::AsyncFd;
+
+use ffi;
+
+use errors::{Error, Result};
+use ethdev::{EthDevice, IntrCtlOp, PortId, QueueId};
+use mbuf::{PktMbuf, RawMbufPtr};
+
+/// The size of the burst buffer of `AsyncRxQueue`.
+const ASYNC_RX_BURST_SIZE: usize = 32;
+
+/// The maximum epoll events drained after the interrupt fired.
+const ASYNC_RX_MAX_EVENTS: usize = 8;
+
+/// An epoll instance dedicated to the interrupt of a RX queue.
+struct EpollFd(RawFd);
+
+impl AsRawFd for EpollFd {
+    fn as_raw_fd(&self) -> RawFd {
+        self.0
+    }
+}
+
+impl Drop for EpollFd {
+    fn drop(&mut self) {
+        unsafe { libc::close(self.0) };
+    }
+}
+
+/// A RX queue as a stream of the received packets.
+///
+/// The packets left in the burst buffer are freed when dropped.
+///
+pub struct AsyncRxQueue {
+    pub port: PortId,
+    pub queue: QueueId,
+    epoll: AsyncFd<EpollFd>,
+    pkts: [RawMbufPtr; ASYNC_RX_BURST_SIZE],
+    pos: usize,
+    len: usize,
+}
+
+impl AsyncRxQueue {
+    /// Bind the interrupt of the RX queue to a new epoll instance, registered in the tokio
+    /// reactor of the current runtime.
+    pub fn new(port: PortId, queue: QueueId) -> Result<AsyncRxQueue> {
+        let fd = unsafe { libc::epoll_create1(libc::EPOLL_CLOEXEC) };
+
+        if fd < 0 {
+            return Err(Error::os_error());
+        }
+
+        let epoll = EpollFd(fd);
+
+        try!(port.rx_intr_ctl(queue, epoll.as_raw_fd(), IntrCtlOp::Add, queue as u64));
+
+        let epoll = match AsyncFd::new(epoll) {
+            Ok(epoll) => epoll,
+            Err(err) => {
+                let _ = port.rx_intr_ctl(queue, fd, IntrCtlOp::Del, queue as u64);
+
+                return Err(Error::IoError(err));
+            }
+        };
+
+        Ok(AsyncRxQueue {
+            port: port,
+            queue: queue,
+            epoll: epoll,
+            pkts: [ptr::null_mut(); ASYNC_RX_BURST_SIZE],
+            pos: 0,
+            len: 0,
+        })
+    }
+
+    /// Consume the fired events, which also clears the interrupt of the device.
+    fn drain_events(&self) {
+        let mut events: [ffi::Struct_rte_epoll_event; ASYNC_RX_MAX_EVENTS] =
+            unsafe { mem::zeroed() };
+
+        unsafe {
+            ffi::rte_epoll_wait(self.epoll.as_raw_fd(),
+                                events.as_mut_ptr(),
+                                ASYNC_RX_MAX_EVENTS as i32,
+                                0)
+        };
+    }
+
+    fn rx_burst(&mut self) -> usize {
+        self.pos = 0;
+        self.len = self.port.rx_burst(self.queue, &mut self.pkts);
+        self.len
+    }
+}
+
+impl Stream for AsyncRxQueue {
+    type Item = RawMbufPtr;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        loop {
+            if this.pos < this.len {
+                this.pos += 1;
+
+                return Poll::Ready(Some(this.pkts[this.pos - 1]));
+            }
+
+            if this.rx_burst() > 0 {
+                continue;
+            }
+
+            if let Err(err) = this.port.rx_intr_enable(this.queue) {
+                warn!("fail to enable RX interrupt of port {} queue {}, {}",
+                      this.port,
+                      this.queue,
+                      err);
+
+                return Poll::Ready(None);
+            }
+
+            // the packets received before the interrupt was enabled don't fire it
+            if this.rx_burst() > 0 {
+                let _ = this.port.rx_intr_disable(this.queue);
+
+                continue;
+            }
+
+            match this.epoll.poll_read_ready(cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Ok(mut guard)) => {
+                    this.drain_events();
+
+                    guard.clear_ready();
+                }
+                Poll::Ready(Err(err)) => {
+                    warn!("fail to wait RX interrupt of port {} queue {}, {}",
+                          this.port,
+                          this.queue,
+                          err);
+
+                    return Poll::Ready(None);
+                }
+            }
+
+            let _ = this.port.rx_intr_disable(this.queue);
+        }
+    }
+}
+
+impl Drop for AsyncRxQueue {
+    fn drop(&mut self) {
+        let _ = self.port.rx_intr_disable(self.queue);
+
+        if let Err(err) = self.port.rx_intr_ctl(self.queue,
+                                                self.epoll.as_raw_fd(),
+                                                IntrCtlOp::Del,
+                                                self.queue as u64) {
+            warn!("fail to unbind RX interrupt of port {} queue {}, {}",
+                  self.port,
+                  self.queue,
+                  err);
+        }
+
+        for m in &self.pkts[self.pos..self.len] {
+            if let Some(m) = unsafe { m.as_mut() } {
+                m.free();
+            }
+        }
+    }
+}
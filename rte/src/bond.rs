@@ -9,14 +9,14 @@ use ether;
 
 /// Supported modes of operation of link bonding library
 #[repr(u8)]
-#[derive(Copy, Clone, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum BondMode {
     /// Round Robin (Mode 0).
     ///
     /// In this mode all transmitted packets will be balanced equally across all
     /// active slaves of the bonded in a round robin fashion.
     ///
-    RouncRobin = ffi::BONDING_MODE_ROUND_ROBIN as u8,
+    RoundRobin = ffi::BONDING_MODE_ROUND_ROBIN as u8,
 
     /// Active Backup (Mode 1).
     ///
@@ -91,7 +91,7 @@ impl From<u8> for BondMode {
 
 /// Balance Mode Transmit Policies
 #[repr(u8)]
-#[derive(Copy, Clone, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum TransmitPolicy {
     /// Layer 2 (Ethernet MAC)
     Layer2,
@@ -112,7 +112,7 @@ pub fn create(name: &str, mode: BondMode, socket_id: SocketId) -> Result<ethdev:
     let port_id =
         unsafe { ffi::rte_eth_bond_create(try!(to_cptr!(name)), mode as u8, socket_id as u8) };
 
-    rte_check!(port_id; ok => { port_id as ethdev::PortId })
+    rte_check!(port_id; ok => { ethdev::PortId::new_unchecked(port_id as u8) })
 }
 
 /// Free a bonded rte_eth_dev device
@@ -162,84 +162,88 @@ pub trait BondedDevice {
 impl BondedDevice for ethdev::PortId {
     fn add_slave(&self, slave: ethdev::PortId) -> Result<&Self> {
         rte_check!(unsafe {
-            ffi::rte_eth_bond_slave_add(*self, slave)
+            ffi::rte_eth_bond_slave_add(u8::from(*self), u8::from(slave))
         }; ok => { self })
     }
 
     fn remove_slave(&self, slave: ethdev::PortId) -> Result<&Self> {
         rte_check!(unsafe {
-            ffi::rte_eth_bond_slave_remove(*self, slave)
+            ffi::rte_eth_bond_slave_remove(u8::from(*self), u8::from(slave))
         }; ok => { self })
     }
 
     fn mode(&self) -> Result<BondMode> {
-        let mode = unsafe { ffi::rte_eth_bond_mode_get(*self) };
+        let mode = unsafe { ffi::rte_eth_bond_mode_get(u8::from(*self)) };
 
         rte_check!(mode; ok => { BondMode::from(mode as u8) })
     }
 
     fn set_mode(&self, mode: BondMode) -> Result<&Self> {
         rte_check!(unsafe {
-            ffi::rte_eth_bond_mode_set(*self, mode as u8)
+            ffi::rte_eth_bond_mode_set(u8::from(*self), mode as u8)
         }; ok => { self })
     }
 
     fn primary(&self) -> Result<ethdev::PortId> {
-        let portid = unsafe { ffi::rte_eth_bond_primary_get(*self) };
+        let portid = unsafe { ffi::rte_eth_bond_primary_get(u8::from(*self)) };
 
-        rte_check!(portid; ok => { portid as ethdev::PortId })
+        rte_check!(portid; ok => { ethdev::PortId::new_unchecked(portid as u8) })
     }
 
     fn set_primary(&self, dev: ethdev::PortId) -> Result<&Self> {
         rte_check!(unsafe {
-            ffi::rte_eth_bond_primary_set(*self, dev)
+            ffi::rte_eth_bond_primary_set(u8::from(*self), u8::from(dev))
         }; ok => { self })
     }
 
     fn slaves(&self) -> Result<Vec<ethdev::PortId>> {
         let mut slaves = [0u8; ffi::RTE_MAX_ETHPORTS as usize];
 
-        let num =
-            unsafe { ffi::rte_eth_bond_slaves_get(*self, slaves.as_mut_ptr(), slaves.len() as u8) };
+        let num = unsafe {
+            ffi::rte_eth_bond_slaves_get(u8::from(*self), slaves.as_mut_ptr(), slaves.len() as u8)
+        };
 
         rte_check!(num; ok => {
-            Vec::from(&slaves[..num as usize])
+            slaves[..num as usize].iter().map(|&id| ethdev::PortId::new_unchecked(id)).collect()
         })
     }
 
     fn active_slaves(&self) -> Result<Vec<ethdev::PortId>> {
         let mut slaves = [0u8; ffi::RTE_MAX_ETHPORTS as usize];
 
-        let num =
-            unsafe { ffi::rte_eth_bond_slaves_get(*self, slaves.as_mut_ptr(), slaves.len() as u8) };
+        let num = unsafe {
+            ffi::rte_eth_bond_active_slaves_get(u8::from(*self),
+                                                slaves.as_mut_ptr(),
+                                                slaves.len() as u8)
+        };
 
         rte_check!(num; ok => {
-            Vec::from(&slaves[..num as usize])
+            slaves[..num as usize].iter().map(|&id| ethdev::PortId::new_unchecked(id)).collect()
         })
     }
 
     fn set_mac_addr(&self, mac_addr: &ether::EtherAddr) -> Result<&Self> {
         rte_check!(unsafe {
-            ffi::rte_eth_bond_mac_address_set(*self,
+            ffi::rte_eth_bond_mac_address_set(u8::from(*self),
                                               mem::transmute(mac_addr.octets().as_ptr()))
         }; ok => { self })
     }
 
     fn reset_mac_addr(&self) -> Result<&Self> {
         rte_check!(unsafe {
-            ffi::rte_eth_bond_mac_address_reset(*self)
+            ffi::rte_eth_bond_mac_address_reset(u8::from(*self))
         }; ok => { self })
     }
 
     fn xmit_policy(&self) -> Result<TransmitPolicy> {
-        let policy = unsafe { ffi::rte_eth_bond_xmit_policy_get(*self) };
+        let policy = unsafe { ffi::rte_eth_bond_xmit_policy_get(u8::from(*self)) };
 
         rte_check!(policy; ok => { TransmitPolicy::from(policy as u8) })
     }
 
     fn set_xmit_policy(&self, policy: TransmitPolicy) -> Result<&Self> {
         rte_check!(unsafe {
-            ffi::rte_eth_bond_xmit_policy_set(*self, policy as u8)
+            ffi::rte_eth_bond_xmit_policy_set(u8::from(*self), policy as u8)
         }; ok => { self })
     }
 }
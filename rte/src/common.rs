@@ -14,6 +14,9 @@ pub enum ProcType {
     Invalid = 2, // RTE_PROC_INVALID
 }
 
+/// The role of the process in a multi-process deployment.
+pub type ProcessType = ProcType;
+
 extern "C" {
     pub fn _rte_cache_line_size() -> size_t;
 }
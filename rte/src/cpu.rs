@@ -0,0 +1,50 @@
+use std::hint;
+use std::sync::atomic::{self, Ordering};
+
+/// Pause the CPU in a spin-wait loop, the `PAUSE` instruction on x86.
+#[inline(always)]
+pub fn pause() {
+    hint::spin_loop()
+}
+
+/// Prevent the compiler from reordering the memory accesses across the barrier.
+#[inline(always)]
+pub fn compiler_barrier() {
+    atomic::compiler_fence(Ordering::SeqCst)
+}
+
+macro_rules! prefetch {
+    ($p:expr, $hint:ident) => ({
+        #[cfg(target_arch = "x86")]
+        unsafe { ::std::arch::x86::_mm_prefetch($p as *const i8, ::std::arch::x86::$hint) }
+        #[cfg(target_arch = "x86_64")]
+        unsafe { ::std::arch::x86_64::_mm_prefetch($p as *const i8, ::std::arch::x86_64::$hint) }
+        #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+        let _ = $p;
+    })
+}
+
+/// Prefetch a cache line into all cache levels.
+#[inline(always)]
+pub fn prefetch0<T>(p: *const T) {
+    prefetch!(p, _MM_HINT_T0)
+}
+
+/// Prefetch a cache line into all cache levels except the 0th cache level.
+#[inline(always)]
+pub fn prefetch1<T>(p: *const T) {
+    prefetch!(p, _MM_HINT_T1)
+}
+
+/// Prefetch a cache line into all cache levels except the 0th and 1st cache levels.
+#[inline(always)]
+pub fn prefetch2<T>(p: *const T) {
+    prefetch!(p, _MM_HINT_T2)
+}
+
+/// Prefetch a cache line into a non-temporal cache structure,
+/// close to the processor to minimize the cache pollution.
+#[inline(always)]
+pub fn prefetch_non_temporal<T>(p: *const T) {
+    prefetch!(p, _MM_HINT_NTA)
+}
@@ -0,0 +1,541 @@
+use std::ptr;
+
+use libc;
+
+use errors::{Error, Result};
+use memory::SocketId;
+use mempool;
+
+pub type CryptoDevId = u8;
+pub type QueuePairId = u16;
+
+/// Get the total number of crypto devices that have been successfully initialised.
+pub fn count() -> u8 {
+    unsafe { rte_cryptodev_count() }
+}
+
+/// Get the device identifier for the named crypto device.
+pub fn get_dev_id(name: &str) -> Result<CryptoDevId> {
+    let dev_id = unsafe { rte_cryptodev_get_dev_id(try!(to_cptr!(name))) };
+
+    rte_check!(dev_id; ok => { dev_id as CryptoDevId })
+}
+
+/// Crypto device configuration structure
+pub struct CryptoConf {
+    /// Socket to allocate resources on
+    pub socket_id: SocketId,
+    /// Number of queue pairs to configure on device
+    pub nb_queue_pairs: u16,
+    /// Number of objects in the session mempool
+    pub session_pool_size: u32,
+    /// Per-lcore cache size of the session mempool
+    pub session_pool_cache_size: u32,
+}
+
+impl Default for CryptoConf {
+    fn default() -> Self {
+        CryptoConf {
+            socket_id: 0,
+            nb_queue_pairs: 1,
+            session_pool_size: 2048,
+            session_pool_cache_size: 64,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Default)]
+pub struct RawCryptoSessionPoolConf {
+    pub nb_objs: libc::uint32_t,
+    pub cache_size: libc::uint32_t,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Default)]
+pub struct RawCryptoConf {
+    pub socket_id: libc::c_int,
+    pub nb_queue_pairs: libc::uint16_t,
+    pub session_mp: RawCryptoSessionPoolConf,
+}
+
+impl<'a> From<&'a CryptoConf> for RawCryptoConf {
+    fn from(conf: &CryptoConf) -> Self {
+        RawCryptoConf {
+            socket_id: conf.socket_id,
+            nb_queue_pairs: conf.nb_queue_pairs,
+            session_mp: RawCryptoSessionPoolConf {
+                nb_objs: conf.session_pool_size,
+                cache_size: conf.session_pool_cache_size,
+            },
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Default)]
+pub struct RawCryptoQueuePairConf {
+    pub nb_descriptors: libc::uint32_t,
+}
+
+/// Crypto operation, allocated from a crypto operation pool.
+pub enum RawCryptoOp {}
+
+pub type RawCryptoOpPtr = *mut RawCryptoOp;
+
+/// The type of crypto operations.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum CryptoOpType {
+    /// Symmetric operation with a session.
+    Symmetric,
+    /// Asymmetric operation.
+    Asymmetric,
+    /// Symmetric operation with the transforms attached to the operation instead of a session.
+    SymmetricSessionless,
+}
+
+impl CryptoOpType {
+    fn raw_type(&self) -> libc::c_uint {
+        match *self {
+            CryptoOpType::Symmetric |
+            CryptoOpType::SymmetricSessionless => 1, // RTE_CRYPTO_OP_TYPE_SYMMETRIC
+            CryptoOpType::Asymmetric => 2, // RTE_CRYPTO_OP_TYPE_ASYMMETRIC
+        }
+    }
+
+    fn is_sessionless(&self) -> libc::c_int {
+        (*self == CryptoOpType::SymmetricSessionless) as libc::c_int
+    }
+}
+
+/// Create a mempool of crypto operations with `priv_sz` bytes of private data.
+pub fn op_pool_create(name: &str,
+                      nb_ops: u32,
+                      cache_size: u32,
+                      priv_sz: u16,
+                      op_type: CryptoOpType,
+                      socket_id: SocketId)
+                      -> Result<mempool::RawMemoryPoolPtr> {
+    let p = unsafe {
+        rte_crypto_op_pool_create(try!(to_cptr!(name)),
+                                  op_type.raw_type(),
+                                  nb_ops,
+                                  cache_size,
+                                  priv_sz,
+                                  socket_id)
+    };
+
+    rte_check!(p, NonNull)
+}
+
+/// Allocate a crypto operation of the type from the crypto operation pool.
+pub fn op_alloc(pool: &mut mempool::RawMemoryPool,
+                op_type: CryptoOpType)
+                -> Result<RawCryptoOpPtr> {
+    let op = unsafe { _rte_crypto_op_alloc(pool, op_type.raw_type(), op_type.is_sessionless()) };
+
+    rte_check!(op, NonNull)
+}
+
+/// Allocate a bulk of crypto operations of the type from the crypto operation pool,
+/// none of them is allocated on error.
+pub fn op_bulk_alloc(pool: &mut mempool::RawMemoryPool,
+                     op_type: CryptoOpType,
+                     ops: &mut [RawCryptoOpPtr])
+                     -> Result<()> {
+    let n = unsafe {
+        _rte_crypto_op_bulk_alloc(pool,
+                                  op_type.raw_type(),
+                                  op_type.is_sessionless(),
+                                  ops.as_mut_ptr(),
+                                  ops.len() as u16)
+    };
+
+    if n as usize == ops.len() {
+        Ok(())
+    } else {
+        Err(Error::OsError(libc::ENOENT))
+    }
+}
+
+/// Free a crypto operation back to its pool.
+pub fn op_free(op: RawCryptoOpPtr) {
+    unsafe { _rte_crypto_op_free(op) }
+}
+
+pub trait CryptoDevice {
+    fn dev_id(&self) -> CryptoDevId;
+
+    /// Configure a crypto device.
+    ///
+    /// This function must be invoked first before any other function in the API.
+    /// This function can also be re-invoked when a device is in the stopped state.
+    ///
+    fn configure(&self, conf: &CryptoConf) -> Result<&Self>;
+
+    /// Allocate and set up a queue pair for a crypto device.
+    fn queue_pair_setup(&self,
+                        qp_id: QueuePairId,
+                        nb_descriptors: u32,
+                        socket_id: SocketId)
+                        -> Result<&Self>;
+
+    /// Get the number of queue pairs on a specific crypto device
+    fn queue_pair_count(&self) -> u16;
+
+    /// Return the NUMA socket to which a device is connected
+    fn socket_id(&self) -> SocketId;
+
+    /// Start a crypto device.
+    fn start(&self) -> Result<&Self>;
+
+    /// Stop a crypto device.
+    fn stop(&self) -> &Self;
+
+    /// Close a stopped crypto device.
+    fn close(&self) -> Result<&Self>;
+
+    /// Enqueue a burst of operations for processing on a queue pair of a crypto device.
+    ///
+    /// Returns the number of operations actually enqueued,
+    /// the remaining operations are still owned by the caller.
+    ///
+    fn enqueue_burst(&self, qp_id: QueuePairId, ops: &[RawCryptoOpPtr]) -> u16;
+
+    /// Dequeue a burst of processed crypto operations from a queue pair of a crypto device.
+    fn dequeue_burst(&self, qp_id: QueuePairId, ops: &mut [RawCryptoOpPtr]) -> u16;
+}
+
+impl CryptoDevice for CryptoDevId {
+    fn dev_id(&self) -> CryptoDevId {
+        *self
+    }
+
+    fn configure(&self, conf: &CryptoConf) -> Result<&Self> {
+        let mut conf = RawCryptoConf::from(conf);
+
+        rte_check!(unsafe { rte_cryptodev_configure(*self, &mut conf) }; ok => { self })
+    }
+
+    fn queue_pair_setup(&self,
+                        qp_id: QueuePairId,
+                        nb_descriptors: u32,
+                        socket_id: SocketId)
+                        -> Result<&Self> {
+        let qp_conf = RawCryptoQueuePairConf { nb_descriptors: nb_descriptors };
+
+        rte_check!(unsafe {
+            rte_cryptodev_queue_pair_setup(*self, qp_id, &qp_conf, socket_id)
+        }; ok => { self })
+    }
+
+    fn queue_pair_count(&self) -> u16 {
+        unsafe { rte_cryptodev_queue_pair_count(*self) }
+    }
+
+    fn socket_id(&self) -> SocketId {
+        unsafe { rte_cryptodev_socket_id(*self) }
+    }
+
+    fn start(&self) -> Result<&Self> {
+        rte_check!(unsafe { rte_cryptodev_start(*self) }; ok => { self })
+    }
+
+    fn stop(&self) -> &Self {
+        unsafe { rte_cryptodev_stop(*self) };
+
+        self
+    }
+
+    fn close(&self) -> Result<&Self> {
+        rte_check!(unsafe { rte_cryptodev_close(*self) }; ok => { self })
+    }
+
+    fn enqueue_burst(&self, qp_id: QueuePairId, ops: &[RawCryptoOpPtr]) -> u16 {
+        unsafe {
+            _rte_cryptodev_enqueue_burst(*self, qp_id, ops.as_ptr() as *mut _, ops.len() as u16)
+        }
+    }
+
+    fn dequeue_burst(&self, qp_id: QueuePairId, ops: &mut [RawCryptoOpPtr]) -> u16 {
+        unsafe { _rte_cryptodev_dequeue_burst(*self, qp_id, ops.as_mut_ptr(), ops.len() as u16) }
+    }
+}
+
+/// Symmetric Cipher Direction
+#[repr(u32)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum CipherOp {
+    Encrypt = 0, // RTE_CRYPTO_CIPHER_OP_ENCRYPT
+    Decrypt = 1, // RTE_CRYPTO_CIPHER_OP_DECRYPT
+}
+
+/// Symmetric Cipher Algorithms
+#[repr(u32)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum CipherAlgo {
+    AesCbc = 5, // RTE_CRYPTO_CIPHER_AES_CBC
+    AesGcm = 10, // RTE_CRYPTO_CIPHER_AES_GCM
+}
+
+/// Symmetric Authentication / Hash Operations
+#[repr(u32)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum AuthOp {
+    Verify = 0, // RTE_CRYPTO_AUTH_OP_VERIFY
+    Generate = 1, // RTE_CRYPTO_AUTH_OP_GENERATE
+}
+
+/// Symmetric Authentication / Hash Algorithms
+#[repr(u32)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum AuthAlgo {
+    AesGcm = 5, // RTE_CRYPTO_AUTH_AES_GCM
+    Sha1Hmac = 12, // RTE_CRYPTO_AUTH_SHA1_HMAC
+    Sha256Hmac = 16, // RTE_CRYPTO_AUTH_SHA256_HMAC
+}
+
+/// Crypto transform of a symmetric crypto session.
+///
+/// Transforms are chained in the order of the slice passed to `CryptoSession::create`,
+/// e.g. `[Cipher, Auth]` for encrypt-then-authenticate.
+/// AES-GCM is expressed as an `AesGcm` cipher followed by an `AesGcm` auth transform.
+///
+#[derive(Copy, Clone, Debug)]
+pub enum CryptoSymXform<'a> {
+    Cipher {
+        op: CipherOp,
+        algo: CipherAlgo,
+        key: &'a [u8],
+    },
+    Auth {
+        op: AuthOp,
+        algo: AuthAlgo,
+        key: &'a [u8],
+        digest_len: u32,
+        aad_len: u32,
+    },
+}
+
+const RTE_CRYPTO_SYM_XFORM_AUTH: u32 = 1;
+const RTE_CRYPTO_SYM_XFORM_CIPHER: u32 = 2;
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct RawCryptoKey {
+    pub data: *mut libc::uint8_t,
+    pub length: libc::size_t,
+}
+
+/// The union of `rte_crypto_cipher_xform` and `rte_crypto_auth_xform`,
+/// the cipher transform is a prefix of the auth transform.
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct RawCryptoXformParams {
+    pub op: libc::uint32_t,
+    pub algo: libc::uint32_t,
+    pub key: RawCryptoKey,
+    pub digest_length: libc::uint32_t,
+    pub add_auth_data_length: libc::uint32_t,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct RawCryptoSymXform {
+    pub next: *mut RawCryptoSymXform,
+    pub type_: libc::uint32_t,
+    pub params: RawCryptoXformParams,
+}
+
+impl<'a> From<&'a CryptoSymXform<'a>> for RawCryptoSymXform {
+    fn from(xform: &CryptoSymXform) -> Self {
+        let (type_, params) = match *xform {
+            CryptoSymXform::Cipher { op, algo, key } => {
+                (RTE_CRYPTO_SYM_XFORM_CIPHER,
+                 RawCryptoXformParams {
+                    op: op as u32,
+                    algo: algo as u32,
+                    key: RawCryptoKey {
+                        data: key.as_ptr() as *mut _,
+                        length: key.len(),
+                    },
+                    digest_length: 0,
+                    add_auth_data_length: 0,
+                })
+            }
+            CryptoSymXform::Auth { op, algo, key, digest_len, aad_len } => {
+                (RTE_CRYPTO_SYM_XFORM_AUTH,
+                 RawCryptoXformParams {
+                    op: op as u32,
+                    algo: algo as u32,
+                    key: RawCryptoKey {
+                        data: key.as_ptr() as *mut _,
+                        length: key.len(),
+                    },
+                    digest_length: digest_len,
+                    add_auth_data_length: aad_len,
+                })
+            }
+        };
+
+        RawCryptoSymXform {
+            next: ptr::null_mut(),
+            type_: type_,
+            params: params,
+        }
+    }
+}
+
+impl RawCryptoSymXform {
+    /// Convert the transforms to the raw transforms linked in the order of the slice.
+    ///
+    /// The returned vector must be kept alive while the head of the chain is in use.
+    ///
+    pub fn chain(xforms: &[CryptoSymXform]) -> Vec<RawCryptoSymXform> {
+        let mut chain: Vec<RawCryptoSymXform> =
+            xforms.iter().map(RawCryptoSymXform::from).collect();
+
+        for i in 1..chain.len() {
+            let next = &mut chain[i] as *mut _;
+
+            chain[i - 1].next = next;
+        }
+
+        chain
+    }
+}
+
+pub enum RawCryptoSession {}
+
+pub type RawCryptoSessionPtr = *mut RawCryptoSession;
+
+/// Symmetric crypto session, freed when dropped.
+///
+/// The session is allocated from the session mempool of the device,
+/// sized with `CryptoConf::session_pool_size`.
+///
+pub struct CryptoSession {
+    dev_id: CryptoDevId,
+    sess: RawCryptoSessionPtr,
+}
+
+impl Drop for CryptoSession {
+    fn drop(&mut self) {
+        if let Err(err) = self.free() {
+            warn!("fail to free crypto session, {}", err);
+        }
+    }
+}
+
+impl CryptoSession {
+    /// Initialise a session for the chain of transforms on the specified crypto device.
+    pub fn create(dev_id: CryptoDevId, xforms: &[CryptoSymXform]) -> Result<CryptoSession> {
+        if xforms.is_empty() {
+            return Err(Error::OsError(libc::EINVAL));
+        }
+
+        let mut chain = RawCryptoSymXform::chain(xforms);
+
+        let sess = unsafe { rte_cryptodev_sym_session_create(dev_id, chain.as_mut_ptr()) };
+
+        rte_check!(sess, NonNull; ok => {
+            CryptoSession {
+                dev_id: dev_id,
+                sess: sess,
+            }
+        })
+    }
+
+    /// The crypto device of the session.
+    pub fn dev_id(&self) -> CryptoDevId {
+        self.dev_id
+    }
+
+    /// Extract the raw pointer from an underlying object.
+    pub fn as_raw(&self) -> RawCryptoSessionPtr {
+        self.sess
+    }
+
+    /// Free the memory associated with the session back to the session mempool.
+    pub fn free(&mut self) -> Result<()> {
+        if self.sess.is_null() {
+            return Ok(());
+        }
+
+        let sess = unsafe { rte_cryptodev_sym_session_free(self.dev_id, self.sess) };
+
+        if sess.is_null() {
+            self.sess = ptr::null_mut();
+
+            Ok(())
+        } else {
+            Err(Error::OsError(libc::EBUSY))
+        }
+    }
+}
+
+extern "C" {
+    fn rte_cryptodev_count() -> libc::uint8_t;
+
+    fn rte_cryptodev_get_dev_id(name: *const libc::c_char) -> libc::c_int;
+
+    fn rte_cryptodev_configure(dev_id: libc::uint8_t, config: *mut RawCryptoConf) -> libc::c_int;
+
+    fn rte_cryptodev_queue_pair_setup(dev_id: libc::uint8_t,
+                                      queue_pair_id: libc::uint16_t,
+                                      qp_conf: *const RawCryptoQueuePairConf,
+                                      socket_id: libc::c_int)
+                                      -> libc::c_int;
+
+    fn rte_cryptodev_queue_pair_count(dev_id: libc::uint8_t) -> libc::uint16_t;
+
+    fn rte_cryptodev_socket_id(dev_id: libc::uint8_t) -> libc::c_int;
+
+    fn rte_cryptodev_start(dev_id: libc::uint8_t) -> libc::c_int;
+
+    fn rte_cryptodev_stop(dev_id: libc::uint8_t);
+
+    fn rte_cryptodev_close(dev_id: libc::uint8_t) -> libc::c_int;
+
+    fn rte_cryptodev_sym_session_create(dev_id: libc::uint8_t,
+                                        xform: *mut RawCryptoSymXform)
+                                        -> RawCryptoSessionPtr;
+
+    fn rte_cryptodev_sym_session_free(dev_id: libc::uint8_t,
+                                      session: RawCryptoSessionPtr)
+                                      -> RawCryptoSessionPtr;
+
+    fn rte_crypto_op_pool_create(name: *const libc::c_char,
+                                 op_type: libc::c_uint,
+                                 nb_elts: libc::c_uint,
+                                 cache_size: libc::c_uint,
+                                 priv_size: libc::uint16_t,
+                                 socket_id: libc::c_int)
+                                 -> mempool::RawMemoryPoolPtr;
+
+    fn _rte_crypto_op_alloc(mp: mempool::RawMemoryPoolPtr,
+                            op_type: libc::c_uint,
+                            sessionless: libc::c_int)
+                            -> RawCryptoOpPtr;
+
+    fn _rte_crypto_op_bulk_alloc(mp: mempool::RawMemoryPoolPtr,
+                                 op_type: libc::c_uint,
+                                 sessionless: libc::c_int,
+                                 ops: *mut RawCryptoOpPtr,
+                                 nb_ops: libc::uint16_t)
+                                 -> libc::c_uint;
+
+    fn _rte_crypto_op_free(op: RawCryptoOpPtr);
+
+    fn _rte_cryptodev_enqueue_burst(dev_id: libc::uint8_t,
+                                    qp_id: libc::uint16_t,
+                                    ops: *mut RawCryptoOpPtr,
+                                    nb_ops: libc::uint16_t)
+                                    -> libc::uint16_t;
+
+    fn _rte_cryptodev_dequeue_burst(dev_id: libc::uint8_t,
+                                    qp_id: libc::uint16_t,
+                                    ops: *mut RawCryptoOpPtr,
+                                    nb_ops: libc::uint16_t)
+                                    -> libc::uint16_t;
+}
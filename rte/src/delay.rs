@@ -0,0 +1,24 @@
+use cpu;
+use cycles;
+
+/// Wait at least n milliseconds.
+#[inline]
+pub fn ms(n: u32) {
+    cycles::delay_ms(n)
+}
+
+/// Wait at least n microseconds.
+#[inline]
+pub fn us(n: u32) {
+    cycles::delay_us(n)
+}
+
+/// Spin for at least n cycles of the TSC counter.
+#[inline]
+pub fn tsc(n: u64) {
+    let start = cycles::rdtsc();
+
+    while cycles::rdtsc().wrapping_sub(start) < n {
+        cpu::pause();
+    }
+}
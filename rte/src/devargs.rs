@@ -1,11 +1,13 @@
 use std::mem;
+use std::str::FromStr;
 use std::os::unix::io::AsRawFd;
 
 use cfile;
 
 use ffi;
 
-use errors::Result;
+use errors::{Error, Result};
+use ethdev::{self, PortId};
 
 /// Type of generic device
 #[repr(u32)]
@@ -40,3 +42,156 @@ pub fn dump<S: AsRawFd>(s: &S) {
         }
     }
 }
+
+/// The arguments of an Ethernet device, e.g. `0000:03:00.0,representor=[0-2]`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EthDevArgs {
+    /// The device name, e.g. the PCI address.
+    pub device: String,
+    /// The representor ids requested with the `representor` argument.
+    pub representors: Vec<u16>,
+    /// The other `key[=value]` arguments.
+    pub args: Vec<(String, Option<String>)>,
+}
+
+impl EthDevArgs {
+    /// The ports of the device and its representors which have been probed.
+    ///
+    /// The representors are matched by their port name ending with `representor_<id>`.
+    ///
+    pub fn ports(&self) -> Vec<PortId> {
+        let names = ethdev::devices()
+            .filter_map(|port_id| ethdev::port_name(port_id).ok().map(|name| (port_id, name)))
+            .collect::<Vec<(PortId, String)>>();
+
+        let parent = names.iter().filter(|&&(_, ref name)| *name == self.device);
+        let representors = self.representors.iter().filter_map(|id| {
+            let suffix = format!("representor_{}", id);
+
+            names.iter()
+                 .find(|&&(_, ref name)| name.contains(&self.device) && name.ends_with(&suffix))
+        });
+
+        parent.chain(representors).map(|&(port_id, _)| port_id).collect()
+    }
+}
+
+// Split the arguments on the commas outside of the brackets.
+fn split_args(s: &str) -> Result<Vec<&str>> {
+    let mut args = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+
+    for (i, c) in s.char_indices() {
+        match c {
+            '[' => depth += 1,
+            ']' if depth > 0 => depth -= 1,
+            ']' => return Err(Error::OsError(::libc::EINVAL)),
+            ',' if depth == 0 => {
+                args.push(&s[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+
+    if depth != 0 {
+        return Err(Error::OsError(::libc::EINVAL));
+    }
+
+    args.push(&s[start..]);
+
+    Ok(args)
+}
+
+// Parse the representor ids, e.g. `3`, `[0-2]` or `[0,2-4]`.
+fn parse_representors(s: &str) -> Result<Vec<u16>> {
+    let s = if s.starts_with('[') && s.ends_with(']') {
+        &s[1..s.len() - 1]
+    } else {
+        s
+    };
+
+    let mut ids = Vec::new();
+
+    for item in s.split(',').map(str::trim) {
+        let mut bounds = item.splitn(2, '-').map(|n| {
+            n.trim().parse::<u16>().map_err(|_| Error::OsError(::libc::EINVAL))
+        });
+
+        let first = try!(bounds.next().unwrap());
+        let last = match bounds.next() {
+            Some(last) => try!(last),
+            None => first,
+        };
+
+        if first > last {
+            return Err(Error::OsError(::libc::EINVAL));
+        }
+
+        ids.extend((first as u32..last as u32 + 1).map(|id| id as u16));
+    }
+
+    Ok(ids)
+}
+
+impl FromStr for EthDevArgs {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let args = try!(split_args(s.trim()));
+        let mut devargs = EthDevArgs { device: String::from(args[0]), ..Default::default() };
+
+        if devargs.device.is_empty() {
+            return Err(Error::OsError(::libc::EINVAL));
+        }
+
+        for arg in &args[1..] {
+            let mut kv = arg.splitn(2, '=');
+            let key = kv.next().unwrap();
+            let value = kv.next();
+
+            match (key, value) {
+                ("representor", Some(value)) => {
+                    devargs.representors.extend(try!(parse_representors(value)))
+                }
+                ("representor", None) | ("", _) => return Err(Error::OsError(::libc::EINVAL)),
+                (key, value) => devargs.args.push((String::from(key), value.map(String::from))),
+            }
+        }
+
+        Ok(devargs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn test_eth_devargs() {
+        let args = EthDevArgs::from_str("0000:03:00.0,representor=[0-2]").unwrap();
+
+        assert_eq!(args.device, "0000:03:00.0");
+        assert_eq!(args.representors, vec![0, 1, 2]);
+        assert!(args.args.is_empty());
+
+        let args = EthDevArgs::from_str("0000:03:00.0,txq_inline=128,representor=[0,4-5],dv")
+            .unwrap();
+
+        assert_eq!(args.representors, vec![0, 4, 5]);
+        assert_eq!(args.args,
+                   vec![(String::from("txq_inline"), Some(String::from("128"))),
+                        (String::from("dv"), None)]);
+
+        assert_eq!(EthDevArgs::from_str("net_null0,representor=3").unwrap().representors,
+                   vec![3]);
+
+        assert!(EthDevArgs::from_str("").is_err());
+        assert!(EthDevArgs::from_str("0000:03:00.0,representor=[2-0]").is_err());
+        assert!(EthDevArgs::from_str("0000:03:00.0,representor=[0-2").is_err());
+        assert!(EthDevArgs::from_str("0000:03:00.0,representor").is_err());
+    }
+}
@@ -0,0 +1,137 @@
+use std::ptr;
+use std::os::raw::c_char;
+
+use libc;
+
+use errors::Result;
+use mbuf::RawMbufPtr;
+use memory::SocketId;
+
+/// The number of packets exchanged with a worker at once by the burst distributor.
+pub const RTE_DIST_BURST_SIZE: usize = 8;
+
+/// The algorithm of the distributor.
+#[repr(u32)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DistributorType {
+    /// The burst distributor, which exchanges up to `RTE_DIST_BURST_SIZE` packets with a worker.
+    Burst = 0, // RTE_DIST_ALG_BURST
+    /// The legacy distributor, which exchanges a single packet with a worker.
+    Single = 1, // RTE_DIST_ALG_SINGLE
+}
+
+pub enum RawDistributor {}
+
+pub type RawDistributorPtr = *mut RawDistributor;
+
+/// Packet distributor, bound to the burst API of DPDK 17.05
+///
+/// The distributor dispatches the packets to the worker lcores,
+/// the packets with the same flow tag (the RSS hash) are processed by the same worker.
+///
+pub struct Distributor(RawDistributorPtr);
+
+unsafe impl Send for Distributor {}
+unsafe impl Sync for Distributor {}
+
+impl Distributor {
+    /// Create a distributor with the workers, which can't be freed.
+    pub fn create(name: &str,
+                  socket_id: SocketId,
+                  num_workers: u32,
+                  type_: DistributorType)
+                  -> Result<Distributor> {
+        let p = unsafe {
+            rte_distributor_create(try!(to_cptr!(name)),
+                                   socket_id as libc::c_uint,
+                                   num_workers,
+                                   type_ as libc::c_uint)
+        };
+
+        rte_check!(p, NonNull; ok => { Distributor(p) })
+    }
+
+    /// Extract the raw pointer from an underlying object.
+    pub fn as_raw(&self) -> RawDistributorPtr {
+        self.0
+    }
+
+    /// Distribute the packets to the workers, returns the number of distributed packets.
+    ///
+    /// Called by the distributor lcore only, with an empty slice to just handle the returns.
+    ///
+    pub fn process(&self, in_mbufs: &mut [RawMbufPtr]) -> usize {
+        unsafe {
+            rte_distributor_process(self.0, in_mbufs.as_mut_ptr(), in_mbufs.len() as u32) as usize
+        }
+    }
+
+    /// Retrieve the packets returned by the workers, returns the number of packets.
+    pub fn returned_pkts(&self, mbufs: &mut [RawMbufPtr]) -> usize {
+        unsafe {
+            rte_distributor_returned_pkts(self.0, mbufs.as_mut_ptr(), mbufs.len() as u32) as usize
+        }
+    }
+
+    /// Flush the in-flight and backlog packets to the workers, returns the number of packets.
+    pub fn flush(&self) -> i32 {
+        unsafe { rte_distributor_flush(self.0) }
+    }
+
+    /// Clear the array of the returned packets.
+    pub fn clear_returns(&self) {
+        unsafe { rte_distributor_clear_returns(self.0) }
+    }
+
+    /// Get the next packets to process by the worker, returns the number of packets.
+    ///
+    /// The buffer must have room for `RTE_DIST_BURST_SIZE` packets.
+    ///
+    pub fn get_pkt_worker(&self, worker_id: u32, buf: *mut RawMbufPtr) -> i32 {
+        unsafe { rte_distributor_get_pkt(self.0, worker_id, buf, ptr::null_mut(), 0) }
+    }
+
+    /// Return a processed packet to the distributor, e.g. before the worker exits.
+    pub fn return_pkt_worker(&self, worker_id: u32, oldpkt: RawMbufPtr) {
+        let mut oldpkt = oldpkt;
+
+        unsafe {
+            rte_distributor_return_pkt(self.0, worker_id, &mut oldpkt, 1);
+        }
+    }
+}
+
+extern "C" {
+    fn rte_distributor_create(name: *const c_char,
+                              socket_id: libc::c_uint,
+                              num_workers: libc::c_uint,
+                              alg_type: libc::c_uint)
+                              -> RawDistributorPtr;
+
+    fn rte_distributor_process(d: RawDistributorPtr,
+                               mbufs: *mut RawMbufPtr,
+                               num_mbufs: libc::c_uint)
+                               -> libc::c_int;
+
+    fn rte_distributor_returned_pkts(d: RawDistributorPtr,
+                                     mbufs: *mut RawMbufPtr,
+                                     max_mbufs: libc::c_uint)
+                                     -> libc::c_int;
+
+    fn rte_distributor_flush(d: RawDistributorPtr) -> libc::c_int;
+
+    fn rte_distributor_clear_returns(d: RawDistributorPtr);
+
+    fn rte_distributor_get_pkt(d: RawDistributorPtr,
+                               worker_id: libc::c_uint,
+                               pkts: *mut RawMbufPtr,
+                               oldpkt: *mut RawMbufPtr,
+                               retcount: libc::c_uint)
+                               -> libc::c_int;
+
+    fn rte_distributor_return_pkt(d: RawDistributorPtr,
+                                  worker_id: libc::c_uint,
+                                  oldpkt: *mut RawMbufPtr,
+                                  num: libc::c_int)
+                                  -> libc::c_int;
+}
@@ -1,11 +1,15 @@
 use std::mem;
 use std::ptr;
 use std::ffi::CStr;
+use std::marker::PhantomData;
 use std::os::raw::c_char;
+use std::sync::atomic::{AtomicBool, ATOMIC_BOOL_INIT, Ordering};
+
+use libc;
 
 use ffi;
 
-use errors::Result;
+use errors::{Error, Result};
 
 pub use common::*;
 pub use config::*;
@@ -15,6 +19,9 @@ pub use launch::*;
 extern "C" {
     fn _rte_version() -> *const c_char;
 
+    #[cfg(dpdk_ver_18_02)]
+    fn rte_eal_cleanup() -> libc::c_int;
+
     fn devinitfn_pmd_af_packet_drv();
     fn devinitfn_bond_drv();
     fn devinitfn_rte_cxgbe_driver();
@@ -63,6 +70,9 @@ unsafe fn init_pmd_drivers() {
     devinitfn_rte_vmxnet3_driver();
 }
 
+static EAL_INITIALIZED: AtomicBool = ATOMIC_BOOL_INIT;
+static EAL_READY: AtomicBool = ATOMIC_BOOL_INIT;
+
 pub fn version<'a>() -> &'a str {
     unsafe { CStr::from_ptr(_rte_version()).to_str().unwrap() }
 }
@@ -75,7 +85,31 @@ pub fn version<'a>() -> &'a str {
 /// The function finishes the initialization process before main() is called.
 /// It puts the SLAVE lcores in the WAIT state.
 ///
+/// EAL could only be initialized once in a process, the second call fails with `EALREADY`.
+///
 pub fn init(args: &Vec<String>) -> Result<i32> {
+    if EAL_INITIALIZED.compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst).is_err() {
+        return Err(Error::OsError(libc::EALREADY));
+    }
+
+    let res = init_eal(args);
+
+    // EAL could be initialized again if the arguments are wrong
+    if res.is_ok() {
+        EAL_READY.store(true, Ordering::SeqCst);
+    } else {
+        EAL_INITIALIZED.store(false, Ordering::SeqCst);
+    }
+
+    res
+}
+
+/// Whether EAL has been initialized successfully by `init` or `Eal::init`.
+pub fn is_initialized() -> bool {
+    EAL_READY.load(Ordering::SeqCst)
+}
+
+fn init_eal(args: &Vec<String>) -> Result<i32> {
     debug!("initial EAL with {} args: {:?}",
            args.len(),
            args.as_slice());
@@ -142,3 +176,67 @@ pub fn has_hugepages() -> bool {
 pub fn socket_id() -> i32 {
     unsafe { ffi::rte_socket_id() as i32 }
 }
+
+/// The Environment Abstraction Layer of the process.
+///
+/// EAL is cleaned up when the guard is dropped, which requires DPDK 18.02 or later.
+///
+/// EAL is initialized once per process, so the guard is neither `Send` nor `Sync`.
+///
+pub struct Eal {
+    parsed: i32,
+    process_type: ProcessType,
+    _marker: PhantomData<*const ()>,
+}
+
+#[cfg(dpdk_ver_18_02)]
+impl Drop for Eal {
+    fn drop(&mut self) {
+        let ret = unsafe { rte_eal_cleanup() };
+
+        if ret < 0 {
+            warn!("fail to cleanup EAL, error {}", ret);
+        }
+    }
+}
+
+impl Eal {
+    /// Initialize the Environment Abstraction Layer (EAL) and return the guard.
+    ///
+    /// Only one guard could exist in a process, the second call fails with `EALREADY`.
+    ///
+    pub fn init(args: &[&str]) -> Result<Eal> {
+        let args: Vec<String> = args.iter().map(|s| String::from(*s)).collect();
+
+        init(&args).map(|parsed| {
+            Eal {
+                parsed: parsed,
+                process_type: process_type(),
+                _marker: PhantomData,
+            }
+        })
+    }
+
+    /// The number of arguments processed by EAL.
+    pub fn parsed_args(&self) -> i32 {
+        self.parsed
+    }
+
+    /// The process type detected when EAL is initialized.
+    ///
+    /// Some operations, e.g. creating the mempools, are only allowed in the primary process.
+    ///
+    pub fn process_type(&self) -> ProcessType {
+        self.process_type
+    }
+
+    /// The version of DPDK.
+    pub fn version() -> &'static str {
+        version()
+    }
+
+    /// Whether the process is the primary process.
+    pub fn is_primary_proc() -> bool {
+        process_type() == ProcType::Primary
+    }
+}
@@ -2,6 +2,7 @@ use std::io;
 use std::fmt;
 use std::error;
 use std::result;
+use std::time::Duration;
 use std::ffi;
 
 use errno::errno;
@@ -14,10 +15,10 @@ extern "C" {
 
 macro_rules! rte_check {
     ( $ret:expr ) => (
-        rte_check!($ret; ok => {()}; err => {$crate::errors::Error::RteError($ret)})
+        rte_check!($ret; ok => {()}; err => {$crate::errors::Error::from_ret($ret)})
     );
     ( $ret:expr; ok => $ok:block) => (
-        rte_check!($ret; ok => $ok; err => {$crate::errors::Error::RteError($ret)})
+        rte_check!($ret; ok => $ok; err => {$crate::errors::Error::from_ret($ret)})
     );
     ( $ret:expr; err => $err:block) => (
         rte_check!($ret; ok => {()}; err => $err)
@@ -54,8 +55,29 @@ pub enum Error {
     OsError(i32),
     IoError(io::Error),
     NulError(ffi::NulError),
+    /// The loopback mode is not supported by the driver.
+    UnsupportedLoopbackMode(String, u32),
+    /// The length of the RSS key doesn't match the expected length of the driver.
+    InvalidRssKeyLen(usize, usize),
+    /// DPDK specific error code, which is not a POSIX errno.
+    DpdkError(i32),
+    /// The port id doesn't refer to a valid Ethernet device.
+    InvalidPortId(u8),
+    /// The operation didn't complete in time, with the elapsed time.
+    Timeout(String, Duration),
+    /// The MTU is out of the range supported by the device.
+    InvalidMtu {
+        requested: u16,
+        min: u16,
+        max: u16,
+    },
+    /// The pointer is null.
+    NullPtr,
 }
 
+/// The DPDK specific error codes start after the POSIX errno.
+const RTE_MIN_ERRNO: i32 = 1000;
+
 impl Error {
     pub fn rte_error() -> Error {
         Error::RteError(unsafe { _rte_errno() })
@@ -64,6 +86,19 @@ impl Error {
     pub fn os_error() -> Error {
         Error::OsError(errno().0 as i32)
     }
+
+    /// The error of a negative return code of the DPDK functions.
+    pub fn from_ret(ret: i32) -> Error {
+        if ret < 0 && -ret < RTE_MIN_ERRNO {
+            Error::RteError(ret)
+        } else {
+            Error::DpdkError(ret)
+        }
+    }
+
+    pub fn invalid_port_id(port_id: u8) -> Error {
+        Error::InvalidPortId(port_id)
+    }
 }
 
 impl fmt::Display for Error {
@@ -76,6 +111,29 @@ impl fmt::Display for Error {
             }
             &Error::OsError(ref errno) => write!(f, "OS error, {}", errno),
             &Error::IoError(ref err) => write!(f, "IO error, {}", err),
+            &Error::UnsupportedLoopbackMode(ref driver, mode) => {
+                write!(f, "loopback mode {} is not supported by driver {}", mode, driver)
+            }
+            &Error::InvalidRssKeyLen(expected, actual) => {
+                write!(f, "RSS key of {} bytes, expected {} bytes", actual, expected)
+            }
+            &Error::DpdkError(code) => {
+                write!(f,
+                       "DPDK error {}, {}",
+                       code,
+                       unsafe { ffi::CStr::from_ptr(rte_strerror(code.abs())).to_string_lossy() })
+            }
+            &Error::InvalidPortId(port_id) => write!(f, "invalid port id {}", port_id),
+            &Error::Timeout(ref what, elapsed) => {
+                write!(f,
+                       "{} timed out after {}.{:03}s",
+                       what,
+                       elapsed.as_secs(),
+                       elapsed.subsec_nanos() / 1_000_000)
+            }
+            &Error::InvalidMtu { requested, min, max } => {
+                write!(f, "MTU {} is out of the supported range {} to {}", requested, min, max)
+            }
             _ => write!(f, "{}", error::Error::description(self)),
         }
     }
@@ -88,6 +146,13 @@ impl error::Error for Error {
             &Error::OsError(_) => "OS error",
             &Error::IoError(ref err) => error::Error::description(err),
             &Error::NulError(ref err) => error::Error::description(err),
+            &Error::UnsupportedLoopbackMode(..) => "unsupported loopback mode",
+            &Error::InvalidRssKeyLen(..) => "invalid RSS key length",
+            &Error::DpdkError(_) => "DPDK error",
+            &Error::InvalidPortId(_) => "invalid port id",
+            &Error::Timeout(..) => "timed out",
+            &Error::InvalidMtu { .. } => "invalid MTU",
+            &Error::NullPtr => "null pointer",
         }
     }
 }
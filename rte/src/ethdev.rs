@@ -1,8 +1,59 @@
+//! Ethernet devices and their RX/TX queues.
+//!
+//! # Interrupt mode receive
+//!
+//! A port configured with `intr_conf.rxq` set can wake up the polling lcore when
+//! its RX queues are empty, instead of busy polling them.
+//!
+//! ```no_run
+//! # extern crate rte;
+//! # use std::ptr;
+//! # use rte::*;
+//! # use rte::ethdev::{EthDevice, IntrCtlOp};
+//! # fn main() {
+//! # let port = PortId::new(0).unwrap();
+//! # let mut pkts: [mbuf::RawMbufPtr; 32] = [ptr::null_mut(); 32];
+//! let queue_id = 0;
+//!
+//! port.rx_intr_ctl(queue_id, RTE_EPOLL_PER_THREAD, IntrCtlOp::Add, queue_id as u64).unwrap();
+//!
+//! loop {
+//!     let n = port.rx_burst(queue_id, &mut pkts);
+//!
+//!     if n > 0 {
+//!         // process the received packets
+//!         continue;
+//!     }
+//!
+//!     // the queue is empty, sleep until the next packet arrives
+//!     port.rx_queue_intr_enable(queue_id).unwrap();
+//!
+//!     let mut event: raw::Struct_rte_epoll_event = unsafe { std::mem::zeroed() };
+//!
+//!     unsafe { raw::rte_epoll_wait(RTE_EPOLL_PER_THREAD, &mut event, 1, -1) };
+//!
+//!     port.rx_queue_intr_disable(queue_id).unwrap();
+//! }
+//! # }
+//! ```
+
+use std::fmt;
+use std::cmp;
 use std::ptr;
+use std::slice;
 use std::mem;
-use std::ops::Range;
+use std::iter::Map;
+use std::ops::{Add, Deref, DerefMut, Range, Sub};
 use std::ffi::CStr;
-use std::os::raw::c_void;
+use std::net::Ipv4Addr;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicUsize, ATOMIC_USIZE_INIT, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+use std::collections::{HashMap, VecDeque};
+use std::os::raw::{c_char, c_void};
+use std::os::unix::io::RawFd;
 
 use libc;
 
@@ -13,13 +64,88 @@ use memory::{SocketId, AsMutRef};
 use mempool;
 use malloc;
 use mbuf;
+use delay;
 use ether;
 use pci;
+use thash;
+
+/// The port identifier of an Ethernet device.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PortId(u8);
+
+impl PortId {
+    /// Create a port identifier, checking that it refers to a valid Ethernet device.
+    pub fn new(id: u8) -> Result<PortId> {
+        if unsafe { ffi::rte_eth_dev_is_valid_port(id) } != 0 {
+            Ok(PortId(id))
+        } else {
+            Err(Error::invalid_port_id(id))
+        }
+    }
+
+    /// Create a port identifier without checking that the device exists.
+    pub fn new_unchecked(id: u8) -> PortId {
+        PortId(id)
+    }
+
+    #[cfg(dpdk_ver_17_05)]
+    fn mtu_range(&self) -> Result<(u16, u16)> {
+        let (mut min_mtu, mut max_mtu) = (0, 0);
+
+        let ret = unsafe { _rte_eth_dev_mtu_range(self.0, &mut min_mtu, &mut max_mtu) };
+
+        rte_check!(ret; ok => { (min_mtu, max_mtu) })
+    }
+
+    // The devices don't report the MTU range before 17.05,
+    // so the maximum is the largest frame received without the Ethernet overhead.
+    #[cfg(not(dpdk_ver_17_05))]
+    fn mtu_range(&self) -> Result<(u16, u16)> {
+        if !self.is_valid() {
+            return Err(Error::invalid_port_id(self.0));
+        }
+
+        let max_rx_pktlen = self.info().max_rx_pktlen.saturating_sub(ETHER_OVERHEAD_LEN);
+
+        Ok((ffi::ETHER_MIN_MTU as u16, cmp::min(max_rx_pktlen, u16::max_value() as u32) as u16))
+    }
+
+    fn check_eeprom_range(&self, offset: u32, length: u32) -> Result<()> {
+        let eeprom_len = try!(self.eeprom_length());
+
+        match offset.checked_add(length) {
+            Some(end) if end <= eeprom_len => Ok(()),
+            _ => Err(Error::OsError(libc::EINVAL)),
+        }
+    }
+}
+
+impl fmt::Display for PortId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl From<PortId> for u8 {
+    fn from(port_id: PortId) -> u8 {
+        port_id.0
+    }
+}
 
-pub type PortId = u8;
 pub type QueueId = u16;
 
+/// The Ethernet header and CRC around the MTU.
+#[cfg(not(dpdk_ver_17_05))]
+const ETHER_OVERHEAD_LEN: u32 = 14 + ffi::ETHER_CRC_LEN;
+
+/// The port id type of the DPDK API, which is 16 bits since 17.11.
+#[cfg(dpdk_ver_17_11)]
+pub type RawPortId = libc::uint16_t;
+#[cfg(not(dpdk_ver_17_11))]
+pub type RawPortId = libc::uint8_t;
+
 /// A structure used to retrieve link-level information of an Ethernet port.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct EthLink {
     pub speed: u32,
     pub duplex: bool,
@@ -50,12 +176,44 @@ pub trait EthDevice {
     /// Reset the general I/O statistics of an Ethernet device.
     fn reset_stats(&self) -> &Self;
 
+    /// Retrieve the general I/O statistics and reset them right after.
+    ///
+    /// The counters updated by the NIC between the two calls are lost,
+    /// since the drivers don't provide an atomic fetch and reset.
+    ///
+    fn stats_get_and_reset(&self) -> Result<RawEthDeviceStats> {
+        let stats = try!(self.stats());
+
+        self.reset_stats();
+
+        Ok(stats)
+    }
+
+    /// Retrieve the extended statistics of an Ethernet device.
+    fn xstats(&self) -> Result<Vec<EthXStat>>;
+
+    /// Reset the extended statistics of an Ethernet device.
+    fn reset_xstats(&self) -> &Self;
+
     /// Retrieve the Ethernet address of an Ethernet device.
     fn mac_addr(&self) -> ether::EtherAddr;
 
+    /// Retrieve all the Ethernet addresses programmed to an Ethernet device.
+    ///
+    /// The first address is the default one, the unused slots are skipped.
+    ///
+    fn mac_addrs(&self) -> Result<Vec<ether::EtherAddr>>;
+
     /// Set the default MAC address.
     fn set_mac_addr(&self, addr: &[u8; ether::ETHER_ADDR_LEN]) -> Result<&Self>;
 
+    /// Set the list of multicast addresses to filter on an Ethernet device,
+    /// an empty list removes all the multicast addresses.
+    ///
+    /// Fails with `EINVAL` if any address is not a multicast one.
+    ///
+    fn set_mc_addr_list(&self, addrs: &[ether::EtherAddr]) -> Result<&Self>;
+
     /// Return the NUMA socket to which an Ethernet device is connected
     fn socket_id(&self) -> SocketId;
 
@@ -95,8 +253,24 @@ pub trait EthDevice {
     fn mtu(&self) -> Result<u16>;
 
     /// Change the MTU of an Ethernet device.
+    #[deprecated(note = "use `set_mtu_checked` to validate the MTU with the device")]
     fn set_mtu(&self, mtu: u16) -> Result<&Self>;
 
+    /// The minimum MTU supported by the device.
+    fn min_mtu(&self) -> Result<u16>;
+
+    /// The maximum MTU supported by the device.
+    ///
+    /// Before DPDK 17.05 it is derived from the maximum RX packet length of the device.
+    ///
+    fn max_mtu(&self) -> Result<u16>;
+
+    /// Change the MTU of an Ethernet device after checking it with the range of the device.
+    ///
+    /// Fails with `Error::InvalidMtu` if the MTU is out of the range.
+    ///
+    fn set_mtu_checked(&self, mtu: u16) -> Result<&Self>;
+
     /// Enable/Disable hardware filtering by an Ethernet device
     /// of received VLAN packets tagged with a given VLAN Tag Identifier.
     fn set_vlan_filter(&self, vlan_id: u16, on: bool) -> Result<&Self>;
@@ -107,6 +281,15 @@ pub trait EthDevice {
         self.link().up
     }
 
+    /// Retrieve the speeds supported by the device, from the slowest to the fastest.
+    fn get_supported_speeds(&self) -> Result<Vec<LinkSpeed>> {
+        if !self.is_valid() {
+            return Err(Error::invalid_port_id(self.portid().into()));
+        }
+
+        Ok(self.info().supported_speeds())
+    }
+
     /// Retrieve the status (ON/OFF), the speed (in Mbps) and
     /// the mode (HALF-DUPLEX or FULL-DUPLEX) of the physical link of an Ethernet device.
     ///
@@ -151,6 +334,126 @@ pub trait EthDevice {
     /// Close a stopped Ethernet device. The device cannot be restarted!
     fn close(&self) -> &Self;
 
+    /// Configure the queues of an Ethernet device, enable the promiscuous mode and start it.
+    ///
+    /// All the RX queues use the same mempool. It waits up to `link_timeout`
+    /// for the link to be up, and fails with `Error::Timeout` if it is still down.
+    /// The device is stopped and closed on any failure.
+    ///
+    fn configure_and_start(&self,
+                           nb_rx: QueueId,
+                           nb_tx: QueueId,
+                           conf: &EthConf,
+                           rxq_conf: Option<ffi::Struct_rte_eth_rxconf>,
+                           txq_conf: Option<ffi::Struct_rte_eth_txconf>,
+                           pool: &mut mempool::RawMemoryPool,
+                           nb_rx_desc: u16,
+                           nb_tx_desc: u16,
+                           link_timeout: Duration)
+                           -> Result<&Self> {
+        let res = self.configure(nb_rx, nb_tx, conf)
+            .and_then(|_| {
+                for queue_id in 0..nb_rx {
+                    try!(self.rx_queue_setup(queue_id, nb_rx_desc, rxq_conf, pool));
+                }
+
+                for queue_id in 0..nb_tx {
+                    try!(self.tx_queue_setup(queue_id, nb_tx_desc, txq_conf));
+                }
+
+                try!(self.promiscuous_enable().start());
+
+                self.wait_link_up(link_timeout)
+            });
+
+        if let Err(err) = res {
+            self.stop().close();
+
+            return Err(err);
+        }
+
+        Ok(self)
+    }
+
+    /// Wait for the link to be up, polling its status with an exponential backoff
+    /// from 100ms up to 1s intervals.
+    ///
+    /// Fails with `Error::Timeout` if the link is still down after the timeout.
+    ///
+    fn wait_link_up(&self, timeout: Duration) -> Result<EthLink> {
+        let start = Instant::now();
+        let mut interval = 100;
+
+        loop {
+            let link = self.link_nowait();
+
+            if link.up {
+                return Ok(link);
+            }
+
+            let elapsed = start.elapsed();
+
+            if elapsed >= timeout {
+                return Err(Error::Timeout(format!("link up of port {}", self.portid()), elapsed));
+            }
+
+            let remaining = timeout - elapsed;
+            let remaining_ms = remaining.as_secs() * 1000 +
+                               (remaining.subsec_nanos() / 1_000_000) as u64;
+
+            delay::ms(cmp::max(cmp::min(interval, remaining_ms), 1) as u32);
+
+            interval = cmp::min(interval * 2, 1000);
+        }
+    }
+
+    /// Receive and free the residual packets of all the RX queues, returns the drained count.
+    ///
+    /// Each queue is polled until it is empty, at most 1000 times in case the hardware
+    /// keeps delivering. The device must be started, the mbufs are freed to their own mempool.
+    ///
+    fn drain_rx_all_queues(&self) -> usize {
+        let mut pkts: [mbuf::RawMbufPtr; DRAIN_BURST_SIZE] = [ptr::null_mut(); DRAIN_BURST_SIZE];
+        let mut drained = 0;
+
+        for queue_id in 0..self.nb_rx_queues().unwrap_or(0) {
+            for _ in 0..DRAIN_MAX_BURSTS {
+                let n = self.rx_burst(queue_id, &mut pkts);
+
+                if n == 0 {
+                    break;
+                }
+
+                for m in &pkts[..n] {
+                    if let Some(m) = unsafe { m.as_mut() } {
+                        mbuf::PktMbuf::free(m);
+                    }
+                }
+
+                drained += n;
+            }
+        }
+
+        drained
+    }
+
+    /// Drain the RX queues with `drain_rx_all_queues()` while the device is still started,
+    /// then stop it, returns the drained count.
+    fn stop_and_drain(&self) -> usize {
+        let drained = self.drain_rx_all_queues();
+
+        self.stop();
+
+        drained
+    }
+
+    /// Reset a stopped Ethernet device to its initial state.
+    ///
+    /// The device must be reconfigured with `configure()` before it can be used again.
+    ///
+    #[cfg(dpdk_ver_18_02)]
+    fn reset(&self) -> Result<&Self>;
+
     /// Retrieve a burst of input packets from a receive queue of an Ethernet device.
     fn rx_burst(&self, queue_id: QueueId, rx_pkts: &mut [mbuf::RawMbufPtr]) -> usize;
 
@@ -160,9 +463,104 @@ pub trait EthDevice {
     fn rx_burst_ex(&self, queue_id: QueueId, rx_pkts: &mut [mbuf::RawMbufPtr], packets: u16) -> u16;
     fn tx_burst_ex(&self, queue_id: QueueId, rx_pkts: &mut [mbuf::RawMbufPtr], packets: u16) -> u16;
 
+    /// Prepare a burst of output packets for the offloads of the transmit queue,
+    /// e.g. computing the pseudo header checksum of TSO.
+    ///
+    /// Returns the number of the packets prepared from the start of the slice,
+    /// the next packet has an invalid offload request and `rte_errno` is set.
+    /// Only the prepared packets should be sent, the others must be freed by the caller.
+    ///
+    #[cfg(dpdk_ver_17_02)]
+    fn tx_prepare(&self, queue_id: QueueId, pkts: &mut [mbuf::RawMbufPtr]) -> u16;
+
     /// Set RX L2 Filtering mode of a VF of an Ethernet device.
     fn set_vf_rxmode(&self, vf: u16, rx_mode: EthVmdqRxMode, on: bool) -> Result<&Self>;
 
+    /// Set RX L2 Filtering mode of a VMDQ pool of an Ethernet device.
+    ///
+    /// The same as `set_vf_rxmode`, for the VMDQ configurations without SR-IOV.
+    ///
+    fn set_pool_rx_mode(&self, pool: u8, mode: EthVmdqRxMode, enable: bool) -> Result<&Self>;
+
+    /// Enable or disable the drop of the packets on all the RX queues without free descriptor.
+    ///
+    /// A VMDQ pool with drop enabled drops its packets when its queues have no free descriptor,
+    /// instead of blocking the other pools. The RX queues are set up again with `rx_drop_en`
+    /// changed in their configuration, so the device must be stopped.
+    ///
+    /// The packets which don't match any pool are received by the default pool
+    /// or dropped, depending on `enable_default_pool` of the VMDQ configuration.
+    ///
+    fn set_split_drop_en(&self, enable: bool) -> Result<&Self>;
+
+    /// Whether all the configured RX queues drop the packets without free descriptor.
+    fn get_split_drop_en(&self) -> Result<bool>;
+
+    /// Set the TX rate limit of the VF in Mbps, for the TX queues in the mask.
+    fn set_vf_rate_limit(&self, vf: u16, tx_rate: u32, q_msk: u64) -> Result<&Self>;
+
+    /// The TX rate limit and queue mask of the VF, set by `set_vf_rate_limit`.
+    ///
+    /// DPDK can't read back the rate limits, so it only returns the values
+    /// set by this process, not the ones set by another process or the PF driver.
+    ///
+    fn get_vf_rate_limit(&self, vf: u16) -> Option<(u32, u64)>;
+
+    /// Enable or disable the receive of the VLAN by the VF.
+    fn set_vf_rx_vlan(&self, vf: u16, vlan_id: u16, on: bool) -> Result<&Self>;
+
+    /// Set the MAC address of the VF from the PF. Only the ixgbe PMD supports it.
+    ///
+    /// Fails with `EINVAL` if the address is not a unicast address or filled with zeros.
+    ///
+    #[cfg(dpdk_ver_17_02)]
+    fn set_vf_mac_addr(&self, vf: u16, addr: &[u8; 6]) -> Result<&Self>;
+
+    /// Retrieve the statistics of the VF accounted by the PF. Only the i40e PMD supports it.
+    #[cfg(dpdk_ver_17_02)]
+    fn get_vf_stats(&self, vf: u16) -> Result<VfStats>;
+
+    /// Update the RSS hash key and functions of the started device.
+    ///
+    /// The key must have the length reported by the driver in `hash_key_size`,
+    /// and the current key is kept when it is `None`.
+    ///
+    fn set_rss_hash_conf(&self, key: Option<&[u8]>, hash_funcs: RssHashFunc) -> Result<&Self>;
+
+    /// Retrieve the RSS hash key and functions of the device.
+    ///
+    /// The key is `None` unless the driver uses a 40 bytes key.
+    ///
+    fn rss_hash_conf(&self) -> Result<EthRssConf>;
+
+    /// Check whether the device supports the mempool ops of the given name for its RX pools.
+    ///
+    /// The ops must have been registered, see `mempool::pool_ops_supported`.
+    ///
+    #[cfg(dpdk_ver_18_02)]
+    fn pool_ops_supported(&self, pool_ops_name: &str) -> bool;
+
+    /// Take the ownership of the device, which must not be owned by another owner.
+    #[cfg(dpdk_ver_18_02)]
+    fn owner_set(&self, owner_id: u64, name: &str) -> Result<&Self>;
+
+    /// Release the ownership of the device, which fails if it is not owned by `owner_id`.
+    #[cfg(dpdk_ver_18_02)]
+    fn owner_unset(&self, owner_id: u64) -> Result<&Self>;
+
+    /// Retrieve the owner of the device, the owner id is 0 if it is not owned.
+    #[cfg(dpdk_ver_18_02)]
+    fn owner_get(&self) -> Result<EthDevOwner>;
+
+    /// Set the loopback mode of the device to the configuration.
+    ///
+    /// DPDK only applies the loopback mode through `rte_eth_conf` when the device
+    /// is configured, so the driver value of the mode is set to `conf.lpbk_mode`,
+    /// and it only takes effect when `conf` is passed to `configure()` afterwards.
+    /// The mode is checked against the driver name of the device.
+    ///
+    fn set_loopback_mode(&self, conf: &mut EthConf, mode: LoopbackMode) -> Result<&Self>;
+
     /// Enable or disable a VF traffic transmit of the Ethernet device.
     fn set_vf_tx(&self, vf: u16, on: bool) -> Result<&Self>;
 
@@ -170,10 +568,228 @@ pub trait EthDevice {
     fn set_vf_rx(&self, vf: u16, on: bool) -> Result<&Self>;
 
     /// Read VLAN Offload configuration from an Ethernet device
+    ///
+    /// Only the `ETH_VLAN_*_OFFLOAD` flags are returned.
+    ///
     fn vlan_offload(&self) -> Result<EthVlanOffloadMode>;
 
     /// Set VLAN offload configuration on an Ethernet device
+    ///
+    /// The offloads which are not in `mode` are disabled.
+    ///
     fn set_vlan_offload(&self, mode: EthVlanOffloadMode) -> Result<&Self>;
+
+    /// Enable or disable the VLAN offloads, keeping the other offloads unchanged.
+    fn update_vlan_offload(&self, mode: EthVlanOffloadMode, on: bool) -> Result<&Self> {
+        let current = try!(self.vlan_offload());
+
+        self.set_vlan_offload(if on { current | mode } else { current - mode })
+    }
+
+    /// Set the Ether type of the L2 tunnel.
+    ///
+    /// `rte_eth_dev_l2_tunnel_eth_type_conf` only supports the E-tag tunnel,
+    /// so the Ether type of the 802.1Q tunnel is set as the TPID of the outer VLAN tag.
+    ///
+    fn set_l2_tunnel_type(&self, tunnel_type: L2TunnelType, ether_type: u16) -> Result<&Self>;
+
+    /// Enable or disable the offloads of the L2 tunnel.
+    ///
+    /// `rte_eth_dev_l2_tunnel_offload_set` only supports the E-tag tunnel, so the 802.1Q
+    /// tunnel is enabled by the extended VLAN offload and stripped by the VLAN stripping
+    /// instead, its insertion and forwarding are not supported.
+    ///
+    fn set_l2_tunnel_offload(&self,
+                             tunnel_type: L2TunnelType,
+                             mask: L2TunnelOffloadMask,
+                             on: bool)
+                             -> Result<&Self>;
+
+    /// Set the Ether type of the L2 tunnel, the same as `set_l2_tunnel_type`.
+    fn l2_tunnel_eth_type_conf(&self, tunnel_type: L2TunnelType, ether_type: u16) -> Result<&Self> {
+        self.set_l2_tunnel_type(tunnel_type, ether_type)
+    }
+
+    /// Enable or disable the offloads of the L2 tunnel in the raw `ETH_L2_TUNNEL_*_MASK` bits,
+    /// the same as `set_l2_tunnel_offload`.
+    ///
+    /// Fails with `EINVAL` if the mask has an unknown bit.
+    ///
+    fn l2_tunnel_offload_set(&self,
+                             tunnel_type: L2TunnelType,
+                             mask: u32,
+                             enable: bool)
+                             -> Result<&Self> {
+        let mask = try!(L2TunnelOffloadMask::from_bits(mask).ok_or(Error::OsError(libc::EINVAL)));
+
+        self.set_l2_tunnel_offload(tunnel_type, mask, enable)
+    }
+
+    /// Set the Ether type (TPID) of the inner or outer VLAN tag.
+    fn set_vlan_ether_type(&self, vlan_type: VlanType, tpid: u16) -> Result<&Self>;
+
+    /// Strip the both tags of the 802.1ad (QinQ) packets on receive.
+    ///
+    /// The outer tag is recognized by the `0x88A8` Ether type,
+    /// then the QinQ tunnel is enabled with the stripping.
+    ///
+    fn enable_qinq_stripping(&self) -> Result<&Self> {
+        try!(self.l2_tunnel_eth_type_conf(L2TunnelType::Standard802_1Q, ether::EtherType::QinQ as u16));
+
+        self.l2_tunnel_offload_set(L2TunnelType::Standard802_1Q,
+                                   (ETH_L2_TUNNEL_ENABLE_MASK | ETH_L2_TUNNEL_STRIPPING_MASK).bits,
+                                   true)
+    }
+
+    /// Set the port VLAN ID, which is inserted into the untagged packets on transmit.
+    ///
+    /// With `ETH_VLAN_EXTEND_OFFLOAD` enabled, the PVID is inserted as the outer tag
+    /// of the tagged packets too, i.e. the packets are double tagged (QinQ).
+    ///
+    fn set_vlan_pvid(&self, vlan_id: u16, on: bool) -> Result<&Self>;
+
+    /// Enable or disable the VLAN stripping of a RX queue, if supported by the device.
+    fn set_vlan_strip_queue(&self, queue_id: QueueId, on: bool) -> Result<&Self>;
+
+    /// Retrieve the type and size of plugin module EEPROM
+    #[cfg(dpdk_ver_18_05)]
+    fn module_info(&self) -> Result<EthModuleInfo>;
+
+    /// Retrieve the data of plugin module EEPROM
+    ///
+    /// The layout of the returned bytes depends on the module type,
+    /// see SFF-8472 for SFP/SFP+ modules and SFF-8636 for QSFP modules.
+    ///
+    #[cfg(dpdk_ver_18_05)]
+    fn module_eeprom(&self, offset: u32, length: u32) -> Result<Vec<u8>>;
+
+    /// Retrieve the information of a RX queue, including the burst mode if supported.
+    fn rx_queue_info(&self, queue_id: QueueId) -> Result<RxQueueInfo>;
+
+    /// Retrieve the information of a TX queue, including the burst mode if supported.
+    fn tx_queue_info(&self, queue_id: QueueId) -> Result<TxQueueInfo>;
+
+    /// The number of the configured RX queues.
+    fn nb_rx_queues(&self) -> Result<QueueId>;
+
+    /// The number of the configured TX queues.
+    fn nb_tx_queues(&self) -> Result<QueueId>;
+
+    /// The numbers of the configured RX and TX queues.
+    ///
+    /// They are read from the device data, which is only updated by `configure()`.
+    ///
+    fn active_queue_counts(&self) -> Result<(QueueId, QueueId)> {
+        Ok((try!(self.nb_rx_queues()), try!(self.nb_tx_queues())))
+    }
+
+    /// Iterate the information of the configured RX queues.
+    ///
+    /// The iteration stops at the first queue which is not set up,
+    /// the other errors are returned and the iteration goes on.
+    /// Nothing is iterated for an invalid port.
+    ///
+    fn iter_rx_queues(&self) -> QueueInfoIter<RxQueueInfo>;
+
+    /// Iterate the information of the configured TX queues, like `iter_rx_queues`.
+    fn iter_tx_queues(&self) -> QueueInfoIter<TxQueueInfo>;
+
+    /// Retrieve the burst mode used by a RX queue, e.g. vector or scalar.
+    #[cfg(dpdk_ver_19_11)]
+    fn rx_burst_mode(&self, queue_id: QueueId) -> Result<BurstMode>;
+
+    /// Retrieve the burst mode used by a TX queue, e.g. vector or scalar.
+    #[cfg(dpdk_ver_19_11)]
+    fn tx_burst_mode(&self, queue_id: QueueId) -> Result<BurstMode>;
+
+    /// Request the driver to free the mbufs transmitted on a TX queue,
+    /// returns the number of packets freed.
+    ///
+    /// Up to `free_cnt` packets are freed, all of them if it is 0.
+    /// Fails with `ENOTSUP` if the driver does not support it.
+    ///
+    #[cfg(dpdk_ver_17_02)]
+    fn tx_done_cleanup(&self, queue_id: QueueId, free_cnt: u32) -> Result<u32>;
+
+    /// Retrieve the DCB information of an Ethernet device.
+    fn dcb_info(&self) -> Result<DcbInfo>;
+
+    /// Retrieve the number and width of the device registers
+    fn reg_info(&self) -> Result<EthRegInfo>;
+
+    /// Retrieve the device registers, the layout of the bytes is driver specific
+    fn regs(&self) -> Result<Vec<u8>>;
+
+    /// Retrieve the size of the device EEPROM
+    fn eeprom_length(&self) -> Result<u32>;
+
+    /// Retrieve the data of the device EEPROM
+    fn get_eeprom(&self, offset: u32, length: u32) -> Result<Vec<u8>>;
+
+    /// Program the data to the device EEPROM
+    fn set_eeprom(&self, offset: u32, data: &[u8]) -> Result<&Self>;
+
+    /// Enable the RX queue interrupt of an Ethernet device.
+    ///
+    /// The port must be configured with `intr_conf.rxq` set.
+    ///
+    fn rx_intr_enable(&self, queue_id: QueueId) -> Result<&Self>;
+
+    /// Disable the RX queue interrupt of an Ethernet device.
+    fn rx_intr_disable(&self, queue_id: QueueId) -> Result<&Self>;
+
+    /// Enable the RX queue interrupt, before waiting on the epoll instance of the queue.
+    ///
+    /// The same as `rx_intr_enable`, see the module documentation for the receive loop.
+    ///
+    fn rx_queue_intr_enable(&self, queue_id: QueueId) -> Result<&Self> {
+        self.rx_intr_enable(queue_id)
+    }
+
+    /// Disable the RX queue interrupt, after waking up from the epoll instance of the queue.
+    fn rx_queue_intr_disable(&self, queue_id: QueueId) -> Result<&Self> {
+        self.rx_intr_disable(queue_id)
+    }
+
+    /// Add or delete the RX queue interrupt event to/from the specified epoll instance.
+    ///
+    /// Use `RTE_EPOLL_PER_THREAD` as `epfd` to bind the event to the epoll instance
+    /// of the calling thread, `data` is passed back in the epoll event.
+    ///
+    fn rx_intr_ctl(&self, queue_id: QueueId, epfd: i32, op: IntrCtlOp, data: u64) -> Result<&Self>;
+
+    /// Retrieve the epoll instance of the calling thread.
+    ///
+    /// The RX queue interrupts added with `rx_intr_ctl(.., RTE_EPOLL_PER_THREAD, ..)`
+    /// wake up this file descriptor, so it could be registered with an event loop like mio.
+    ///
+    fn rx_intr_epoll_fd(&self) -> Result<RawFd>;
+
+    /// Retrieve the packet types which could be identified by the device,
+    /// limited to the layers of `ptype_mask`.
+    fn supported_ptypes(&self,
+                        ptype_mask: mbuf::PacketTypeFlags)
+                        -> Result<Vec<mbuf::PacketTypeFlags>>;
+
+    /// Inform the device about the packet types which the application is interested in.
+    ///
+    /// The device may skip parsing the other layers, an empty slice disables the parsing.
+    ///
+    #[cfg(dpdk_ver_19_11)]
+    fn set_ptypes(&self, ptypes: &[mbuf::PacketTypeFlags]) -> Result<&Self>;
+
+    /// Add a 5-tuple filter which steers the matched packets to a RX queue.
+    ///
+    /// The `filter_id` identifies the filter for `remove_5tuple_filter()`
+    /// and `get_5tuple_filter()`, it must be unique on the port.
+    ///
+    fn add_5tuple_filter(&self, filter: &FiveTupleFilter, filter_id: u16) -> Result<&Self>;
+
+    /// Remove a 5-tuple filter added with `add_5tuple_filter()`.
+    fn remove_5tuple_filter(&self, filter_id: u16) -> Result<&Self>;
+
+    /// Get a 5-tuple filter added with `add_5tuple_filter()`.
+    fn get_5tuple_filter(&self, filter_id: u16) -> Result<FiveTupleFilter>;
 }
 
 /// Get the total number of Ethernet devices that have been successfully initialized
@@ -188,8 +804,45 @@ pub fn count() -> u8 {
     unsafe { ffi::rte_eth_dev_count() }
 }
 
-pub fn devices() -> Range<PortId> {
-    0..count()
+pub fn devices() -> Map<Range<u8>, fn(u8) -> PortId> {
+    (0..count()).map(PortId::new_unchecked as fn(u8) -> PortId)
+}
+
+/// Get the number of the valid ports, which may be noncontiguous after hotplug.
+pub fn count_valid() -> u8 {
+    // iterate in u32, since a u8 range is empty when `RTE_MAX_ETHPORTS` is 256
+    (0..ffi::RTE_MAX_ETHPORTS)
+        .map(|port_id| PortId(port_id as u8))
+        .filter(|port_id| port_id.is_valid())
+        .count() as u8
+}
+
+/// Get the number of the valid ports which are started.
+pub fn count_running() -> u8 {
+    (0..ffi::RTE_MAX_ETHPORTS)
+        .map(|port_id| PortId(port_id as u8))
+        .filter(|port_id| port_id.is_valid() && unsafe { _rte_eth_dev_started(port_id.0) } != 0)
+        .count() as u8
+}
+
+/// Get the port id of the device from its name, e.g. the PCI address.
+pub fn port_by_name(name: &str) -> Result<PortId> {
+    let mut port_id: RawPortId = 0;
+
+    let ret = unsafe { rte_eth_dev_get_port_by_name(try!(to_cptr!(name)), &mut port_id) };
+
+    rte_check!(ret; ok => { PortId(port_id as u8) })
+}
+
+/// Get the name of the device from its port id.
+pub fn port_name(port_id: PortId) -> Result<String> {
+    let mut name = [0 as c_char; ffi::RTE_ETH_NAME_MAX_LEN as usize + 1];
+
+    let ret = unsafe { rte_eth_dev_get_name_by_port(port_id.0 as RawPortId, name.as_mut_ptr()) };
+
+    rte_check!(ret; ok => {
+        unsafe { CStr::from_ptr(name.as_ptr()).to_string_lossy().into_owned() }
+    })
 }
 
 /// Attach a new Ethernet device specified by aruguments.
@@ -198,71 +851,429 @@ pub fn attach(devargs: &str) -> Result<PortId> {
 
     let ret = unsafe { ffi::rte_eth_dev_attach(try!(to_cptr!(devargs)), &mut portid) };
 
-    rte_check!(ret; ok => { portid })
+    rte_check!(ret; ok => { PortId(portid) })
 }
 
-impl EthDevice for PortId {
-    fn portid(&self) -> PortId {
-        *self
+/// Detach a stopped and closed Ethernet device, returns the name of the detached device.
+///
+/// Fails with `EBUSY` if the device is still started or has configured queues.
+///
+pub fn detach(port_id: PortId) -> Result<String> {
+    if !port_id.is_valid() {
+        return Err(Error::invalid_port_id(port_id.0));
     }
 
-    fn configure(&self,
-                 nb_rx_queue: QueueId,
-                 nb_tx_queue: QueueId,
-                 conf: &EthConf)
-                 -> Result<&Self> {
-        rte_check!(unsafe {
-            ffi::rte_eth_dev_configure(*self,
-                                       nb_rx_queue,
-                                       nb_tx_queue,
-                                       RawEthConf::from(conf).as_raw())
-        }; ok => { self })
+    if unsafe { _rte_eth_dev_started(port_id.0) } != 0 ||
+       try!(port_id.nb_rx_queues()) != 0 || try!(port_id.nb_tx_queues()) != 0 {
+        return Err(Error::OsError(libc::EBUSY));
     }
 
-    fn info(&self) -> RawEthDeviceInfo {
-        let mut info: RawEthDeviceInfo = Default::default();
+    let mut name = [0 as c_char; ffi::RTE_ETH_NAME_MAX_LEN as usize + 1];
 
-        unsafe { ffi::rte_eth_dev_info_get(*self, &mut info) }
+    let ret = unsafe { ffi::rte_eth_dev_detach(port_id.0, name.as_mut_ptr()) };
 
-        info
-    }
+    rte_check!(ret; ok => {
+        unsafe { CStr::from_ptr(name.as_ptr()).to_string_lossy().into_owned() }
+    })
+}
 
-    fn stats(&self) -> Result<RawEthDeviceStats> {
-        let mut stats: RawEthDeviceStats = Default::default();
+/// Allocate a new unique owner id to take the ownership of the devices.
+#[cfg(dpdk_ver_18_02)]
+pub fn owner_new() -> Result<u64> {
+    let mut owner_id = 0;
 
-        rte_check!(unsafe {
-            ffi::rte_eth_stats_get(*self, &mut stats)
-        }; ok => { stats })
-    }
+    let ret = unsafe { rte_eth_dev_owner_new(&mut owner_id) };
 
-    fn reset_stats(&self) -> &Self {
-        unsafe { ffi::rte_eth_stats_reset(*self) };
+    rte_check!(ret; ok => { owner_id })
+}
 
-        self
+/// The burst size used by `EthDevice::drain_rx_all_queues()`.
+const DRAIN_BURST_SIZE: usize = 32;
+
+/// The maximum bursts received from a queue by `EthDevice::drain_rx_all_queues()`.
+const DRAIN_MAX_BURSTS: usize = 1000;
+
+/// The time to wait for the links in `start_all()`, the same as `EthDevice::link()`.
+const START_ALL_LINK_TIMEOUT_MS: u64 = 9000;
+
+/// Start the Ethernet devices, then wait up to 9 seconds for all their links to be up.
+///
+/// The links are polled together, so the startup takes the time of the slowest port.
+/// The failure of a port doesn't prevent the others from starting, a started port
+/// whose link is still down after the timeout fails with `Error::Timeout`.
+///
+pub fn start_all<I: IntoIterator<Item = PortId>>(ports: I) -> Vec<(PortId, Result<()>)> {
+    let mut results = ports.into_iter()
+        .map(|port_id| (port_id, port_id.start().map(|_| ())))
+        .collect::<Vec<(PortId, Result<()>)>>();
+
+    let mut pending = (0..results.len()).filter(|&i| results[i].1.is_ok()).collect::<Vec<usize>>();
+    let timeout = Duration::from_millis(START_ALL_LINK_TIMEOUT_MS);
+    let start = Instant::now();
+
+    loop {
+        pending.retain(|&i| !results[i].0.link_nowait().up);
+
+        if pending.is_empty() {
+            break;
+        }
+
+        let elapsed = start.elapsed();
+
+        if elapsed >= timeout {
+            for &i in &pending {
+                let port_id = results[i].0;
+
+                results[i].1 = Err(Error::Timeout(format!("link up of port {}", port_id), elapsed));
+            }
+
+            break;
+        }
+
+        delay::ms(100);
+    }
+
+    results
+}
+
+/// Find an unused device name for the virtual device of the driver.
+fn unused_vdev_name(driver: &str) -> String {
+    (0..)
+        .map(|i| format!("{}{}", driver, i))
+        .find(|name| port_by_name(name).is_err())
+        .unwrap()
+}
+
+/// The number of mbufs of the pool created by `create_null_port()` for each RX queue.
+const NULL_PORT_POOL_SIZE: u32 = 1024;
+
+/// The driver of the null devices, which is renamed in DPDK 16.11.
+#[cfg(dpdk_ver_16_11)]
+const NULL_PMD_NAME: &'static str = "net_null";
+#[cfg(not(dpdk_ver_16_11))]
+const NULL_PMD_NAME: &'static str = "eth_null";
+
+/// The sequence of the mempools created by `create_null_port()`,
+/// so a port id reused after `destroy_null_port()` gets a new name.
+static NULL_PORT_POOL_SEQ: AtomicUsize = ATOMIC_USIZE_INIT;
+
+/// Create a started `net_null` device with the RX and TX queues, e.g. to test without NIC.
+///
+/// The null device receives the packets allocated from a mempool created for the port,
+/// and drops the sent packets. The port should be destroyed with `destroy_null_port()`.
+///
+pub fn create_null_port(num_queues: QueueId) -> Result<PortId> {
+    let port_id = try!(attach(&unused_vdev_name(NULL_PMD_NAME)));
+
+    let pool_name = format!("null_pool_{}_{}",
+                            port_id,
+                            NULL_PORT_POOL_SEQ.fetch_add(1, Ordering::SeqCst));
+    let pool = match mbuf::pktmbuf_pool_create(&pool_name,
+                                               NULL_PORT_POOL_SIZE * num_queues as u32,
+                                               32,
+                                               0,
+                                               mbuf::RTE_MBUF_DEFAULT_BUF_SIZE,
+                                               port_id.socket_id()) {
+        Ok(pool) => pool,
+        Err(err) => {
+            let _ = detach(port_id);
+
+            return Err(err);
+        }
+    };
+
+    let res = port_id.configure(num_queues, num_queues, &EthConf::default())
+        .and_then(|_| {
+            for queue_id in 0..num_queues {
+                try!(port_id.rx_queue_setup(queue_id, 128, None, unsafe { &mut *pool }));
+                try!(port_id.tx_queue_setup(queue_id, 512, None));
+            }
+
+            port_id.start()
+        });
+
+    match res {
+        Ok(_) => {
+            NULL_PORT_POOLS.lock().unwrap().insert(port_id.0, pool as usize);
+
+            Ok(port_id)
+        }
+        Err(err) => {
+            port_id.stop().close();
+
+            let _ = detach(port_id);
+
+            free_null_port_pool(pool);
+
+            Err(err)
+        }
+    }
+}
+
+/// Stop, close and detach a device created by `create_null_port()` and free its mempool,
+/// returns the name of the detached device.
+pub fn destroy_null_port(port_id: PortId) -> Result<String> {
+    port_id.stop().close();
+
+    let name = try!(detach(port_id));
+
+    if let Some(pool) = NULL_PORT_POOLS.lock().unwrap().remove(&port_id.0) {
+        free_null_port_pool(pool as mempool::RawMemoryPoolPtr);
+    }
+
+    Ok(name)
+}
+
+#[cfg(dpdk_ver_16_07)]
+fn free_null_port_pool(pool: mempool::RawMemoryPoolPtr) {
+    mempool::free(pool)
+}
+
+// The mempools can't be freed before DPDK 16.07.
+#[cfg(not(dpdk_ver_16_07))]
+fn free_null_port_pool(_pool: mempool::RawMemoryPoolPtr) {}
+
+/// Create a `net_tap` device with a kernel TAP interface of the name.
+pub fn create_tap_port(name: &str) -> Result<PortId> {
+    attach(&format!("{},iface={}", unused_vdev_name("net_tap"), name))
+}
+
+/// The size of the packets sent by `loopback_test()`.
+const LOOPBACK_PKT_LEN: usize = 64;
+
+/// The time to wait for the packets in `loopback_test()`.
+const LOOPBACK_TIMEOUT_MS: u64 = 1000;
+
+/// The byte pattern of the packets sent by `loopback_test()`, mixed with their index.
+const LOOPBACK_PATTERN: u8 = 0xa5;
+
+/// Send the packets on the first queue of the started port and receive them back,
+/// when the port loops back the packets, e.g. a ring device or a NIC in loopback mode.
+///
+/// Each packet is filled with a pattern of its index, the packets must be received
+/// in order with the same length and content, otherwise `EIO` is returned.
+///
+/// Returns the number of the sent packets, fails with `Error::Timeout` if less packets
+/// are received in time. The received packets are freed.
+///
+pub fn loopback_test(port: PortId,
+                     pool: &mut mempool::RawMemoryPool,
+                     num_packets: usize)
+                     -> Result<usize> {
+    use mbuf::{PktMbuf, PktMbufPool};
+
+    let mut pkts = vec![ptr::null_mut(); num_packets];
+
+    try!(pool.alloc_bulk(&mut pkts));
+
+    for (i, &m) in pkts.iter().enumerate() {
+        let m = unsafe { &mut *m };
+        let data = try!(m.append(LOOPBACK_PKT_LEN));
+
+        unsafe { ptr::write_bytes(data, LOOPBACK_PATTERN ^ i as u8, LOOPBACK_PKT_LEN) };
+    }
+
+    let mut sent = 0;
+
+    while sent < num_packets {
+        let n = port.tx_burst(0, &mut pkts[sent..]);
+
+        if n == 0 {
+            break;
+        }
+
+        sent += n;
+    }
+
+    for &m in &pkts[sent..] {
+        unsafe { &mut *m }.free();
+    }
+
+    let mut received = 0;
+    let mut corrupted = false;
+    let timeout = Duration::from_millis(LOOPBACK_TIMEOUT_MS);
+    let start = Instant::now();
+
+    while received < sent {
+        let n = port.rx_burst(0, &mut pkts[..sent - received]);
+
+        for (i, &m) in pkts[..n].iter().enumerate() {
+            let m = unsafe { &mut *m };
+            let data = pktmbuf_mtod!(m as *mut mbuf::RawMbuf, *const u8);
+            let pattern = LOOPBACK_PATTERN ^ (received + i) as u8;
+
+            corrupted |= m.data_len as usize != LOOPBACK_PKT_LEN ||
+                         unsafe { slice::from_raw_parts(data, LOOPBACK_PKT_LEN) }
+                .iter()
+                .any(|&b| b != pattern);
+
+            m.free();
+        }
+
+        received += n;
+
+        if n == 0 {
+            let elapsed = start.elapsed();
+
+            if elapsed >= timeout {
+                return Err(Error::Timeout(format!("loopback of {} packets on port {}",
+                                                  sent - received,
+                                                  port),
+                                          elapsed));
+            }
+
+            delay::ms(1);
+        }
+    }
+
+    if corrupted {
+        Err(Error::OsError(libc::EIO))
+    } else {
+        Ok(sent)
+    }
+}
+
+impl EthDevice for PortId {
+    fn portid(&self) -> PortId {
+        *self
+    }
+
+    fn configure(&self,
+                 nb_rx_queue: QueueId,
+                 nb_tx_queue: QueueId,
+                 conf: &EthConf)
+                 -> Result<&Self> {
+        rte_check!(unsafe {
+            ffi::rte_eth_dev_configure(self.0,
+                                       nb_rx_queue,
+                                       nb_tx_queue,
+                                       RawEthConf::from(conf).as_raw())
+        }; ok => { self })
+    }
+
+    fn info(&self) -> RawEthDeviceInfo {
+        let mut info: RawEthDeviceInfo = Default::default();
+
+        unsafe { ffi::rte_eth_dev_info_get(self.0, &mut info) }
+
+        info
+    }
+
+    fn stats(&self) -> Result<RawEthDeviceStats> {
+        let mut stats: RawEthDeviceStats = Default::default();
+
+        rte_check!(unsafe {
+            ffi::rte_eth_stats_get(self.0, &mut stats)
+        }; ok => { stats })
+    }
+
+    fn reset_stats(&self) -> &Self {
+        unsafe { ffi::rte_eth_stats_reset(self.0) };
+
+        self
+    }
+
+    fn xstats(&self) -> Result<Vec<EthXStat>> {
+        let n = unsafe { ffi::rte_eth_xstats_get(self.0, ptr::null_mut(), 0) };
+
+        if n <= 0 {
+            return rte_check!(n; ok => { Vec::new() });
+        }
+
+        let mut xstats: Vec<ffi::Struct_rte_eth_xstats> = vec![Default::default(); n as usize];
+
+        let n = unsafe { ffi::rte_eth_xstats_get(self.0, xstats.as_mut_ptr(), n as u32) };
+
+        rte_check!(n; ok => {
+            xstats.iter()
+                .take(n as usize)
+                .map(|xstat| {
+                    EthXStat {
+                        name: unsafe {
+                            CStr::from_ptr(xstat.name.as_ptr()).to_string_lossy().into_owned()
+                        },
+                        value: xstat.value,
+                    }
+                })
+                .collect()
+        })
+    }
+
+    fn reset_xstats(&self) -> &Self {
+        unsafe { ffi::rte_eth_xstats_reset(self.0) };
+
+        self
     }
 
     fn mac_addr(&self) -> ether::EtherAddr {
         unsafe {
             let mut addr: ffi::Struct_ether_addr = mem::zeroed();
 
-            ffi::rte_eth_macaddr_get(*self, &mut addr);
+            ffi::rte_eth_macaddr_get(self.0, &mut addr);
 
             ether::EtherAddr::from(addr.addr_bytes)
         }
     }
 
+    fn mac_addrs(&self) -> Result<Vec<ether::EtherAddr>> {
+        if !self.is_valid() {
+            return Err(Error::invalid_port_id(self.0));
+        }
+
+        let n = self.info().max_mac_addrs as usize;
+        let mut addrs: Vec<ffi::Struct_ether_addr> = vec![Default::default(); n];
+
+        // `rte_eth_macaddr_get` only returns the default address,
+        // so the table is copied from the device data in C to avoid depending on its layout
+        let n = unsafe { _rte_eth_dev_mac_addrs(self.0, addrs.as_mut_ptr(), n as u32) };
+
+        rte_check!(n; ok => {
+            addrs.iter()
+                .take(n as usize)
+                .map(|addr| ether::EtherAddr::from(addr.addr_bytes))
+                .filter(|addr| !addr.is_zero())
+                .collect()
+        })
+    }
+
     fn set_mac_addr(&self, addr: &[u8; ether::ETHER_ADDR_LEN]) -> Result<&Self> {
         rte_check!(unsafe {
-            ffi::rte_eth_dev_default_mac_addr_set(*self, mem::transmute(addr.as_ptr()))
+            ffi::rte_eth_dev_default_mac_addr_set(self.0, mem::transmute(addr.as_ptr()))
         }; ok => { self })
     }
 
+    fn set_mc_addr_list(&self, addrs: &[ether::EtherAddr]) -> Result<&Self> {
+        if let Some(addr) = addrs.iter().find(|addr| !addr.is_multicast()) {
+            warn!("fail to set multicast addresses of port {}, {} is not multicast",
+                  self.0,
+                  addr);
+
+            return Err(Error::OsError(libc::EINVAL));
+        }
+
+        let mut mc_addrs = addrs.iter()
+            .map(|addr| ffi::Struct_ether_addr { addr_bytes: *addr.octets() })
+            .collect::<Vec<ffi::Struct_ether_addr>>();
+
+        let ret = unsafe {
+            ffi::rte_eth_dev_set_mc_addr_list(self.0,
+                                              if mc_addrs.is_empty() {
+                                                  ptr::null_mut()
+                                              } else {
+                                                  mc_addrs.as_mut_ptr()
+                                              },
+                                              mc_addrs.len() as u32)
+        };
+
+        rte_check!(ret; ok => { self })
+    }
+
     fn socket_id(&self) -> SocketId {
-        unsafe { ffi::rte_eth_dev_socket_id(*self) }
+        unsafe { ffi::rte_eth_dev_socket_id(self.0) }
     }
 
     fn is_valid(&self) -> bool {
-        unsafe { ffi::rte_eth_dev_is_valid_port(*self) != 0 }
+        unsafe { ffi::rte_eth_dev_is_valid_port(self.0) != 0 }
     }
 
     fn rx_queue_setup(&self,
@@ -272,7 +1283,7 @@ impl EthDevice for PortId {
                       mb_pool: &mut mempool::RawMemoryPool)
                       -> Result<&Self> {
         rte_check!(unsafe {
-            ffi::rte_eth_rx_queue_setup(*self,
+            ffi::rte_eth_rx_queue_setup(self.0,
                                         rx_queue_id,
                                         nb_rx_desc,
                                         self.socket_id() as u32,
@@ -287,7 +1298,7 @@ impl EthDevice for PortId {
                       tx_conf: Option<ffi::Struct_rte_eth_txconf>)
                       -> Result<&Self> {
         rte_check!(unsafe {
-            ffi::rte_eth_tx_queue_setup(*self,
+            ffi::rte_eth_tx_queue_setup(self.0,
                                         tx_queue_id,
                                         nb_tx_desc,
                                         self.socket_id() as u32,
@@ -296,19 +1307,19 @@ impl EthDevice for PortId {
     }
 
     fn promiscuous_enable(&self) -> &Self {
-        unsafe { ffi::rte_eth_promiscuous_enable(*self) };
+        unsafe { ffi::rte_eth_promiscuous_enable(self.0) };
 
         self
     }
 
     fn promiscuous_disable(&self) -> &Self {
-        unsafe { ffi::rte_eth_promiscuous_disable(*self) };
+        unsafe { ffi::rte_eth_promiscuous_disable(self.0) };
 
         self
     }
 
     fn is_promiscuous_enabled(&self) -> Result<bool> {
-        let ret = unsafe { ffi::rte_eth_promiscuous_get(*self) };
+        let ret = unsafe { ffi::rte_eth_promiscuous_get(self.0) };
 
         rte_check!(ret; ok => { ret != 0 })
     }
@@ -316,23 +1327,58 @@ impl EthDevice for PortId {
     fn mtu(&self) -> Result<u16> {
         let mut mtu: u16 = 0;
 
-        rte_check!(unsafe { ffi::rte_eth_dev_get_mtu(*self, &mut mtu)}; ok => { mtu })
+        rte_check!(unsafe { ffi::rte_eth_dev_get_mtu(self.0, &mut mtu)}; ok => { mtu })
     }
 
     fn set_mtu(&self, mtu: u16) -> Result<&Self> {
-        rte_check!(unsafe { ffi::rte_eth_dev_set_mtu(*self, mtu) }; ok => { self })
+        self.set_mtu_checked(mtu)
+    }
+
+    fn min_mtu(&self) -> Result<u16> {
+        self.mtu_range().map(|(min_mtu, _)| min_mtu)
+    }
+
+    fn max_mtu(&self) -> Result<u16> {
+        self.mtu_range().map(|(_, max_mtu)| max_mtu)
+    }
+
+    fn set_mtu_checked(&self, mtu: u16) -> Result<&Self> {
+        let (min_mtu, max_mtu) = try!(self.mtu_range());
+
+        if mtu < min_mtu || mtu > max_mtu {
+            return Err(Error::InvalidMtu {
+                requested: mtu,
+                min: min_mtu,
+                max: max_mtu,
+            });
+        }
+
+        rte_check!(unsafe { ffi::rte_eth_dev_set_mtu(self.0, mtu) }; ok => { self })
     }
 
     fn set_vlan_filter(&self, vlan_id: u16, on: bool) -> Result<&Self> {
         rte_check!(unsafe {
-            ffi::rte_eth_dev_vlan_filter(*self, vlan_id, bool_value!(on) as i32)
+            ffi::rte_eth_dev_vlan_filter(self.0, vlan_id, bool_value!(on) as i32)
         }; ok => { self })
     }
 
     fn link(&self) -> EthLink {
         let link = 0u64;
 
-        unsafe { ffi::rte_eth_link_get(*self, mem::transmute(&link)) }
+        unsafe { ffi::rte_eth_link_get(self.0, mem::transmute(&link)) }
+
+        EthLink {
+            speed: (link & 0xFFFFFFFF) as u32,
+            duplex: (link & (1 << 32)) != 0,
+            autoneg: (link & (1 << 33)) != 0,
+            up: (link & (1 << 34)) != 0,
+        }
+    }
+
+    fn link_nowait(&self) -> EthLink {
+        let link = 0u64;
+
+        unsafe { ffi::rte_eth_link_get_nowait(self.0, mem::transmute(&link)) }
 
         EthLink {
             speed: (link & 0xFFFFFFFF) as u32,
@@ -342,148 +1388,1668 @@ impl EthDevice for PortId {
         }
     }
 
-    fn link_nowait(&self) -> EthLink {
-        let link = 0u64;
+    fn set_link_up(&self) -> Result<&Self> {
+        rte_check!(unsafe { ffi::rte_eth_dev_set_link_up(self.0) }; ok => { self })
+    }
+
+    fn set_link_down(&self) -> Result<&Self> {
+        rte_check!(unsafe { ffi::rte_eth_dev_set_link_down(self.0) }; ok => { self })
+    }
+
+    fn rx_queue_start(&self, rx_queue_id: QueueId) -> Result<&Self> {
+        rte_check!(unsafe { ffi::rte_eth_dev_rx_queue_start(self.0, rx_queue_id) }; ok => { self })
+    }
+
+    fn rx_queue_stop(&self, rx_queue_id: QueueId) -> Result<&Self> {
+        rte_check!(unsafe { ffi::rte_eth_dev_rx_queue_stop(self.0, rx_queue_id) }; ok => { self })
+    }
+
+    fn tx_queue_start(&self, tx_queue_id: QueueId) -> Result<&Self> {
+        rte_check!(unsafe { ffi::rte_eth_dev_tx_queue_start(self.0, tx_queue_id) }; ok => { self })
+    }
+
+    fn tx_queue_stop(&self, tx_queue_id: QueueId) -> Result<&Self> {
+        rte_check!(unsafe { ffi::rte_eth_dev_tx_queue_stop(self.0, tx_queue_id) }; ok => { self })
+    }
+
+    fn start(&self) -> Result<&Self> {
+        rte_check!(unsafe { ffi::rte_eth_dev_start(self.0) }; ok => { self })
+    }
+
+    fn stop(&self) -> &Self {
+        unsafe { ffi::rte_eth_dev_stop(self.0) };
+
+        self
+    }
+
+    fn close(&self) -> &Self {
+        unsafe { ffi::rte_eth_dev_close(self.0) };
+
+        self
+    }
+
+    #[cfg(dpdk_ver_18_02)]
+    fn reset(&self) -> Result<&Self> {
+        match unsafe { _rte_eth_dev_started(self.0) } {
+            0 => {}
+            ret if ret < 0 => return Err(Error::invalid_port_id(self.0)),
+            _ => return Err(Error::OsError(libc::EBUSY)),
+        }
+
+        rte_check!(unsafe { rte_eth_dev_reset(self.0 as RawPortId) }; ok => { self })
+    }
+
+    fn rx_burst(&self, queue_id: QueueId, rx_pkts: &mut [mbuf::RawMbufPtr]) -> usize {
+        unsafe {
+            _rte_eth_rx_burst(self.0, queue_id, rx_pkts.as_mut_ptr(), rx_pkts.len() as u16) as usize
+        }
+    }
+
+    fn tx_burst(&self, queue_id: QueueId, rx_pkts: &mut [mbuf::RawMbufPtr]) -> usize {
+        unsafe {
+            if rx_pkts.is_empty() {
+                _rte_eth_tx_burst(self.0, queue_id, ptr::null_mut(), 0) as usize
+            } else {
+                _rte_eth_tx_burst(self.0,
+                                  queue_id,
+                                  rx_pkts.as_mut_ptr(),
+                                  rx_pkts.len() as u16) as usize
+            }
+        }
+    }
+
+    fn rx_burst_ex(&self, queue_id: QueueId, rx_pkts: &mut [mbuf::RawMbufPtr], packets: u16) -> u16 {
+        unsafe {
+            _rte_eth_rx_burst(self.0, queue_id, rx_pkts.as_mut_ptr(), packets)
+        }
+    }
+
+    fn tx_burst_ex(&self, queue_id: QueueId, rx_pkts: &mut [mbuf::RawMbufPtr], packets: u16) -> u16 {
+        unsafe {
+            _rte_eth_tx_burst(self.0, queue_id, rx_pkts.as_mut_ptr(), packets)
+        }
+    }
+
+    #[cfg(dpdk_ver_17_02)]
+    fn tx_prepare(&self, queue_id: QueueId, pkts: &mut [mbuf::RawMbufPtr]) -> u16 {
+        unsafe { _rte_eth_tx_prepare(self.0, queue_id, pkts.as_mut_ptr(), pkts.len() as u16) }
+    }
+
+
+    fn set_vf_rxmode(&self, vf: u16, rx_mode: EthVmdqRxMode, on: bool) -> Result<&Self> {
+        rte_check!(unsafe {
+            ffi::rte_eth_dev_set_vf_rxmode(self.0, vf, rx_mode.bits, bool_value!(on))
+        }; ok => { self })
+    }
+
+    fn set_pool_rx_mode(&self, pool: u8, mode: EthVmdqRxMode, enable: bool) -> Result<&Self> {
+        self.set_vf_rxmode(pool as u16, mode, enable)
+    }
+
+    fn set_split_drop_en(&self, enable: bool) -> Result<&Self> {
+        for queue_id in 0..try!(self.nb_rx_queues()) {
+            let mut info: ffi::Struct_rte_eth_rxq_info = Default::default();
+
+            try!(rte_check!(unsafe {
+                ffi::rte_eth_rx_queue_info_get(self.0, queue_id, &mut info)
+            }));
+
+            info.conf.rx_drop_en = bool_value!(enable);
+
+            try!(rte_check!(unsafe {
+                ffi::rte_eth_rx_queue_setup(self.0,
+                                            queue_id,
+                                            info.nb_desc,
+                                            self.socket_id() as u32,
+                                            &info.conf,
+                                            info.mp)
+            }));
+        }
+
+        Ok(self)
+    }
+
+    fn get_split_drop_en(&self) -> Result<bool> {
+        let mut enabled = true;
+
+        for queue_id in 0..try!(self.nb_rx_queues()) {
+            let mut info: ffi::Struct_rte_eth_rxq_info = Default::default();
+
+            try!(rte_check!(unsafe {
+                ffi::rte_eth_rx_queue_info_get(self.0, queue_id, &mut info)
+            }));
+
+            enabled &= info.conf.rx_drop_en != 0;
+        }
+
+        Ok(enabled)
+    }
+
+    fn set_vf_rate_limit(&self, vf: u16, tx_rate: u32, q_msk: u64) -> Result<&Self> {
+        if vf >= self.info().max_vfs || tx_rate > ::std::u16::MAX as u32 {
+            return Err(Error::OsError(libc::EINVAL));
+        }
+
+        let link = self.link_nowait();
+
+        if link.up && tx_rate > link.speed {
+            return Err(Error::OsError(libc::EINVAL));
+        }
+
+        let ret = unsafe { ffi::rte_eth_set_vf_rate_limit(self.0, vf, tx_rate as u16, q_msk) };
+
+        try!(rte_check!(ret));
+
+        VF_RATE_LIMITS.lock().unwrap().insert((self.0, vf), (tx_rate, q_msk));
+
+        Ok(self)
+    }
+
+    fn get_vf_rate_limit(&self, vf: u16) -> Option<(u32, u64)> {
+        VF_RATE_LIMITS.lock().unwrap().get(&(self.0, vf)).cloned()
+    }
+
+    fn set_vf_rx_vlan(&self, vf: u16, vlan_id: u16, on: bool) -> Result<&Self> {
+        // the VFs are selected by a 64 bits mask
+        if vf >= cmp::min(self.info().max_vfs, 64) || vlan_id >= 4096 {
+            return Err(Error::OsError(libc::EINVAL));
+        }
+
+        let ret = unsafe {
+            ffi::rte_eth_dev_set_vf_vlan_filter(self.0, vlan_id, 1 << vf, bool_value!(on))
+        };
+
+        rte_check!(ret; ok => { self })
+    }
+
+    #[cfg(dpdk_ver_17_02)]
+    fn set_vf_mac_addr(&self, vf: u16, addr: &[u8; 6]) -> Result<&Self> {
+        if !ether::EtherAddr::from(*addr).is_valid() {
+            warn!("fail to set MAC address of VF {} of port {}, {} is not a valid address",
+                  vf,
+                  self,
+                  ether::EtherAddr::from(*addr));
+
+            return Err(Error::OsError(libc::EINVAL));
+        }
+
+        let mut addr = ffi::Struct_ether_addr { addr_bytes: *addr };
+
+        let ret = unsafe { rte_pmd_ixgbe_set_vf_mac_addr(self.0 as RawPortId, vf, &mut addr) };
+
+        rte_check!(ret; ok => { self })
+    }
+
+    #[cfg(dpdk_ver_17_02)]
+    fn get_vf_stats(&self, vf: u16) -> Result<VfStats> {
+        let mut stats: RawEthDeviceStats = Default::default();
+
+        let ret = unsafe { rte_pmd_i40e_get_vf_stats(self.0 as RawPortId, vf, &mut stats) };
+
+        rte_check!(ret; ok => { VfStats::from(stats) })
+    }
+
+    fn set_rss_hash_conf(&self, key: Option<&[u8]>, hash_funcs: RssHashFunc) -> Result<&Self> {
+        let mut conf = ffi::Struct_rte_eth_rss_conf {
+            rss_key: ptr::null_mut(),
+            rss_key_len: 0,
+            rss_hf: hash_funcs.bits,
+        };
+
+        if let Some(key) = key {
+            let key_size = self.info().hash_key_size as usize;
+
+            if key_size != 0 && key.len() != key_size {
+                return Err(Error::InvalidRssKeyLen(key_size, key.len()));
+            }
+
+            conf.rss_key = key.as_ptr() as *mut u8;
+            conf.rss_key_len = key.len() as u8;
+        }
+
+        let ret = unsafe { ffi::rte_eth_dev_rss_hash_update(self.0, &mut conf) };
+
+        rte_check!(ret; ok => { self })
+    }
+
+    fn rss_hash_conf(&self) -> Result<EthRssConf> {
+        let key_size = cmp::max(self.info().hash_key_size as usize, 40);
+        let mut key = vec![0u8; key_size];
+        let mut conf = ffi::Struct_rte_eth_rss_conf {
+            rss_key: key.as_mut_ptr(),
+            rss_key_len: key_size as u8,
+            rss_hf: 0,
+        };
+
+        let ret = unsafe { ffi::rte_eth_dev_rss_hash_conf_get(self.0, &mut conf) };
+
+        rte_check!(ret; ok => {
+            EthRssConf {
+                key: if conf.rss_key_len == 40 {
+                    let mut rss_key = [0u8; 40];
+
+                    rss_key.copy_from_slice(&key[..40]);
+
+                    Some(rss_key)
+                } else {
+                    None
+                },
+                hash: RssHashFunc::from_bits_truncate(conf.rss_hf),
+            }
+        })
+    }
+
+    #[cfg(dpdk_ver_18_02)]
+    fn pool_ops_supported(&self, pool_ops_name: &str) -> bool {
+        if !mempool::pool_ops_supported(pool_ops_name) {
+            return false;
+        }
+
+        match to_cptr!(pool_ops_name) {
+            Ok(name) => unsafe { rte_eth_dev_pool_ops_supported(self.0 as RawPortId, name) >= 0 },
+            Err(_) => false,
+        }
+    }
+
+    #[cfg(dpdk_ver_18_02)]
+    fn owner_set(&self, owner_id: u64, name: &str) -> Result<&Self> {
+        let name = name.as_bytes();
+
+        if name.len() >= RTE_ETH_MAX_OWNER_NAME_LEN {
+            return Err(Error::OsError(libc::EINVAL));
+        }
+
+        let mut owner = RawEthDevOwner {
+            id: owner_id,
+            name: [0; RTE_ETH_MAX_OWNER_NAME_LEN],
+        };
+
+        for (dst, &src) in owner.name.iter_mut().zip(name) {
+            *dst = src as c_char;
+        }
+
+        let ret = unsafe { rte_eth_dev_owner_set(self.0 as RawPortId, &owner) };
+
+        rte_check!(ret; ok => { self })
+    }
+
+    #[cfg(dpdk_ver_18_02)]
+    fn owner_unset(&self, owner_id: u64) -> Result<&Self> {
+        let ret = unsafe { rte_eth_dev_owner_unset(self.0 as RawPortId, owner_id) };
+
+        rte_check!(ret; ok => { self })
+    }
+
+    #[cfg(dpdk_ver_18_02)]
+    fn owner_get(&self) -> Result<EthDevOwner> {
+        let mut owner: RawEthDevOwner = unsafe { mem::zeroed() };
+
+        let ret = unsafe { rte_eth_dev_owner_get(self.0 as RawPortId, &mut owner) };
+
+        rte_check!(ret; ok => { EthDevOwner::from(&owner) })
+    }
+
+    fn set_loopback_mode(&self, conf: &mut EthConf, mode: LoopbackMode) -> Result<&Self> {
+        if !self.is_valid() {
+            return Err(Error::invalid_port_id(self.0));
+        }
+
+        conf.lpbk_mode = try!(mode.driver_value(self.info().driver_name()));
+
+        Ok(self)
+    }
+
+    fn set_vf_tx(&self, vf: u16, on: bool) -> Result<&Self> {
+        rte_check!(unsafe {
+            ffi::rte_eth_dev_set_vf_tx(self.0, vf, bool_value!(on))
+        }; ok => { self })
+    }
+
+    fn set_vf_rx(&self, vf: u16, on: bool) -> Result<&Self> {
+        rte_check!(unsafe {
+            ffi::rte_eth_dev_set_vf_rx(self.0, vf, bool_value!(on))
+        }; ok => { self })
+    }
+
+    fn vlan_offload(&self) -> Result<EthVlanOffloadMode> {
+        let ret = unsafe { ffi::rte_eth_dev_get_vlan_offload(self.0) };
+
+        if ret < 0 {
+            return Err(Error::from_ret(ret));
+        }
+
+        // the mode is a combination of the offload bits, which overlap with the masks
+        let offloads = ETH_VLAN_STRIP_OFFLOAD | ETH_VLAN_FILTER_OFFLOAD | ETH_VLAN_EXTEND_OFFLOAD;
+
+        Ok(EthVlanOffloadMode::from_bits_truncate(ret) & offloads)
+    }
+
+    fn set_vlan_offload(&self, mode: EthVlanOffloadMode) -> Result<&Self> {
+        rte_check!(unsafe {
+            ffi::rte_eth_dev_set_vlan_offload(self.0, mode.bits)
+        }; ok => { self })
+    }
+
+    fn set_l2_tunnel_type(&self, tunnel_type: L2TunnelType, ether_type: u16) -> Result<&Self> {
+        if tunnel_type == L2TunnelType::Standard802_1Q {
+            return self.set_vlan_ether_type(VlanType::ETH_VLAN_TYPE_OUTER, ether_type);
+        }
+
+        let mut conf = ffi::Struct_rte_eth_l2_tunnel_conf {
+            l2_tunnel_type: ffi::Enum_rte_eth_tunnel_type::RTE_L2_TUNNEL_TYPE_E_TAG,
+            ether_type: ether_type,
+            ..Default::default()
+        };
+
+        let ret = unsafe { ffi::rte_eth_dev_l2_tunnel_eth_type_conf(self.0, &mut conf) };
+
+        rte_check!(ret; ok => { self })
+    }
+
+    fn set_l2_tunnel_offload(&self,
+                             tunnel_type: L2TunnelType,
+                             mask: L2TunnelOffloadMask,
+                             on: bool)
+                             -> Result<&Self> {
+        if tunnel_type == L2TunnelType::Standard802_1Q {
+            if mask.intersects(ETH_L2_TUNNEL_INSERTION_MASK | ETH_L2_TUNNEL_FORWARDING_MASK) {
+                return Err(Error::OsError(libc::ENOTSUP));
+            }
+
+            let mut mode = EthVlanOffloadMode::empty();
+
+            if mask.contains(ETH_L2_TUNNEL_ENABLE_MASK) {
+                mode = mode | ETH_VLAN_EXTEND_OFFLOAD;
+            }
+
+            if mask.contains(ETH_L2_TUNNEL_STRIPPING_MASK) {
+                mode = mode | ETH_VLAN_STRIP_OFFLOAD;
+            }
+
+            return self.update_vlan_offload(mode, on);
+        }
+
+        let mut conf = ffi::Struct_rte_eth_l2_tunnel_conf {
+            l2_tunnel_type: ffi::Enum_rte_eth_tunnel_type::RTE_L2_TUNNEL_TYPE_E_TAG,
+            ..Default::default()
+        };
+
+        let ret = unsafe {
+            ffi::rte_eth_dev_l2_tunnel_offload_set(self.0, &mut conf, mask.bits, bool_value!(on))
+        };
+
+        rte_check!(ret; ok => { self })
+    }
+
+    fn set_vlan_ether_type(&self, vlan_type: VlanType, tpid: u16) -> Result<&Self> {
+        let ret = unsafe { ffi::rte_eth_dev_set_vlan_ether_type(self.0, vlan_type, tpid) };
+
+        rte_check!(ret; ok => { self })
+    }
+
+    fn set_vlan_pvid(&self, vlan_id: u16, on: bool) -> Result<&Self> {
+        if vlan_id >= 4096 {
+            return Err(Error::OsError(libc::EINVAL));
+        }
+
+        let ret = unsafe {
+            ffi::rte_eth_dev_set_vlan_pvid(self.0, vlan_id, bool_value!(on) as i32)
+        };
+
+        rte_check!(ret; ok => { self })
+    }
+
+    fn set_vlan_strip_queue(&self, queue_id: QueueId, on: bool) -> Result<&Self> {
+        let ret = unsafe {
+            ffi::rte_eth_dev_set_vlan_strip_on_queue(self.0, queue_id, bool_value!(on) as i32)
+        };
+
+        rte_check!(ret; ok => { self })
+    }
+
+    #[cfg(dpdk_ver_18_05)]
+    fn module_info(&self) -> Result<EthModuleInfo> {
+        let mut info: RawEthModuleInfo = Default::default();
+
+        rte_check!(unsafe {
+            rte_eth_dev_get_module_info(self.0 as RawPortId, &mut info)
+        }; ok => { EthModuleInfo::from(info) })
+    }
+
+    #[cfg(dpdk_ver_18_05)]
+    fn module_eeprom(&self, offset: u32, length: u32) -> Result<Vec<u8>> {
+        let mut data = vec![0u8; length as usize];
+        let mut info = ffi::Struct_rte_dev_eeprom_info {
+            data: data.as_mut_ptr() as *mut c_void,
+            offset: offset,
+            length: length,
+            magic: 0,
+        };
+
+        rte_check!(unsafe {
+            rte_eth_dev_get_module_eeprom(self.0 as RawPortId, &mut info)
+        }; ok => { data })
+    }
+
+    fn rx_queue_info(&self, queue_id: QueueId) -> Result<RxQueueInfo> {
+        let mut info: ffi::Struct_rte_eth_rxq_info = Default::default();
+
+        let ret = unsafe { ffi::rte_eth_rx_queue_info_get(self.0, queue_id, &mut info) };
+
+        rte_check!(ret; ok => {
+            RxQueueInfo {
+                mempool: info.mp,
+                scattered_rx: info.scattered_rx != 0,
+                nb_desc: info.nb_desc,
+                burst_mode: rx_queue_burst_mode(self, queue_id),
+            }
+        })
+    }
+
+    fn tx_queue_info(&self, queue_id: QueueId) -> Result<TxQueueInfo> {
+        let mut info: ffi::Struct_rte_eth_txq_info = Default::default();
+
+        let ret = unsafe { ffi::rte_eth_tx_queue_info_get(self.0, queue_id, &mut info) };
+
+        rte_check!(ret; ok => {
+            TxQueueInfo {
+                tx_free_thresh: info.conf.tx_free_thresh,
+                tx_rs_thresh: info.conf.tx_rs_thresh,
+                txq_flags: info.conf.txq_flags,
+                tx_deferred_start: info.conf.tx_deferred_start != 0,
+                nb_desc: info.nb_desc,
+                burst_mode: tx_queue_burst_mode(self, queue_id),
+            }
+        })
+    }
+
+    fn nb_rx_queues(&self) -> Result<QueueId> {
+        let ret = unsafe { _rte_eth_dev_nb_rx_queues(self.0) };
+
+        rte_check!(ret; ok => { ret as QueueId }; err => { Error::invalid_port_id(self.0) })
+    }
+
+    fn nb_tx_queues(&self) -> Result<QueueId> {
+        let ret = unsafe { _rte_eth_dev_nb_tx_queues(self.0) };
+
+        rte_check!(ret; ok => { ret as QueueId }; err => { Error::invalid_port_id(self.0) })
+    }
+
+    fn iter_rx_queues(&self) -> QueueInfoIter<RxQueueInfo> {
+        QueueInfoIter {
+            port: *self,
+            next: 0,
+            count: self.nb_rx_queues().unwrap_or(0),
+            info: <PortId as EthDevice>::rx_queue_info,
+        }
+    }
+
+    fn iter_tx_queues(&self) -> QueueInfoIter<TxQueueInfo> {
+        QueueInfoIter {
+            port: *self,
+            next: 0,
+            count: self.nb_tx_queues().unwrap_or(0),
+            info: <PortId as EthDevice>::tx_queue_info,
+        }
+    }
+
+    #[cfg(dpdk_ver_19_11)]
+    fn rx_burst_mode(&self, queue_id: QueueId) -> Result<BurstMode> {
+        let mut mode: RawBurstMode = unsafe { mem::zeroed() };
+
+        let ret = unsafe { rte_eth_rx_burst_mode_get(self.0 as RawPortId, queue_id, &mut mode) };
+
+        rte_check!(ret; ok => { BurstMode::from(&mode) })
+    }
+
+    #[cfg(dpdk_ver_19_11)]
+    fn tx_burst_mode(&self, queue_id: QueueId) -> Result<BurstMode> {
+        let mut mode: RawBurstMode = unsafe { mem::zeroed() };
+
+        let ret = unsafe { rte_eth_tx_burst_mode_get(self.0 as RawPortId, queue_id, &mut mode) };
+
+        rte_check!(ret; ok => { BurstMode::from(&mode) })
+    }
+
+    #[cfg(dpdk_ver_17_02)]
+    fn tx_done_cleanup(&self, queue_id: QueueId, free_cnt: u32) -> Result<u32> {
+        let ret = unsafe { rte_eth_tx_done_cleanup(self.0 as RawPortId, queue_id, free_cnt) };
+
+        rte_check!(ret; ok => { ret as u32 }; err => {
+            if ret == -libc::ENOTSUP {
+                Error::OsError(libc::ENOTSUP)
+            } else {
+                Error::from_ret(ret)
+            }
+        })
+    }
+
+    fn dcb_info(&self) -> Result<DcbInfo> {
+        let mut info: RawDcbInfo = Default::default();
+
+        let ret = unsafe { ffi::rte_eth_dev_get_dcb_info(self.0, &mut info) };
+
+        rte_check!(ret; ok => {
+            let pfc_enable = unsafe { _rte_eth_dev_dcb_capability(self.0) } &
+                             ffi::ETH_DCB_PFC_SUPPORT != 0;
+
+            DcbInfo::new(info, pfc_enable)
+        })
+    }
+
+    #[cfg(dpdk_ver_16_07)]
+    fn reg_info(&self) -> Result<EthRegInfo> {
+        // the driver fills the length and width when the data is NULL
+        let mut info = RawRegInfo::default();
+
+        let ret = unsafe { rte_eth_dev_get_reg_info(self.0 as RawPortId, &mut info) };
+
+        rte_check!(ret; ok => {
+            EthRegInfo {
+                length: info.length,
+                width: info.width,
+            }
+        })
+    }
+
+    #[cfg(not(dpdk_ver_16_07))]
+    fn reg_info(&self) -> Result<EthRegInfo> {
+        let len = unsafe { ffi::rte_eth_dev_get_reg_length(self.0) };
+
+        rte_check!(len; ok => {
+            EthRegInfo {
+                length: len as u32,
+                width: REG_WIDTH,
+            }
+        })
+    }
+
+    #[cfg(dpdk_ver_16_07)]
+    fn regs(&self) -> Result<Vec<u8>> {
+        let reg_info = try!(self.reg_info());
+        let mut data = vec![0u8; (reg_info.length * reg_info.width) as usize];
+        let mut info = RawRegInfo {
+            data: data.as_mut_ptr() as *mut c_void,
+            length: reg_info.length,
+            width: reg_info.width,
+            ..Default::default()
+        };
+
+        let ret = unsafe { rte_eth_dev_get_reg_info(self.0 as RawPortId, &mut info) };
+
+        rte_check!(ret; ok => { data })
+    }
+
+    #[cfg(not(dpdk_ver_16_07))]
+    fn regs(&self) -> Result<Vec<u8>> {
+        let reg_info = try!(self.reg_info());
+        let mut data = vec![0u8; (reg_info.length * reg_info.width) as usize];
+        let mut info = ffi::Struct_rte_dev_reg_info {
+            data: data.as_mut_ptr() as *mut c_void,
+            offset: 0,
+            length: reg_info.length,
+            version: 0,
+        };
+
+        let ret = unsafe { ffi::rte_eth_dev_get_reg_info(self.0, &mut info) };
+
+        rte_check!(ret; ok => { data })
+    }
+
+    fn eeprom_length(&self) -> Result<u32> {
+        let len = unsafe { ffi::rte_eth_dev_get_eeprom_length(self.0) };
+
+        rte_check!(len; ok => { len as u32 })
+    }
+
+    fn get_eeprom(&self, offset: u32, length: u32) -> Result<Vec<u8>> {
+        try!(self.check_eeprom_range(offset, length));
+
+        let mut data = vec![0u8; length as usize];
+        let mut info = ffi::Struct_rte_dev_eeprom_info {
+            data: data.as_mut_ptr() as *mut c_void,
+            offset: offset,
+            length: length,
+            magic: 0,
+        };
+
+        let ret = unsafe { ffi::rte_eth_dev_get_eeprom(self.0, &mut info) };
+
+        rte_check!(ret; ok => { data })
+    }
+
+    fn set_eeprom(&self, offset: u32, data: &[u8]) -> Result<&Self> {
+        try!(self.check_eeprom_range(offset, data.len() as u32));
+
+        let mut info = ffi::Struct_rte_dev_eeprom_info {
+            data: data.as_ptr() as *mut c_void,
+            offset: offset,
+            length: data.len() as u32,
+            magic: 0,
+        };
+
+        let ret = unsafe { ffi::rte_eth_dev_set_eeprom(self.0, &mut info) };
+
+        rte_check!(ret; ok => { self })
+    }
+
+    fn rx_intr_enable(&self, queue_id: QueueId) -> Result<&Self> {
+        rte_check!(unsafe { ffi::rte_eth_dev_rx_intr_enable(self.0, queue_id) }; ok => { self })
+    }
+
+    fn rx_intr_disable(&self, queue_id: QueueId) -> Result<&Self> {
+        rte_check!(unsafe { ffi::rte_eth_dev_rx_intr_disable(self.0, queue_id) }; ok => { self })
+    }
+
+    fn rx_intr_ctl(&self, queue_id: QueueId, epfd: i32, op: IntrCtlOp, data: u64) -> Result<&Self> {
+        rte_check!(unsafe {
+            ffi::rte_eth_dev_rx_intr_ctl_q(self.0, queue_id, epfd, op as i32, data as *mut c_void)
+        }; ok => { self })
+    }
+
+    fn rx_intr_epoll_fd(&self) -> Result<RawFd> {
+        let fd = unsafe { ffi::rte_intr_tls_epfd() };
+
+        rte_check!(fd; ok => { fd })
+    }
+
+    fn supported_ptypes(&self,
+                        ptype_mask: mbuf::PacketTypeFlags)
+                        -> Result<Vec<mbuf::PacketTypeFlags>> {
+        let num = unsafe {
+            ffi::rte_eth_dev_get_supported_ptypes(self.0, ptype_mask.bits, ptr::null_mut(), 0)
+        };
+
+        if num <= 0 {
+            return rte_check!(num; ok => { Vec::new() });
+        }
+
+        let mut ptypes = vec![0u32; num as usize];
+
+        let num = unsafe {
+            ffi::rte_eth_dev_get_supported_ptypes(self.0,
+                                                  ptype_mask.bits,
+                                                  ptypes.as_mut_ptr(),
+                                                  ptypes.len() as i32)
+        };
+
+        rte_check!(num; ok => {
+            ptypes.iter()
+                .take(num as usize)
+                .map(|ptype| mbuf::PacketTypeFlags::from_bits_truncate(*ptype))
+                .collect()
+        })
+    }
+
+    #[cfg(dpdk_ver_19_11)]
+    fn set_ptypes(&self, ptypes: &[mbuf::PacketTypeFlags]) -> Result<&Self> {
+        let mask = ptypes.iter()
+            .fold(mbuf::PacketTypeFlags::empty(), |mask, ptype| mask | ptype.layer_mask());
+        let mut ptypes: Vec<u32> = ptypes.iter().map(|ptype| ptype.bits).collect();
+
+        let ret = unsafe {
+            rte_eth_dev_set_ptypes(self.0 as RawPortId,
+                                   mask.bits,
+                                   ptypes.as_mut_ptr(),
+                                   ptypes.len() as u32)
+        };
+
+        rte_check!(ret; ok => { self })
+    }
+
+    fn add_5tuple_filter(&self, filter: &FiveTupleFilter, filter_id: u16) -> Result<&Self> {
+        let mut filters = NTUPLE_FILTERS.lock().unwrap();
+
+        if filters.contains_key(&(self.0, filter_id)) {
+            return Err(Error::OsError(libc::EEXIST));
+        }
+
+        let mut raw = ffi::Struct_rte_eth_ntuple_filter::from(filter);
+
+        let ret = unsafe {
+            ffi::rte_eth_dev_filter_ctrl(self.0,
+                                         ffi::Enum_rte_filter_type::RTE_ETH_FILTER_NTUPLE,
+                                         ffi::Enum_rte_filter_op::RTE_ETH_FILTER_ADD,
+                                         &mut raw as *mut _ as *mut c_void)
+        };
+
+        rte_check!(ret; ok => {
+            filters.insert((self.0, filter_id), *filter);
+
+            self
+        })
+    }
+
+    fn remove_5tuple_filter(&self, filter_id: u16) -> Result<&Self> {
+        let mut filters = NTUPLE_FILTERS.lock().unwrap();
+
+        let mut raw = match filters.get(&(self.0, filter_id)) {
+            Some(filter) => ffi::Struct_rte_eth_ntuple_filter::from(filter),
+            None => return Err(Error::OsError(libc::ENOENT)),
+        };
+
+        let ret = unsafe {
+            ffi::rte_eth_dev_filter_ctrl(self.0,
+                                         ffi::Enum_rte_filter_type::RTE_ETH_FILTER_NTUPLE,
+                                         ffi::Enum_rte_filter_op::RTE_ETH_FILTER_DELETE,
+                                         &mut raw as *mut _ as *mut c_void)
+        };
+
+        rte_check!(ret; ok => {
+            filters.remove(&(self.0, filter_id));
+
+            self
+        })
+    }
+
+    fn get_5tuple_filter(&self, filter_id: u16) -> Result<FiveTupleFilter> {
+        NTUPLE_FILTERS.lock()
+            .unwrap()
+            .get(&(self.0, filter_id))
+            .cloned()
+            .ok_or(Error::OsError(libc::ENOENT))
+    }
+}
+
+lazy_static! {
+    // The drivers identify a ntuple filter by its content and can't read it back,
+    // so the programmed filters are kept per port and filter id.
+    static ref NTUPLE_FILTERS: Mutex<HashMap<(u8, u16), FiveTupleFilter>> =
+        Mutex::new(HashMap::new());
+
+    // The rate limits per port and VF, which can't be read back either.
+    static ref VF_RATE_LIMITS: Mutex<HashMap<(u8, u16), (u32, u64)>> = Mutex::new(HashMap::new());
+
+    // The mempools of the ports created by `create_null_port()`.
+    static ref NULL_PORT_POOLS: Mutex<HashMap<u8, usize>> = Mutex::new(HashMap::new());
+}
+
+/// A 5-tuple filter which matches the IPv4 packets and steers them to a RX queue.
+///
+/// A mask of all ones compares the field, while a zero mask ignores it.
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FiveTupleFilter {
+    pub src_ip: Ipv4Addr,
+    pub src_ip_mask: u32,
+    pub dst_ip: Ipv4Addr,
+    pub dst_ip_mask: u32,
+    pub src_port: u16,
+    pub src_port_mask: u16,
+    pub dst_port: u16,
+    pub dst_port_mask: u16,
+    /// L4 protocol, e.g. `IPPROTO_TCP`.
+    pub proto: u8,
+    pub proto_mask: u8,
+    /// Priority of the filter, the higher value wins.
+    pub priority: u16,
+    /// Queue assigned to the matched packets.
+    pub queue_id: QueueId,
+}
+
+const RTE_5TUPLE_FLAGS: u16 = (ffi::RTE_NTUPLE_FLAGS_DST_IP | ffi::RTE_NTUPLE_FLAGS_SRC_IP |
+                               ffi::RTE_NTUPLE_FLAGS_DST_PORT |
+                               ffi::RTE_NTUPLE_FLAGS_SRC_PORT |
+                               ffi::RTE_NTUPLE_FLAGS_PROTO) as u16;
+
+impl<'a> From<&'a FiveTupleFilter> for ffi::Struct_rte_eth_ntuple_filter {
+    fn from(f: &'a FiveTupleFilter) -> Self {
+        ffi::Struct_rte_eth_ntuple_filter {
+            flags: RTE_5TUPLE_FLAGS,
+            dst_ip: u32::from(f.dst_ip).to_be(),
+            dst_ip_mask: f.dst_ip_mask,
+            src_ip: u32::from(f.src_ip).to_be(),
+            src_ip_mask: f.src_ip_mask,
+            dst_port: f.dst_port.to_be(),
+            dst_port_mask: f.dst_port_mask,
+            src_port: f.src_port.to_be(),
+            src_port_mask: f.src_port_mask,
+            proto: f.proto,
+            proto_mask: f.proto_mask,
+            tcp_flags: 0,
+            priority: f.priority,
+            queue: f.queue_id,
+        }
+    }
+}
+
+/// The loopback mode of an Ethernet device.
+///
+/// The `lpbk_mode` values are defined by each driver, so the constants identify the modes
+/// and are translated to the value of the driver by `driver_value()`. The generic `MAC`
+/// and `PHY` modes are translated for any driver which supports them, the driver specific
+/// modes only for their driver.
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LoopbackMode(u32);
+
+impl LoopbackMode {
+    /// The loopback is disabled, supported by all the drivers.
+    pub const DISABLED: LoopbackMode = LoopbackMode(0);
+
+    /// The packets are looped back in the MAC of the NIC.
+    pub const MAC: LoopbackMode = LoopbackMode(1);
+
+    /// The packets are looped back in the PHY of the NIC.
+    pub const PHY: LoopbackMode = LoopbackMode(2);
+
+    /// The TX to RX loopback of the ixgbe NICs, `IXGBE_LPBK_TX_RX`.
+    pub const IXGBE_TX_RX: LoopbackMode = LoopbackMode(3);
+
+    /// The MAC loopback of the i40e NICs, `I40E_AQ_LB_MODE_EN`.
+    pub const I40E_MAC: LoopbackMode = LoopbackMode(4);
+
+    /// The loopback modes and their values of the drivers which support it,
+    /// by the driver names before and after DPDK 16.11.
+    const DRIVER_MODES: &'static [(&'static str, &'static str, &'static [(LoopbackMode, u32)])] =
+        &[("rte_ixgbe_pmd",
+           "net_ixgbe",
+           &[(LoopbackMode::IXGBE_TX_RX, 1), (LoopbackMode::MAC, 1)]),
+          ("rte_i40e_pmd", "net_i40e", &[(LoopbackMode::I40E_MAC, 1), (LoopbackMode::MAC, 1)])];
+
+    /// The `lpbk_mode` value of the mode for the driver.
+    ///
+    /// Only `DISABLED` is accepted by the drivers which don't support the loopback.
+    ///
+    pub fn driver_value(&self, driver_name: &str) -> Result<u32> {
+        if *self == LoopbackMode::DISABLED {
+            return Ok(0);
+        }
+
+        LoopbackMode::DRIVER_MODES
+            .iter()
+            .find(|&&(name, alias, _)| driver_name == name || driver_name == alias)
+            .and_then(|&(_, _, modes)| {
+                modes.iter().find(|&&(mode, _)| mode == *self).map(|&(_, value)| value)
+            })
+            .ok_or_else(|| Error::UnsupportedLoopbackMode(driver_name.to_owned(), self.0))
+    }
+
+    /// Check that the mode is supported by the driver.
+    pub fn validate(&self, driver_name: &str) -> Result<()> {
+        self.driver_value(driver_name).map(|_| ())
+    }
+}
+
+pub trait EthDeviceInfo {
+    /// Device Driver name.
+    fn driver_name(&self) -> &str;
+
+    fn pci_dev(&self) -> Option<&mut pci::RawPciDevice>;
+
+    /// The speeds supported by the device, from the slowest to the fastest.
+    fn supported_speeds(&self) -> Vec<LinkSpeed>;
+}
+
+pub type RawEthDeviceInfo = ffi::Struct_rte_eth_dev_info;
+
+impl EthDeviceInfo for RawEthDeviceInfo {
+    #[inline]
+    fn driver_name(&self) -> &str {
+        unsafe { CStr::from_ptr(self.driver_name).to_str().unwrap() }
+    }
+
+    #[inline]
+    fn pci_dev(&self) -> Option<&mut pci::RawPciDevice> {
+        self.pci_dev.as_mut_ref()
+    }
+
+    fn supported_speeds(&self) -> Vec<LinkSpeed> {
+        LinkSpeed::from_bits_truncate(self.speed_capa).speeds()
+    }
+}
+
+/// The information of a RX queue.
+#[derive(Clone, Debug)]
+pub struct RxQueueInfo {
+    /// The mempool used by the queue.
+    pub mempool: mempool::RawMemoryPoolPtr,
+    /// Whether the scattered packets RX is enabled.
+    pub scattered_rx: bool,
+    /// Number of RX descriptors.
+    pub nb_desc: u16,
+    /// The burst mode, `None` if the driver doesn't report it.
+    pub burst_mode: Option<BurstMode>,
+}
+
+/// The information of a TX queue.
+#[derive(Clone, Debug)]
+pub struct TxQueueInfo {
+    /// Start freeing TX buffers if there are less free descriptors than this value.
+    pub tx_free_thresh: u16,
+    /// Drives the setting of RS bit on TXDs.
+    pub tx_rs_thresh: u16,
+    /// The `ETH_TXQ_FLAGS_*` flags of the queue.
+    pub txq_flags: u32,
+    /// Whether the queue is not started with the device.
+    pub tx_deferred_start: bool,
+    /// Number of TX descriptors.
+    pub nb_desc: u16,
+    /// The burst mode, `None` if the driver doesn't report it.
+    pub burst_mode: Option<BurstMode>,
+}
+
+/// An iterator over the information of the configured queues of a port.
+pub struct QueueInfoIter<T> {
+    port: PortId,
+    next: QueueId,
+    count: QueueId,
+    info: fn(&PortId, QueueId) -> Result<T>,
+}
+
+impl<T> Iterator for QueueInfoIter<T> {
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next >= self.count {
+            return None;
+        }
+
+        let queue_id = self.next;
+
+        self.next += 1;
+
+        match (self.info)(&self.port, queue_id) {
+            // the queue is not set up
+            Err(Error::RteError(errno)) if errno == -libc::EINVAL => {
+                self.next = self.count;
+
+                None
+            }
+            res => Some(res),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some((self.count - self.next) as usize))
+    }
+}
+
+/// The burst mode of a RX queue, `None` if the driver or DPDK doesn't report it.
+#[cfg(dpdk_ver_19_11)]
+fn rx_queue_burst_mode(port: &PortId, queue_id: QueueId) -> Option<BurstMode> {
+    port.rx_burst_mode(queue_id).ok()
+}
+
+#[cfg(not(dpdk_ver_19_11))]
+fn rx_queue_burst_mode(_port: &PortId, _queue_id: QueueId) -> Option<BurstMode> {
+    None
+}
+
+/// The burst mode of a TX queue, `None` if the driver or DPDK doesn't report it.
+#[cfg(dpdk_ver_19_11)]
+fn tx_queue_burst_mode(port: &PortId, queue_id: QueueId) -> Option<BurstMode> {
+    port.tx_burst_mode(queue_id).ok()
+}
+
+#[cfg(not(dpdk_ver_19_11))]
+fn tx_queue_burst_mode(_port: &PortId, _queue_id: QueueId) -> Option<BurstMode> {
+    None
+}
+
+/// The owner of an Ethernet device.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EthDevOwner {
+    /// The unique owner id, 0 if the device is not owned.
+    pub id: u64,
+    /// The owner name.
+    pub name: String,
+}
+
+#[cfg(dpdk_ver_18_02)]
+const RTE_ETH_MAX_OWNER_NAME_LEN: usize = 64;
+
+#[cfg(dpdk_ver_18_02)]
+#[repr(C)]
+struct RawEthDevOwner {
+    id: u64,
+    name: [libc::c_char; RTE_ETH_MAX_OWNER_NAME_LEN],
+}
+
+#[cfg(dpdk_ver_18_02)]
+impl<'a> From<&'a RawEthDevOwner> for EthDevOwner {
+    fn from(owner: &'a RawEthDevOwner) -> Self {
+        EthDevOwner {
+            id: owner.id,
+            name: unsafe { CStr::from_ptr(owner.name.as_ptr()).to_string_lossy().into_owned() },
+        }
+    }
+}
+
+/// The burst mode used by a queue of the driver.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BurstMode {
+    /// The raw `RTE_ETH_BURST_FLAG_*` flags.
+    pub flags: u64,
+    /// The driver supplied description, e.g. "Vector AVX2".
+    pub info: String,
+}
+
+#[cfg(dpdk_ver_19_11)]
+const RTE_ETH_BURST_MODE_INFO_SIZE: usize = 1024;
+
+#[cfg(dpdk_ver_19_11)]
+#[repr(C)]
+struct RawBurstMode {
+    flags: u64,
+    info: [libc::c_char; RTE_ETH_BURST_MODE_INFO_SIZE],
+}
+
+#[cfg(dpdk_ver_19_11)]
+impl<'a> From<&'a RawBurstMode> for BurstMode {
+    fn from(mode: &'a RawBurstMode) -> Self {
+        BurstMode {
+            flags: mode.flags,
+            info: unsafe { CStr::from_ptr(mode.info.as_ptr()).to_string_lossy().into_owned() },
+        }
+    }
+}
+
+/// The registers of the drivers are 32 bits, before DPDK 16.07 reports their width.
+#[cfg(not(dpdk_ver_16_07))]
+const REG_WIDTH: u32 = 4;
+
+/// The `rte_dev_reg_info` of DPDK 16.07, which adds the width of the registers.
+#[cfg(dpdk_ver_16_07)]
+#[repr(C)]
+struct RawRegInfo {
+    data: *mut c_void,
+    offset: u32,
+    length: u32,
+    width: u32,
+    version: u32,
+}
+
+#[cfg(dpdk_ver_16_07)]
+impl Default for RawRegInfo {
+    fn default() -> Self {
+        RawRegInfo {
+            data: ptr::null_mut(),
+            offset: 0,
+            length: 0,
+            width: 0,
+            version: 0,
+        }
+    }
+}
+
+/// The size of the device registers.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct EthRegInfo {
+    /// Number of registers.
+    pub length: u32,
+    /// Size of a register in bytes.
+    pub width: u32,
+}
+
+pub type RawDcbInfo = ffi::Struct_rte_eth_dcb_info;
+
+/// The DCB information of an Ethernet device.
+///
+/// The queues of the traffic classes are mapped for each of the `ETH_MAX_VMDQ_POOL`
+/// VMDQ pools, the pool 0 is the only one used without VMDQ.
+///
+pub struct DcbInfo {
+    raw: RawDcbInfo,
+    pfc_enable: bool,
+    rxq: Vec<Vec<(u8, u16, u16)>>,
+    txq: Vec<Vec<(u8, u16, u16)>>,
+}
+
+impl DcbInfo {
+    fn new(raw: RawDcbInfo, pfc_enable: bool) -> DcbInfo {
+        let nb_tcs = cmp::min(raw.nb_tcs as usize, raw.tc_bws.len());
+        let rxq = raw.tc_queue
+            .tc_rxq
+            .iter()
+            .map(|pool| {
+                pool[..nb_tcs]
+                    .iter()
+                    .enumerate()
+                    .map(|(tc, q)| (tc as u8, q.base as u16, q.nb_queue as u16))
+                    .collect()
+            })
+            .collect();
+        let txq = raw.tc_queue
+            .tc_txq
+            .iter()
+            .map(|pool| {
+                pool[..nb_tcs]
+                    .iter()
+                    .enumerate()
+                    .map(|(tc, q)| (tc as u8, q.base as u16, q.nb_queue as u16))
+                    .collect()
+            })
+            .collect();
+
+        DcbInfo {
+            raw: raw,
+            pfc_enable: pfc_enable,
+            rxq: rxq,
+            txq: txq,
+        }
+    }
+
+    /// Number of traffic classes.
+    pub fn nb_tcs(&self) -> u8 {
+        self.raw.nb_tcs
+    }
+
+    /// Whether the priority flow control is enabled.
+    pub fn pfc_enable(&self) -> bool {
+        self.pfc_enable
+    }
+
+    /// The traffic class of each user priority.
+    pub fn prio_tc(&self) -> &[u8] {
+        &self.raw.prio_tc
+    }
+
+    /// The bandwidth percentage of each traffic class.
+    pub fn tc_bandwidth(&self) -> &[u8] {
+        &self.raw.tc_bws[..self.rxq[0].len()]
+    }
+
+    /// The RX queues of each traffic class in the VMDQ pool 0, as `(tc, base_queue, nb_queue)`.
+    pub fn tc_queue_map(&self) -> &[(u8, u16, u16)] {
+        &self.rxq[0]
+    }
+
+    /// The TX queues of each traffic class in the VMDQ pool 0, as `(tc, base_queue, nb_queue)`.
+    pub fn tc_tx_queue_map(&self) -> &[(u8, u16, u16)] {
+        &self.txq[0]
+    }
+
+    /// The RX queues of each traffic class in the VMDQ pool, `None` if the pool is out of range.
+    pub fn pool_tc_queue_map(&self, pool: usize) -> Option<&[(u8, u16, u16)]> {
+        self.rxq.get(pool).map(|q| q.as_slice())
+    }
+
+    /// The TX queues of each traffic class in the VMDQ pool, `None` if the pool is out of range.
+    pub fn pool_tc_tx_queue_map(&self, pool: usize) -> Option<&[(u8, u16, u16)]> {
+        self.txq.get(pool).map(|q| q.as_slice())
+    }
+
+    /// Extract the raw structure from an underlying object.
+    pub fn as_raw(&self) -> &RawDcbInfo {
+        &self.raw
+    }
+}
+
+/// Operations to control the RX queue interrupt event.
+#[repr(i32)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum IntrCtlOp {
+    /// Add the interrupt event to the epoll instance.
+    Add = 1, // RTE_INTR_EVENT_ADD
+    /// Delete the interrupt event from the epoll instance.
+    Del = 2, // RTE_INTR_EVENT_DEL
+}
+
+/// Type of the plugin module EEPROM, as defined by the SFF specifications.
+#[repr(u32)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum EthModuleType {
+    Unknown = 0,
+    /// SFP module, SFF-8079
+    Sff8079 = 1, // RTE_ETH_MODULE_SFF_8079
+    /// SFP+ module with diagnostic monitoring, SFF-8472
+    Sff8472 = 2, // RTE_ETH_MODULE_SFF_8472
+    /// QSFP28 module, SFF-8636
+    Sff8636 = 3, // RTE_ETH_MODULE_SFF_8636
+    /// QSFP+ module, SFF-8436
+    Sff8436 = 4, // RTE_ETH_MODULE_SFF_8436
+}
+
+impl From<u32> for EthModuleType {
+    fn from(v: u32) -> Self {
+        match v {
+            1...4 => unsafe { mem::transmute(v) },
+            _ => EthModuleType::Unknown,
+        }
+    }
+}
+
+/// A structure used to retrieve the type and size of plugin module EEPROM.
+#[derive(Copy, Clone, Debug)]
+pub struct EthModuleInfo {
+    /// Type of the plugin module EEPROM.
+    pub type_: EthModuleType,
+    /// Length of the plugin module EEPROM.
+    pub eeprom_len: u32,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Default)]
+pub struct RawEthModuleInfo {
+    pub type_: u32,
+    pub eeprom_len: u32,
+}
+
+impl From<RawEthModuleInfo> for EthModuleInfo {
+    fn from(info: RawEthModuleInfo) -> Self {
+        EthModuleInfo {
+            type_: EthModuleType::from(info.type_),
+            eeprom_len: info.eeprom_len,
+        }
+    }
+}
+
+pub trait EthDeviceStats {}
+
+pub type RawEthDeviceStats = ffi::Struct_rte_eth_stats;
+
+impl EthDeviceStats for RawEthDeviceStats {}
+
+/// The statistics of a VF accounted by the PF.
+///
+/// The counters wrap around, so the delta between two polls is `current - previous`.
+///
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct VfStats {
+    /// Total number of successfully received packets.
+    pub ipackets: u64,
+    /// Total number of successfully transmitted packets.
+    pub opackets: u64,
+    /// Total number of successfully received bytes.
+    pub ibytes: u64,
+    /// Total number of successfully transmitted bytes.
+    pub obytes: u64,
+    /// Total number of erroneous received packets.
+    pub ierrors: u64,
+    /// Total number of failed transmitted packets.
+    pub oerrors: u64,
+}
+
+/// The packet and bit rates of the statistics over a period.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct StatRates {
+    pub rx_pps: f64,
+    pub tx_pps: f64,
+    pub rx_bps: f64,
+    pub tx_bps: f64,
+}
+
+impl StatRates {
+    fn new(ipackets: u64, opackets: u64, ibytes: u64, obytes: u64, elapsed: Duration) -> Self {
+        let secs = elapsed.as_secs() as f64 + elapsed.subsec_nanos() as f64 / 1e9;
+
+        if secs == 0.0 {
+            return StatRates::default();
+        }
+
+        StatRates {
+            rx_pps: ipackets as f64 / secs,
+            tx_pps: opackets as f64 / secs,
+            rx_bps: ibytes as f64 * 8.0 / secs,
+            tx_bps: obytes as f64 * 8.0 / secs,
+        }
+    }
+}
+
+// Implement the rates, the conversion from the raw statistics
+// and the field-wise wrapping operators of the statistics.
+macro_rules! impl_stats {
+    ($stats:ident { $($field:ident),* }) => {
+        impl $stats {
+            /// The rates of the delta of the statistics over the elapsed time.
+            pub fn rate(delta: &$stats, elapsed: Duration) -> StatRates {
+                StatRates::new(delta.ipackets, delta.opackets, delta.ibytes, delta.obytes, elapsed)
+            }
+        }
+
+        impl From<RawEthDeviceStats> for $stats {
+            fn from(stats: RawEthDeviceStats) -> Self {
+                $stats { $($field: stats.$field),* }
+            }
+        }
+
+        impl Add for $stats {
+            type Output = $stats;
+
+            fn add(self, rhs: $stats) -> $stats {
+                $stats { $($field: self.$field.wrapping_add(rhs.$field)),* }
+            }
+        }
+
+        impl Sub for $stats {
+            type Output = $stats;
+
+            fn sub(self, rhs: $stats) -> $stats {
+                $stats { $($field: self.$field.wrapping_sub(rhs.$field)),* }
+            }
+        }
+    }
+}
+
+impl_stats!(VfStats { ipackets, opackets, ibytes, obytes, ierrors, oerrors });
+
+/// The general statistics of a port, which are accumulated by `StatsAccumulator`.
+///
+/// Besides the counters of `VfStats`, it counts the packets missed by the HW.
+///
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct PortStats {
+    /// Total number of successfully received packets.
+    pub ipackets: u64,
+    /// Total number of successfully transmitted packets.
+    pub opackets: u64,
+    /// Total number of successfully received bytes.
+    pub ibytes: u64,
+    /// Total number of successfully transmitted bytes.
+    pub obytes: u64,
+    /// Total number of packets dropped by the HW because there are no RX buffers.
+    pub imissed: u64,
+    /// Total number of erroneous received packets.
+    pub ierrors: u64,
+    /// Total number of failed transmitted packets.
+    pub oerrors: u64,
+    /// Total number of RX mbuf allocation failures.
+    pub rx_nombuf: u64,
+}
+
+impl_stats!(PortStats { ipackets, opackets, ibytes, obytes, imissed, ierrors, oerrors, rx_nombuf });
+
+/// Accumulate the statistics of a port over the last periods, to compute its average rates.
+///
+/// The periods are kept in a ring buffer, the oldest one is dropped when it is full.
+/// The counters of the port are never reset, each period is the delta
+/// against the snapshot taken at the previous poll.
+///
+pub struct StatsAccumulator {
+    last: Instant,
+    snapshot: Option<PortStats>,
+    periods: VecDeque<(Duration, PortStats)>,
+    capacity: usize,
+}
+
+impl StatsAccumulator {
+    /// Create an accumulator of the last `capacity` periods, starting the first period now.
+    pub fn new(capacity: usize) -> StatsAccumulator {
+        StatsAccumulator {
+            last: Instant::now(),
+            snapshot: None,
+            periods: VecDeque::with_capacity(capacity),
+            capacity: cmp::max(capacity, 1),
+        }
+    }
+
+    /// End the current period with the statistics of the device counted since the last poll.
+    ///
+    /// The first poll only takes the snapshot the next period is counted from,
+    /// the accumulator should always be polled with the same device.
+    ///
+    pub fn poll<T: EthDevice>(&mut self, dev: &T) -> Result<()> {
+        let stats = PortStats::from(try!(dev.stats()));
 
-        unsafe { ffi::rte_eth_link_get_nowait(*self, mem::transmute(&link)) }
+        match self.snapshot {
+            Some(prev) => {
+                let elapsed = self.last.elapsed();
 
-        EthLink {
-            speed: (link & 0xFFFFFFFF) as u32,
-            duplex: (link & (1 << 32)) != 0,
-            autoneg: (link & (1 << 33)) != 0,
-            up: (link & (1 << 34)) != 0,
+                self.push(stats - prev, elapsed);
+            }
+            None => self.last = Instant::now(),
         }
-    }
 
-    fn set_link_up(&self) -> Result<&Self> {
-        rte_check!(unsafe { ffi::rte_eth_dev_set_link_up(*self) }; ok => { self })
-    }
+        self.snapshot = Some(stats);
 
-    fn set_link_down(&self) -> Result<&Self> {
-        rte_check!(unsafe { ffi::rte_eth_dev_set_link_down(*self) }; ok => { self })
+        Ok(())
     }
 
-    fn rx_queue_start(&self, rx_queue_id: QueueId) -> Result<&Self> {
-        rte_check!(unsafe { ffi::rte_eth_dev_rx_queue_start(*self, rx_queue_id) }; ok => { self })
-    }
+    /// End the current period with the statistics counted during its elapsed time.
+    pub fn push(&mut self, delta: PortStats, elapsed: Duration) {
+        if self.periods.len() == self.capacity {
+            self.periods.pop_front();
+        }
 
-    fn rx_queue_stop(&self, rx_queue_id: QueueId) -> Result<&Self> {
-        rte_check!(unsafe { ffi::rte_eth_dev_rx_queue_stop(*self, rx_queue_id) }; ok => { self })
+        self.periods.push_back((elapsed, delta));
+        self.last = Instant::now();
     }
 
-    fn tx_queue_start(&self, tx_queue_id: QueueId) -> Result<&Self> {
-        rte_check!(unsafe { ffi::rte_eth_dev_tx_queue_start(*self, tx_queue_id) }; ok => { self })
+    /// The number of the accumulated periods.
+    pub fn len(&self) -> usize {
+        self.periods.len()
     }
 
-    fn tx_queue_stop(&self, tx_queue_id: QueueId) -> Result<&Self> {
-        rte_check!(unsafe { ffi::rte_eth_dev_tx_queue_stop(*self, tx_queue_id) }; ok => { self })
+    pub fn is_empty(&self) -> bool {
+        self.periods.is_empty()
     }
 
-    fn start(&self) -> Result<&Self> {
-        rte_check!(unsafe { ffi::rte_eth_dev_start(*self) }; ok => { self })
+    /// The statistics and the elapsed time of all the accumulated periods.
+    pub fn total(&self) -> (PortStats, Duration) {
+        self.periods
+            .iter()
+            .fold((PortStats::default(), Duration::from_secs(0)),
+                  |(stats, elapsed), &(period, delta)| (stats + delta, elapsed + period))
     }
 
-    fn stop(&self) -> &Self {
-        unsafe { ffi::rte_eth_dev_stop(*self) };
+    /// The average rates over the accumulated periods.
+    pub fn rates(&self) -> StatRates {
+        let (stats, elapsed) = self.total();
 
-        self
+        PortStats::rate(&stats, elapsed)
     }
+}
 
-    fn close(&self) -> &Self {
-        unsafe { ffi::rte_eth_dev_close(*self) };
+/// An extended statistic of an Ethernet device.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EthXStat {
+    pub name: String,
+    pub value: u64,
+}
 
-        self
-    }
+/// Poll the extended statistics of the ports in a background thread.
+///
+/// The thread is a regular OS thread instead of a lcore,
+/// it is stopped and joined when the poller is dropped.
+///
+pub struct StatsPoller {
+    stop: Option<mpsc::Sender<()>>,
+    handle: Option<thread::JoinHandle<()>>,
+}
 
-    fn rx_burst(&self, queue_id: QueueId, rx_pkts: &mut [mbuf::RawMbufPtr]) -> usize {
-        unsafe {
-            _rte_eth_rx_burst(*self, queue_id, rx_pkts.as_mut_ptr(), rx_pkts.len() as u16) as usize
-        }
-    }
+impl StatsPoller {
+    /// Start polling the ports at the interval, calling `cb` with the statistics of each port.
+    pub fn new<F>(ports: Vec<PortId>, interval: Duration, cb: F) -> StatsPoller
+        where F: Fn(PortId, &[EthXStat]) + Send + 'static
+    {
+        let (stop, stopped) = mpsc::channel();
+
+        let handle = thread::spawn(move || {
+            loop {
+                for port in &ports {
+                    match port.xstats() {
+                        Ok(xstats) => cb(*port, &xstats),
+                        Err(err) => warn!("fail to get xstats of port {}, {}", port, err),
+                    }
+                }
 
-    fn tx_burst(&self, queue_id: QueueId, rx_pkts: &mut [mbuf::RawMbufPtr]) -> usize {
-        unsafe {
-            if rx_pkts.is_empty() {
-                _rte_eth_tx_burst(*self, queue_id, ptr::null_mut(), 0) as usize
-            } else {
-                _rte_eth_tx_burst(*self,
-                                  queue_id,
-                                  rx_pkts.as_mut_ptr(),
-                                  rx_pkts.len() as u16) as usize
+                match stopped.recv_timeout(interval) {
+                    Err(mpsc::RecvTimeoutError::Timeout) => {}
+                    _ => break,
+                }
             }
-        }
-    }
+        });
 
-    fn rx_burst_ex(&self, queue_id: QueueId, rx_pkts: &mut [mbuf::RawMbufPtr], packets: u16) -> u16 {
-        unsafe {
-            _rte_eth_rx_burst(*self, queue_id, rx_pkts.as_mut_ptr(), packets)
+        StatsPoller {
+            stop: Some(stop),
+            handle: Some(handle),
         }
     }
+}
 
-    fn tx_burst_ex(&self, queue_id: QueueId, rx_pkts: &mut [mbuf::RawMbufPtr], packets: u16) -> u16 {
-        unsafe {
-            _rte_eth_tx_burst(*self, queue_id, rx_pkts.as_mut_ptr(), packets)
+impl Drop for StatsPoller {
+    fn drop(&mut self) {
+        self.stop.take();
+
+        if let Some(handle) = self.handle.take() {
+            if handle.join().is_err() {
+                warn!("stats poller thread panicked");
+            }
         }
     }
+}
+
+/// Watch the link state of the ports in a background thread.
+///
+/// The callback is fired when the speed, duplex or up state of a port changes,
+/// including the first time the state of the port is seen.
+///
+pub struct LinkWatcher {
+    states: Arc<Mutex<HashMap<PortId, EthLink>>>,
+    stop: Option<mpsc::Sender<()>>,
+    handle: Option<thread::JoinHandle<()>>,
+}
 
+impl LinkWatcher {
+    /// Start watching the ports at the interval.
+    pub fn new<F>(ports: Vec<PortId>, poll_interval: Duration, on_change: F) -> LinkWatcher
+        where F: Fn(PortId, EthLink) + Send + 'static
+    {
+        let states = Arc::new(Mutex::new(HashMap::new()));
+        let (stop, stopped) = mpsc::channel();
+
+        let handle = {
+            let states = states.clone();
+
+            thread::spawn(move || {
+                loop {
+                    for port in &ports {
+                        let link = port.link_nowait();
+                        let prev = states.lock().unwrap().insert(*port, link);
+
+                        let changed = match prev {
+                            Some(prev) => {
+                                prev.speed != link.speed || prev.duplex != link.duplex ||
+                                prev.up != link.up
+                            }
+                            None => true,
+                        };
+
+                        if changed {
+                            on_change(*port, link);
+                        }
+                    }
+
+                    match stopped.recv_timeout(poll_interval) {
+                        Err(mpsc::RecvTimeoutError::Timeout) => {}
+                        _ => break,
+                    }
+                }
+            })
+        };
 
-    fn set_vf_rxmode(&self, vf: u16, rx_mode: EthVmdqRxMode, on: bool) -> Result<&Self> {
-        rte_check!(unsafe {
-            ffi::rte_eth_dev_set_vf_rxmode(*self, vf, rx_mode.bits, bool_value!(on))
-        }; ok => { self })
+        LinkWatcher {
+            states: states,
+            stop: Some(stop),
+            handle: Some(handle),
+        }
     }
 
-    fn set_vf_tx(&self, vf: u16, on: bool) -> Result<&Self> {
-        rte_check!(unsafe {
-            ffi::rte_eth_dev_set_vf_tx(*self, vf, bool_value!(on))
-        }; ok => { self })
+    /// The link state of the port seen by the last poll.
+    pub fn current_state(&self, port: PortId) -> Option<EthLink> {
+        self.states.lock().unwrap().get(&port).cloned()
     }
+}
 
-    fn set_vf_rx(&self, vf: u16, on: bool) -> Result<&Self> {
-        rte_check!(unsafe {
-            ffi::rte_eth_dev_set_vf_rx(*self, vf, bool_value!(on))
-        }; ok => { self })
+impl Drop for LinkWatcher {
+    fn drop(&mut self) {
+        self.stop.take();
+
+        if let Some(handle) = self.handle.take() {
+            if handle.join().is_err() {
+                warn!("link watcher thread panicked");
+            }
+        }
     }
+}
 
-    fn vlan_offload(&self) -> Result<EthVlanOffloadMode> {
-        let mode = unsafe { ffi::rte_eth_dev_get_vlan_offload(*self) };
+/// The size of the burst buffer of `RxHandler`.
+const RX_HANDLER_BURST_SIZE: usize = 32;
+
+/// The receiving side of a RX queue of a port.
+///
+/// As an iterator, it yields the received packets one at a time, refilling
+/// its burst buffer when it is consumed. The iteration ends when the queue is empty,
+/// and it can be polled again later. The packets left in the buffer are freed when dropped.
+///
+pub struct RxHandler {
+    pub port: PortId,
+    pub queue: QueueId,
+    pkts: [mbuf::RawMbufPtr; RX_HANDLER_BURST_SIZE],
+    pos: usize,
+    len: usize,
+}
 
-        rte_check!(mode; ok => { EthVlanOffloadMode::from_bits_truncate(mode) })
+impl RxHandler {
+    pub fn new(port: PortId, queue: QueueId) -> RxHandler {
+        RxHandler {
+            port: port,
+            queue: queue,
+            pkts: [ptr::null_mut(); RX_HANDLER_BURST_SIZE],
+            pos: 0,
+            len: 0,
+        }
     }
 
-    fn set_vlan_offload(&self, mode: EthVlanOffloadMode) -> Result<&Self> {
-        rte_check!(unsafe {
-            ffi::rte_eth_dev_set_vlan_offload(*self, mode.bits)
-        }; ok => { self })
+    /// Retrieve a burst of input packets from the queue, bypassing the burst buffer.
+    pub fn recv(&self, pkts: &mut [mbuf::RawMbufPtr]) -> usize {
+        self.port.rx_burst(self.queue, pkts)
     }
 }
 
-pub trait EthDeviceInfo {
-    /// Device Driver name.
-    fn driver_name(&self) -> &str;
+impl Iterator for RxHandler {
+    type Item = mbuf::RawMbufPtr;
 
-    fn pci_dev(&self) -> Option<&mut pci::RawPciDevice>;
-}
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pos == self.len {
+            self.pos = 0;
+            self.len = self.port.rx_burst(self.queue, &mut self.pkts);
+        }
 
-pub type RawEthDeviceInfo = ffi::Struct_rte_eth_dev_info;
+        if self.pos < self.len {
+            self.pos += 1;
 
-impl EthDeviceInfo for RawEthDeviceInfo {
-    #[inline]
-    fn driver_name(&self) -> &str {
-        unsafe { CStr::from_ptr(self.driver_name).to_str().unwrap() }
+            Some(self.pkts[self.pos - 1])
+        } else {
+            None
+        }
     }
+}
 
-    #[inline]
-    fn pci_dev(&self) -> Option<&mut pci::RawPciDevice> {
-        self.pci_dev.as_mut_ref()
+impl Drop for RxHandler {
+    fn drop(&mut self) {
+        for m in &self.pkts[self.pos..self.len] {
+            if let Some(m) = unsafe { m.as_mut() } {
+                mbuf::PktMbuf::free(m);
+            }
+        }
     }
 }
 
-pub trait EthDeviceStats {}
+/// The sending side of a TX queue of a port.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TxHandler {
+    pub port: PortId,
+    pub queue: QueueId,
+}
 
-pub type RawEthDeviceStats = ffi::Struct_rte_eth_stats;
+impl TxHandler {
+    pub fn new(port: PortId, queue: QueueId) -> TxHandler {
+        TxHandler {
+            port: port,
+            queue: queue,
+        }
+    }
 
-impl EthDeviceStats for RawEthDeviceStats {}
+    /// Send a burst of output packets to the queue, returns the number of the sent packets.
+    ///
+    /// The packets which are not sent are still owned by the caller.
+    ///
+    pub fn send(&self, pkts: &mut [mbuf::RawMbufPtr]) -> usize {
+        self.port.tx_burst(self.queue, pkts)
+    }
+}
 
 bitflags! {
     /// Definitions used for VMDQ pool rx mode setting
@@ -510,6 +3076,37 @@ bitflags! {
     }
 }
 
+/// The inner or outer VLAN tag.
+pub type VlanType = ffi::Enum_rte_vlan_type;
+
+/// The type of the L2 tunnel.
+///
+/// DPDK only defines the E-tag (802.1BR) tunnel in `rte_eth_tunnel_type`,
+/// the 802.1Q double tagging (QinQ) is configured with the outer VLAN Ether type
+/// and the extended VLAN offload instead, see `EthDevice::enable_qinq_stripping`.
+///
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum L2TunnelType {
+    /// The 802.1BR E-tag, `RTE_L2_TUNNEL_TYPE_E_TAG`.
+    ETag,
+    /// The 802.1Q-in-802.1Q double tagging.
+    Standard802_1Q,
+}
+
+bitflags! {
+    /// The offloads of the L2 tunnel.
+    pub flags L2TunnelOffloadMask: u32 {
+        /// Enable the L2 tunnel.
+        const ETH_L2_TUNNEL_ENABLE_MASK     = 0x00000001,
+        /// Insert the tunnel tag on transmit.
+        const ETH_L2_TUNNEL_INSERTION_MASK  = 0x00000002,
+        /// Strip the tunnel tag on receive.
+        const ETH_L2_TUNNEL_STRIPPING_MASK  = 0x00000004,
+        /// Forward the packets based on the tunnel tag.
+        const ETH_L2_TUNNEL_FORWARDING_MASK = 0x00000008,
+    }
+}
+
 bitflags! {
     /// Definitions used for VLAN Offload functionality
     pub flags EthVlanOffloadMode: i32 {
@@ -662,6 +3259,26 @@ impl Default for EthRssConf {
     }
 }
 
+impl EthRssConf {
+    /// RSS with a symmetric Toeplitz key of the IP, TCP and UDP packets,
+    /// so the both directions of a flow are received by the same queue.
+    pub fn symmetric() -> EthRssConf {
+        let mut key = [0u8; thash::RSS_KEY_LEN];
+
+        key.copy_from_slice(thash::ThashKey::new_symmetric(thash::RSS_KEY_LEN).bytes());
+
+        EthRssConf {
+            key: Some(key),
+            hash: ETH_RSS_IP | ETH_RSS_TCP | ETH_RSS_UDP,
+        }
+    }
+
+    /// Check if the key repeats a 16 bits pattern, which makes the hash symmetric.
+    pub fn is_symmetric(&self) -> bool {
+        self.key.map_or(false, |key| key.iter().zip(key[2..].iter()).all(|(a, b)| a == b))
+    }
+}
+
 #[derive(Default)]
 pub struct RxAdvConf {
     /// Port RSS configuration
@@ -675,6 +3292,147 @@ pub enum TxAdvConf {
 
 }
 
+/// The flow director mode.
+#[repr(u32)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum FdirMode {
+    /// Disable the flow director.
+    Disable = 0, // RTE_FDIR_MODE_NONE
+    /// Match the hash signature of the flows.
+    Signature = 1, // RTE_FDIR_MODE_SIGNATURE
+    /// Match the fields of the flows.
+    Perfect = 2, // RTE_FDIR_MODE_PERFECT
+    /// Match the MAC address and VLAN of the flows.
+    PerfectMac = 3, // RTE_FDIR_MODE_PERFECT_MAC_VLAN
+    /// Match the tunneled flows.
+    PerfectTunnel = 4, // RTE_FDIR_MODE_PERFECT_TUNNEL
+}
+
+/// The memory reserved for the flow director filters.
+#[repr(u32)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum FdirPballocSize {
+    Size64K = 0, // RTE_FDIR_PBALLOC_64K
+    Size128K = 1, // RTE_FDIR_PBALLOC_128K
+    Size256K = 2, // RTE_FDIR_PBALLOC_256K
+}
+
+/// How the flow director reports the matched filter in the mbuf.
+#[repr(u32)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum FdirStatusMode {
+    /// Never report the matched filter.
+    NoReport = 0, // RTE_FDIR_NO_REPORT_STATUS
+    /// Report the matched filter only for the matched packets.
+    Report = 1, // RTE_FDIR_REPORT_STATUS
+    /// Always report the status of the flow director.
+    ReportAlways = 2, // RTE_FDIR_REPORT_STATUS_ALWAYS
+}
+
+pub type FdirPayloadType = ffi::Enum_rte_eth_payload_type;
+
+/// The offsets of the flexible payload bytes extracted from a layer of the packets.
+#[derive(Copy, Clone)]
+pub struct FdirFlexPayload {
+    pub payload_type: FdirPayloadType,
+    pub src_offset: [u16; 16],
+}
+
+/// The mask of the flexible payload bytes for a flow type.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct FdirFlexMask {
+    pub flow_type: u16,
+    pub mask: [u8; 16],
+}
+
+/// The flexible payload of the flow director filters.
+///
+/// At most 8 payloads and 18 masks are used.
+///
+#[derive(Clone, Default)]
+pub struct FdirFlexConf {
+    pub payloads: Vec<FdirFlexPayload>,
+    pub masks: Vec<FdirFlexMask>,
+}
+
+impl From<FdirFlexConf> for ffi::Struct_rte_eth_fdir_flex_conf {
+    fn from(c: FdirFlexConf) -> Self {
+        let mut conf = ffi::Struct_rte_eth_fdir_flex_conf::default();
+
+        for (cfg, payload) in conf.flex_set.iter_mut().zip(c.payloads.iter()) {
+            cfg._type = payload.payload_type;
+            cfg.src_offset = payload.src_offset;
+
+            conf.nb_payloads += 1;
+        }
+
+        for (mask, m) in conf.flex_mask.iter_mut().zip(c.masks.iter()) {
+            mask.flow_type = m.flow_type;
+            mask.mask = m.mask;
+
+            conf.nb_flexmasks += 1;
+        }
+
+        conf
+    }
+}
+
+/// The flow director configuration of `EthConf`.
+#[derive(Clone)]
+pub struct FdirConf {
+    pub mode: FdirMode,
+    pub pballoc: FdirPballocSize,
+    pub status: FdirStatusMode,
+    /// The RX queue of the packets matching the drop filters.
+    pub drop_queue: u8,
+    pub flex_conf: Option<FdirFlexConf>,
+}
+
+impl Default for FdirConf {
+    fn default() -> Self {
+        FdirConf {
+            mode: FdirMode::Disable,
+            pballoc: FdirPballocSize::Size64K,
+            status: FdirStatusMode::NoReport,
+            drop_queue: 0,
+            flex_conf: None,
+        }
+    }
+}
+
+impl From<FdirConf> for ffi::Struct_rte_fdir_conf {
+    fn from(c: FdirConf) -> Self {
+        let mut conf = ffi::Struct_rte_fdir_conf::default();
+
+        conf.mode = match c.mode {
+            FdirMode::Disable => ffi::Enum_rte_fdir_mode::RTE_FDIR_MODE_NONE,
+            FdirMode::Signature => ffi::Enum_rte_fdir_mode::RTE_FDIR_MODE_SIGNATURE,
+            FdirMode::Perfect => ffi::Enum_rte_fdir_mode::RTE_FDIR_MODE_PERFECT,
+            FdirMode::PerfectMac => ffi::Enum_rte_fdir_mode::RTE_FDIR_MODE_PERFECT_MAC_VLAN,
+            FdirMode::PerfectTunnel => ffi::Enum_rte_fdir_mode::RTE_FDIR_MODE_PERFECT_TUNNEL,
+        };
+        conf.pballoc = match c.pballoc {
+            FdirPballocSize::Size64K => ffi::Enum_rte_fdir_pballoc_type::RTE_FDIR_PBALLOC_64K,
+            FdirPballocSize::Size128K => ffi::Enum_rte_fdir_pballoc_type::RTE_FDIR_PBALLOC_128K,
+            FdirPballocSize::Size256K => ffi::Enum_rte_fdir_pballoc_type::RTE_FDIR_PBALLOC_256K,
+        };
+        conf.status = match c.status {
+            FdirStatusMode::NoReport => ffi::Enum_rte_fdir_status_mode::RTE_FDIR_NO_REPORT_STATUS,
+            FdirStatusMode::Report => ffi::Enum_rte_fdir_status_mode::RTE_FDIR_REPORT_STATUS,
+            FdirStatusMode::ReportAlways => {
+                ffi::Enum_rte_fdir_status_mode::RTE_FDIR_REPORT_STATUS_ALWAYS
+            }
+        };
+        conf.drop_queue = c.drop_queue;
+
+        if let Some(flex_conf) = c.flex_conf {
+            conf.flex_conf = flex_conf.into();
+        }
+
+        conf
+    }
+}
+
 /// Device supported speeds bitmap flags
 bitflags! {
     pub flags LinkSpeed: u32 {
@@ -709,7 +3467,34 @@ impl Default for LinkSpeed {
     }
 }
 
-#[derive(Default)]
+impl LinkSpeed {
+    /// The speeds of the `ETH_LINK_SPEED_*` flags, in Mbps.
+    const SPEEDS: &'static [(LinkSpeed, u32)] = &[(ETH_LINK_SPEED_10M_HD, 10),
+                                                  (ETH_LINK_SPEED_10M, 10),
+                                                  (ETH_LINK_SPEED_100M_HD, 100),
+                                                  (ETH_LINK_SPEED_100M, 100),
+                                                  (ETH_LINK_SPEED_1G, 1000),
+                                                  (ETH_LINK_SPEED_2_5G, 2500),
+                                                  (ETH_LINK_SPEED_5G, 5000),
+                                                  (ETH_LINK_SPEED_10G, 10000),
+                                                  (ETH_LINK_SPEED_20G, 20000),
+                                                  (ETH_LINK_SPEED_25G, 25000),
+                                                  (ETH_LINK_SPEED_40G, 40000),
+                                                  (ETH_LINK_SPEED_50G, 50000),
+                                                  (ETH_LINK_SPEED_56G, 56000),
+                                                  (ETH_LINK_SPEED_100G, 100000)];
+
+    /// The speed in Mbps of a single speed flag, `None` for the other values.
+    pub fn to_mbps(&self) -> Option<u32> {
+        Self::SPEEDS.iter().find(|&&(speed, _)| speed == *self).map(|&(_, mbps)| mbps)
+    }
+
+    /// Split the bitmap into the single speed flags, from the slowest to the fastest.
+    pub fn speeds(&self) -> Vec<LinkSpeed> {
+        Self::SPEEDS.iter().map(|&(speed, _)| speed).filter(|speed| self.contains(*speed)).collect()
+    }
+}
+
 pub struct EthConf {
     /// bitmap of ETH_LINK_SPEED_XXX of speeds to be used.
     ///
@@ -735,10 +3520,51 @@ pub struct EthConf {
     /// Currently,Priority Flow Control(PFC) are supported,
     /// if DCB with PFC is needed, and the variable must be set ETH_DCB_PFC_SUPPORT.
     pub dcb_capability_en: u32,
-    pub fdir_conf: Option<ffi::Struct_rte_fdir_conf>,
+    /// Flow director configuration.
+    pub fdir_conf: Option<FdirConf>,
     pub intr_conf: Option<ffi::Struct_rte_intr_conf>,
 }
 
+impl EthConf {
+    /// An empty configuration, which leaves every feature to the driver defaults.
+    pub fn zeroed() -> EthConf {
+        EthConf {
+            link_speeds: ETH_LINK_SPEED_AUTONEG,
+            rxmode: None,
+            txmode: None,
+            lpbk_mode: 0,
+            rx_adv_conf: None,
+            tx_adv_conf: None,
+            dcb_capability_en: 0,
+            fdir_conf: None,
+            intr_conf: None,
+        }
+    }
+}
+
+impl Default for EthConf {
+    /// Autonegotiate the link, with the checksum and VLAN stripping offloads enabled on RX
+    /// and a single TX queue mode.
+    ///
+    /// The VLAN filter is left disabled, since its table is empty
+    /// and the tagged packets would be dropped.
+    ///
+    fn default() -> Self {
+        EthConf {
+            rxmode: Some(EthRxMode {
+                hw_ip_checksum: true,
+                hw_vlan_strip: true,
+                ..Default::default()
+            }),
+            txmode: Some(EthTxMode {
+                mq_mode: ffi::Enum_rte_eth_tx_mq_mode::ETH_MQ_TX_NONE,
+                ..Default::default()
+            }),
+            ..EthConf::zeroed()
+        }
+    }
+}
+
 pub type RawEthConfPtr = *const ffi::Struct_rte_eth_conf;
 
 pub struct RawEthConf(RawEthConfPtr);
@@ -791,6 +3617,12 @@ impl<'a> From<&'a EthConf> for RawEthConf {
                 }
             }
 
+            if let Some(ref fdir_conf) = c.fdir_conf {
+                let fdir_conf = ffi::Struct_rte_fdir_conf::from(fdir_conf.clone());
+
+                _rte_eth_conf_set_fdir_conf(conf, &fdir_conf);
+            }
+
             RawEthConf(conf)
         }
     }
@@ -820,6 +3652,7 @@ pub trait TxBuffer {
 }
 
 /// Initialize default values for buffered transmitting
+#[deprecated(note = "use `alloc_buffer_owned`, which frees the buffer when dropped")]
 pub fn alloc_buffer(size: usize, socket_id: i32) -> Result<RawTxBufferPtr> {
     unsafe {
         let p = malloc::zmalloc_socket("tx_buffer",
@@ -841,6 +3674,105 @@ pub fn alloc_buffer(size: usize, socket_id: i32) -> Result<RawTxBufferPtr> {
     }
 }
 
+/// The handler of the buffered packets which cannot be sent, it owns the unsent packets.
+pub type TxBufferErrorHandler = Box<dyn FnMut(&mut [mbuf::RawMbufPtr]) + Send>;
+
+/// A TX buffer allocated by `alloc_buffer_owned`, which is freed when dropped.
+///
+/// The packets left in the buffer are not sent when it is dropped.
+///
+pub struct OwnedTxBuffer {
+    raw: RawTxBufferPtr,
+    handler: Option<Box<TxBufferErrorHandler>>,
+}
+
+impl OwnedTxBuffer {
+    pub fn as_raw(&self) -> RawTxBufferPtr {
+        self.raw
+    }
+
+    /// Release the ownership of the buffer, which must be freed with `TxBuffer::free`.
+    ///
+    /// The error handler is dropped, and the buffer falls back to drop the unsent packets.
+    ///
+    pub fn into_raw(mut self) -> RawTxBufferPtr {
+        let raw = self.raw;
+
+        if self.handler.is_some() {
+            unsafe {
+                ffi::rte_eth_tx_buffer_set_err_callback(raw,
+                                                        Some(ffi::rte_eth_tx_buffer_drop_callback),
+                                                        ptr::null_mut());
+            }
+        }
+
+        self.raw = ptr::null_mut();
+
+        raw
+    }
+
+    /// Handle the buffered packets which cannot be sent with the closure.
+    pub fn set_err_handler<F>(&mut self, handler: F) -> Result<&mut Self>
+        where F: FnMut(&mut [mbuf::RawMbufPtr]) + Send + 'static
+    {
+        let mut handler: Box<TxBufferErrorHandler> = Box::new(Box::new(handler));
+
+        let ret = unsafe {
+            ffi::rte_eth_tx_buffer_set_err_callback(self.raw,
+                                                    Some(tx_buffer_error_handler),
+                                                    &mut *handler as *mut TxBufferErrorHandler
+                                                        as *mut c_void)
+        };
+
+        rte_check!(ret; ok => {
+            self.handler = Some(handler);
+
+            self
+        })
+    }
+}
+
+unsafe extern "C" fn tx_buffer_error_handler(unsent: *mut mbuf::RawMbufPtr,
+                                             count: u16,
+                                             userdata: *mut c_void) {
+    let handler = &mut *(userdata as *mut TxBufferErrorHandler);
+
+    handler(slice::from_raw_parts_mut(unsent, count as usize))
+}
+
+impl Drop for OwnedTxBuffer {
+    fn drop(&mut self) {
+        if !self.raw.is_null() {
+            malloc::free(self.raw as *mut c_void)
+        }
+    }
+}
+
+impl Deref for OwnedTxBuffer {
+    type Target = RawTxBuffer;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { &*self.raw }
+    }
+}
+
+impl DerefMut for OwnedTxBuffer {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { &mut *self.raw }
+    }
+}
+
+/// Allocate a TX buffer of `size` packets on the socket, which is freed when dropped.
+pub fn alloc_buffer_owned(size: usize, socket_id: i32) -> Result<OwnedTxBuffer> {
+    #[allow(deprecated)]
+    let raw = try!(alloc_buffer(size, socket_id));
+
+    Ok(OwnedTxBuffer {
+        raw: raw,
+        handler: None,
+    })
+}
+
 impl TxBuffer for RawTxBuffer {
     fn free(&mut self) {
         malloc::free(self as RawTxBufferPtr as *mut c_void);
@@ -887,6 +3819,13 @@ extern "C" {
                          nb_pkts: libc::uint16_t)
                          -> libc::uint16_t;
 
+    #[cfg(dpdk_ver_17_02)]
+    fn _rte_eth_tx_prepare(port_id: libc::uint8_t,
+                           queue_id: libc::uint16_t,
+                           tx_pkts: *mut mbuf::RawMbufPtr,
+                           nb_pkts: libc::uint16_t)
+                           -> libc::uint16_t;
+
     fn _rte_eth_conf_new() -> RawEthConfPtr;
 
     fn _rte_eth_conf_free(conf: RawEthConfPtr);
@@ -914,5 +3853,106 @@ extern "C" {
                                   rss_key_len: libc::uint8_t,
                                   rss_hf: libc::uint64_t);
 
+    fn _rte_eth_conf_set_fdir_conf(conf: RawEthConfPtr,
+                                   fdir_conf: *const ffi::Struct_rte_fdir_conf);
+
     fn _rte_eth_tx_buffer_size(size: libc::size_t) -> libc::size_t;
+
+    fn _rte_eth_dev_started(port_id: libc::uint8_t) -> libc::c_int;
+
+    #[cfg(dpdk_ver_17_05)]
+    fn _rte_eth_dev_mtu_range(port_id: libc::uint8_t,
+                              min_mtu: *mut libc::uint16_t,
+                              max_mtu: *mut libc::uint16_t)
+                              -> libc::c_int;
+
+    fn _rte_eth_dev_mac_addrs(port_id: libc::uint8_t,
+                              addrs: *mut ffi::Struct_ether_addr,
+                              n: libc::uint32_t)
+                              -> libc::c_int;
+
+    fn _rte_eth_dev_dcb_capability(port_id: libc::uint8_t) -> libc::uint32_t;
+
+    #[cfg(dpdk_ver_19_11)]
+    fn rte_eth_rx_burst_mode_get(port_id: RawPortId,
+                                 queue_id: libc::uint16_t,
+                                 mode: *mut RawBurstMode)
+                                 -> libc::c_int;
+
+    #[cfg(dpdk_ver_19_11)]
+    fn rte_eth_tx_burst_mode_get(port_id: RawPortId,
+                                 queue_id: libc::uint16_t,
+                                 mode: *mut RawBurstMode)
+                                 -> libc::c_int;
+
+    #[cfg(dpdk_ver_17_02)]
+    fn rte_eth_tx_done_cleanup(port_id: RawPortId,
+                               queue_id: libc::uint16_t,
+                               free_cnt: libc::uint32_t)
+                               -> libc::c_int;
+
+    #[cfg(dpdk_ver_18_05)]
+    fn rte_eth_dev_get_module_info(port_id: RawPortId,
+                                   modinfo: *mut RawEthModuleInfo)
+                                   -> libc::c_int;
+
+    #[cfg(dpdk_ver_18_05)]
+    fn rte_eth_dev_get_module_eeprom(port_id: RawPortId,
+                                     info: *mut ffi::Struct_rte_dev_eeprom_info)
+                                     -> libc::c_int;
+
+    #[cfg(dpdk_ver_16_07)]
+    fn rte_eth_dev_get_reg_info(port_id: RawPortId, info: *mut RawRegInfo) -> libc::c_int;
+
+    #[cfg(dpdk_ver_18_02)]
+    fn rte_eth_dev_reset(port_id: RawPortId) -> libc::c_int;
+
+    #[cfg(dpdk_ver_19_11)]
+    fn rte_eth_dev_set_ptypes(port_id: RawPortId,
+                              ptype_mask: libc::uint32_t,
+                              set_ptypes: *mut libc::uint32_t,
+                              num: libc::c_uint)
+                              -> libc::c_int;
+
+
+    fn _rte_eth_dev_nb_rx_queues(port_id: libc::uint8_t) -> libc::c_int;
+
+    fn _rte_eth_dev_nb_tx_queues(port_id: libc::uint8_t) -> libc::c_int;
+
+    #[cfg(dpdk_ver_18_02)]
+    fn rte_eth_dev_pool_ops_supported(port_id: RawPortId,
+                                      pool: *const c_char)
+                                      -> libc::c_int;
+
+    fn rte_eth_dev_get_port_by_name(name: *const c_char,
+                                    port_id: *mut RawPortId)
+                                    -> libc::c_int;
+
+    fn rte_eth_dev_get_name_by_port(port_id: RawPortId, name: *mut c_char) -> libc::c_int;
+
+    #[cfg(dpdk_ver_18_02)]
+    fn rte_eth_dev_owner_new(owner_id: *mut libc::uint64_t) -> libc::c_int;
+
+    #[cfg(dpdk_ver_18_02)]
+    fn rte_eth_dev_owner_set(port_id: RawPortId,
+                             owner: *const RawEthDevOwner)
+                             -> libc::c_int;
+
+    #[cfg(dpdk_ver_18_02)]
+    fn rte_eth_dev_owner_unset(port_id: RawPortId, owner_id: libc::uint64_t) -> libc::c_int;
+
+    #[cfg(dpdk_ver_18_02)]
+    fn rte_eth_dev_owner_get(port_id: RawPortId, owner: *mut RawEthDevOwner) -> libc::c_int;
+
+    #[cfg(dpdk_ver_17_02)]
+    fn rte_pmd_ixgbe_set_vf_mac_addr(port: RawPortId,
+                                     vf: libc::uint16_t,
+                                     mac_addr: *mut ffi::Struct_ether_addr)
+                                     -> libc::c_int;
+
+    #[cfg(dpdk_ver_17_02)]
+    fn rte_pmd_i40e_get_vf_stats(port: RawPortId,
+                                 vf_id: libc::uint16_t,
+                                 stats: *mut RawEthDeviceStats)
+                                 -> libc::c_int;
 }
@@ -208,6 +208,79 @@ pub const ETHER_TYPE_SLOW_BE: u16 = rte_cpu_to_be_16!(ffi::ETHER_TYPE_SLOW as u1
 /// Transparent Ethernet Bridging.
 pub const ETHER_TYPE_TEB_BE: u16 = rte_cpu_to_be_16!(ffi::ETHER_TYPE_TEB as u16);
 
+/// The common Ethernet frame types, in the host byte order.
+#[repr(u16)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum EtherType {
+    /// IPv4 Protocol.
+    Ipv4 = 0x0800,
+    /// Arp Protocol.
+    Arp = 0x0806,
+    /// Reverse Arp Protocol.
+    Rarp = 0x8035,
+    /// IEEE 802.1Q VLAN tagging.
+    Vlan = 0x8100,
+    /// IPv6 Protocol.
+    Ipv6 = 0x86DD,
+    /// Slow protocols (LACP and Marker).
+    Slow = 0x8809,
+    /// MPLS unicast.
+    Mpls = 0x8847,
+    /// MPLS multicast.
+    MplsMcast = 0x8848,
+    /// IEEE 802.1ad QinQ tagging.
+    QinQ = 0x88A8,
+    /// Link Layer Discovery Protocol.
+    Lldp = 0x88CC,
+    /// IEEE 802.1AS 1588 Precise Time Protocol.
+    Ptp = 0x88F7,
+    /// FCoE Initialization Protocol.
+    FcoeInit = 0x8914,
+    /// Legacy QinQ tagging.
+    Qinq1 = 0x9100,
+    /// Transparent Ethernet Bridging.
+    Teb = 0x6558,
+}
+
+impl EtherType {
+    /// Convert a frame type in the host byte order.
+    pub fn from_u16(v: u16) -> Option<EtherType> {
+        match v {
+            0x0800 => Some(EtherType::Ipv4),
+            0x0806 => Some(EtherType::Arp),
+            0x8035 => Some(EtherType::Rarp),
+            0x8100 => Some(EtherType::Vlan),
+            0x86DD => Some(EtherType::Ipv6),
+            0x8809 => Some(EtherType::Slow),
+            0x8847 => Some(EtherType::Mpls),
+            0x8848 => Some(EtherType::MplsMcast),
+            0x88A8 => Some(EtherType::QinQ),
+            0x88CC => Some(EtherType::Lldp),
+            0x88F7 => Some(EtherType::Ptp),
+            0x8914 => Some(EtherType::FcoeInit),
+            0x9100 => Some(EtherType::Qinq1),
+            0x6558 => Some(EtherType::Teb),
+            _ => None,
+        }
+    }
+
+    /// Convert a frame type in the network byte order, e.g. `EtherHdr::ether_type`.
+    pub fn from_be(v: u16) -> Option<EtherType> {
+        EtherType::from_u16(u16::from_be(v))
+    }
+
+    /// The frame type in the network byte order.
+    pub fn to_be(self) -> u16 {
+        (self as u16).to_be()
+    }
+}
+
+impl From<EtherType> for u16 {
+    fn from(t: EtherType) -> Self {
+        t as u16
+    }
+}
+
 /// Ethernet header: Contains the destination address, source address and frame type.
 pub type EtherHdr = ffi::Struct_ether_hdr;
 
@@ -283,4 +356,19 @@ mod tests {
         assert!(rand_addr.is_local_admin());
         assert!(rand_addr.is_valid());
     }
+
+    #[test]
+    fn test_ether_type() {
+        assert_eq!(EtherType::from_u16(0x0800), Some(EtherType::Ipv4));
+        assert_eq!(EtherType::from_u16(0x88A8), Some(EtherType::QinQ));
+        assert_eq!(EtherType::from_u16(0x1234), None);
+
+        assert_eq!(u16::from(EtherType::Ipv6), 0x86DD);
+        assert_eq!(EtherType::Ipv4.to_be(), ETHER_TYPE_IPV4_BE);
+        assert_eq!(EtherType::from_be(ETHER_TYPE_ARP_BE), Some(EtherType::Arp));
+
+        let t: u16 = EtherType::Vlan.into();
+
+        assert_eq!(t, 0x8100);
+    }
 }
@@ -0,0 +1,498 @@
+use std::mem;
+use std::ptr;
+use std::ffi::CStr;
+
+use libc;
+
+use errors::Result;
+use ethdev;
+use mbuf;
+
+pub type EventDevId = u8;
+pub type EventQueueId = u8;
+pub type EventPortId = u8;
+
+/// Get the total number of event devices that have been successfully initialised.
+pub fn count() -> u8 {
+    unsafe { rte_event_dev_count() }
+}
+
+/// Get the device identifier for the named event device.
+pub fn get_dev_id(name: &str) -> Result<EventDevId> {
+    let dev_id = unsafe { rte_event_dev_get_dev_id(try!(to_cptr!(name))) };
+
+    rte_check!(dev_id; ok => { dev_id as EventDevId })
+}
+
+/// Event device information
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct EventDevInfo {
+    pub driver_name: *const libc::c_char,
+    pub dev: *mut libc::c_void,
+    pub min_dequeue_timeout_ns: u32,
+    pub max_dequeue_timeout_ns: u32,
+    pub dequeue_timeout_ns: u32,
+    pub max_event_queues: u8,
+    pub max_event_queue_flows: u32,
+    pub max_event_queue_priority_levels: u8,
+    pub max_event_priority_levels: u8,
+    pub max_event_ports: u8,
+    pub max_event_port_dequeue_depth: u8,
+    pub max_event_port_enqueue_depth: u32,
+    pub max_num_events: i32,
+    pub event_dev_cap: u32,
+}
+
+impl EventDevInfo {
+    pub fn driver_name(&self) -> &str {
+        unsafe { CStr::from_ptr(self.driver_name).to_str().unwrap() }
+    }
+}
+
+/// Override the per-dequeue timeout with the `timeout_ticks` of `dequeue_burst`.
+pub const RTE_EVENT_DEV_CFG_PER_DEQUEUE_TIMEOUT: u32 = 1 << 0;
+
+/// Event device configuration structure
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default)]
+pub struct EventDevConf {
+    pub dequeue_timeout_ns: u32,
+    pub nb_events_limit: i32,
+    pub nb_event_queues: u8,
+    pub nb_event_ports: u8,
+    pub nb_event_queue_flows: u32,
+    pub nb_event_port_dequeue_depth: u32,
+    pub nb_event_port_enqueue_depth: u32,
+    pub event_dev_cfg: u32,
+}
+
+/// Allow events of any schedule type to be enqueued to the queue.
+pub const RTE_EVENT_QUEUE_CFG_ALL_TYPES: u32 = 1 << 0;
+/// The queue is linked to a single event port.
+pub const RTE_EVENT_QUEUE_CFG_SINGLE_LINK: u32 = 1 << 1;
+
+/// Event queue configuration structure
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default)]
+pub struct EventQueueConf {
+    pub nb_atomic_flows: u32,
+    pub nb_atomic_order_sequences: u32,
+    pub event_queue_cfg: u32,
+    pub schedule_type: u8,
+    pub priority: u8,
+}
+
+/// Event port configuration structure
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default)]
+pub struct EventPortConf {
+    pub new_event_threshold: i32,
+    pub dequeue_depth: u16,
+    pub enqueue_depth: u16,
+    pub disable_implicit_release: u8,
+}
+
+/// Event scheduling types
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum SchedType {
+    Ordered = 0, // RTE_SCHED_TYPE_ORDERED
+    Atomic = 1, // RTE_SCHED_TYPE_ATOMIC
+    Parallel = 2, // RTE_SCHED_TYPE_PARALLEL
+}
+
+/// Event operations
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum EventOp {
+    New = 0, // RTE_EVENT_OP_NEW
+    Forward = 1, // RTE_EVENT_OP_FORWARD
+    Release = 2, // RTE_EVENT_OP_RELEASE
+}
+
+/// Event types, identifying the origin of the event
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum EventType {
+    EthDev = 0, // RTE_EVENT_TYPE_ETHDEV
+    CryptoDev = 1, // RTE_EVENT_TYPE_CRYPTODEV
+    Timer = 2, // RTE_EVENT_TYPE_TIMER
+    Cpu = 3, // RTE_EVENT_TYPE_CPU
+    EthRxAdapter = 4, // RTE_EVENT_TYPE_ETH_RX_ADAPTER
+}
+
+/// The generic `rte_event` structure to hold the event attributes for dequeue and enqueue.
+///
+/// The first word packs the event attributes as C bitfields,
+/// the second word holds the event payload, e.g. a pointer to `rte_mbuf`.
+///
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Event {
+    pub event: u64,
+    pub u64: u64,
+}
+
+macro_rules! event_field {
+    ($get:ident, $set:ident, $ty:ty, $shift:expr, $bits:expr) => (
+        pub fn $get(&self) -> $ty {
+            ((self.event >> $shift) & ((1 << $bits) - 1)) as $ty
+        }
+
+        pub fn $set(&mut self, v: $ty) -> &mut Self {
+            let mask = ((1u64 << $bits) - 1) << $shift;
+
+            self.event = (self.event & !mask) | (((v as u64) << $shift) & mask);
+            self
+        }
+    )
+}
+
+impl Event {
+    event_field!(flow_id, set_flow_id, u32, 0, 20);
+    event_field!(sub_event_type, set_sub_event_type, u8, 20, 8);
+    event_field!(event_type_raw, set_event_type_raw, u8, 28, 4);
+    event_field!(op_raw, set_op_raw, u8, 32, 2);
+    event_field!(sched_type_raw, set_sched_type_raw, u8, 38, 2);
+    event_field!(queue_id, set_queue_id, EventQueueId, 40, 8);
+    event_field!(priority, set_priority, u8, 48, 8);
+
+    pub fn event_type(&self) -> EventType {
+        unsafe { mem::transmute(self.event_type_raw()) }
+    }
+
+    pub fn set_event_type(&mut self, event_type: EventType) -> &mut Self {
+        self.set_event_type_raw(event_type as u8)
+    }
+
+    pub fn op(&self) -> EventOp {
+        unsafe { mem::transmute(self.op_raw()) }
+    }
+
+    pub fn set_op(&mut self, op: EventOp) -> &mut Self {
+        self.set_op_raw(op as u8)
+    }
+
+    pub fn sched_type(&self) -> SchedType {
+        unsafe { mem::transmute(self.sched_type_raw()) }
+    }
+
+    pub fn set_sched_type(&mut self, sched_type: SchedType) -> &mut Self {
+        self.set_sched_type_raw(sched_type as u8)
+    }
+
+    /// The mbuf carried by the event.
+    pub fn mbuf(&self) -> mbuf::RawMbufPtr {
+        self.u64 as mbuf::RawMbufPtr
+    }
+
+    pub fn set_mbuf(&mut self, m: mbuf::RawMbufPtr) -> &mut Self {
+        self.u64 = m as u64;
+        self
+    }
+}
+
+pub trait EventDevice {
+    fn dev_id(&self) -> EventDevId;
+
+    /// Retrieve the contextual information of an event device.
+    fn info(&self) -> Result<EventDevInfo>;
+
+    /// Configure an event device.
+    ///
+    /// This function must be invoked first before any other function in the API.
+    /// This function can also be re-invoked when a device is in the stopped state.
+    ///
+    fn configure(&self, conf: &EventDevConf) -> Result<&Self>;
+
+    /// Retrieve the default configuration information of an event queue.
+    fn queue_default_conf(&self, queue_id: EventQueueId) -> Result<EventQueueConf>;
+
+    /// Allocate and set up an event queue for an event device.
+    fn queue_setup(&self, queue_id: EventQueueId, conf: Option<&EventQueueConf>) -> Result<&Self>;
+
+    /// Retrieve the default configuration information of an event port.
+    fn port_default_conf(&self, port_id: EventPortId) -> Result<EventPortConf>;
+
+    /// Allocate and set up an event port for an event device.
+    fn port_setup(&self, port_id: EventPortId, conf: Option<&EventPortConf>) -> Result<&Self>;
+
+    /// Link multiple source event queues to destination event port.
+    ///
+    /// An empty `queues` links all the configured event queues with the normal priority.
+    ///
+    fn port_link(&self, port_id: EventPortId, queues: &[(EventQueueId, u8)]) -> Result<usize>;
+
+    /// Start an event device.
+    fn start(&self) -> Result<&Self>;
+
+    /// Stop an event device.
+    fn stop(&self) -> &Self;
+
+    /// Close an event device. The device cannot be restarted!
+    fn close(&self) -> Result<&Self>;
+
+    /// Enqueue a burst of events objects on an event port of an event device.
+    fn enqueue_burst(&self, port_id: EventPortId, events: &[Event]) -> usize;
+
+    /// Dequeue a burst of events objects from an event port of an event device.
+    fn dequeue_burst(&self,
+                     port_id: EventPortId,
+                     events: &mut [Event],
+                     timeout_ticks: u64)
+                     -> usize;
+}
+
+impl EventDevice for EventDevId {
+    fn dev_id(&self) -> EventDevId {
+        *self
+    }
+
+    fn info(&self) -> Result<EventDevInfo> {
+        let mut info: EventDevInfo = unsafe { mem::zeroed() };
+
+        rte_check!(unsafe { rte_event_dev_info_get(*self, &mut info) }; ok => { info })
+    }
+
+    fn configure(&self, conf: &EventDevConf) -> Result<&Self> {
+        rte_check!(unsafe { rte_event_dev_configure(*self, conf) }; ok => { self })
+    }
+
+    fn queue_default_conf(&self, queue_id: EventQueueId) -> Result<EventQueueConf> {
+        let mut conf = EventQueueConf::default();
+
+        rte_check!(unsafe {
+            rte_event_queue_default_conf_get(*self, queue_id, &mut conf)
+        }; ok => { conf })
+    }
+
+    fn queue_setup(&self,
+                   queue_id: EventQueueId,
+                   conf: Option<&EventQueueConf>)
+                   -> Result<&Self> {
+        rte_check!(unsafe {
+            rte_event_queue_setup(*self, queue_id, conf.map_or(ptr::null(), |conf| conf as *const _))
+        }; ok => { self })
+    }
+
+    fn port_default_conf(&self, port_id: EventPortId) -> Result<EventPortConf> {
+        let mut conf = EventPortConf::default();
+
+        rte_check!(unsafe {
+            rte_event_port_default_conf_get(*self, port_id, &mut conf)
+        }; ok => { conf })
+    }
+
+    fn port_setup(&self, port_id: EventPortId, conf: Option<&EventPortConf>) -> Result<&Self> {
+        rte_check!(unsafe {
+            rte_event_port_setup(*self, port_id, conf.map_or(ptr::null(), |conf| conf as *const _))
+        }; ok => { self })
+    }
+
+    fn port_link(&self, port_id: EventPortId, queues: &[(EventQueueId, u8)]) -> Result<usize> {
+        let nb_links = if queues.is_empty() {
+            unsafe { rte_event_port_link(*self, port_id, ptr::null(), ptr::null(), 0) }
+        } else {
+            let (queue_ids, priorities): (Vec<u8>, Vec<u8>) = queues.iter().cloned().unzip();
+
+            unsafe {
+                rte_event_port_link(*self,
+                                    port_id,
+                                    queue_ids.as_ptr(),
+                                    priorities.as_ptr(),
+                                    queues.len() as u16)
+            }
+        };
+
+        rte_check!(nb_links; ok => { nb_links as usize })
+    }
+
+    fn start(&self) -> Result<&Self> {
+        rte_check!(unsafe { rte_event_dev_start(*self) }; ok => { self })
+    }
+
+    fn stop(&self) -> &Self {
+        unsafe { rte_event_dev_stop(*self) };
+
+        self
+    }
+
+    fn close(&self) -> Result<&Self> {
+        rte_check!(unsafe { rte_event_dev_close(*self) }; ok => { self })
+    }
+
+    fn enqueue_burst(&self, port_id: EventPortId, events: &[Event]) -> usize {
+        unsafe {
+            _rte_event_enqueue_burst(*self, port_id, events.as_ptr(), events.len() as u16) as usize
+        }
+    }
+
+    fn dequeue_burst(&self,
+                     port_id: EventPortId,
+                     events: &mut [Event],
+                     timeout_ticks: u64)
+                     -> usize {
+        unsafe {
+            _rte_event_dequeue_burst(*self,
+                                     port_id,
+                                     events.as_mut_ptr(),
+                                     events.len() as u16,
+                                     timeout_ticks) as usize
+        }
+    }
+}
+
+/// Use the `flow_id` of `ev` instead of the RSS hash of the mbuf.
+pub const RTE_EVENT_ETH_RX_ADAPTER_QUEUE_FLOW_ID_VALID: u32 = 1;
+
+/// Rx queue configuration of the Rx adapter
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default)]
+pub struct EthRxAdapterQueueConf {
+    pub rx_queue_flags: u32,
+    pub servicing_weight: u16,
+    pub ev: Event,
+}
+
+/// The event ethernet Rx adapter, which moves packets from ethernet Rx queues to an event device.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct EthRxAdapter(u8);
+
+impl EthRxAdapter {
+    /// Create a new ethernet Rx event adapter with the specified identifier.
+    pub fn create(id: u8, dev_id: EventDevId, port_conf: &EventPortConf) -> Result<EthRxAdapter> {
+        let mut port_conf = *port_conf;
+
+        rte_check!(unsafe {
+            rte_event_eth_rx_adapter_create(id, dev_id, &mut port_conf)
+        }; ok => { EthRxAdapter(id) })
+    }
+
+    pub fn id(&self) -> u8 {
+        self.0
+    }
+
+    /// Add receive queue to an event adapter, `None` adds all the Rx queues of the device.
+    pub fn queue_add(&self,
+                     port_id: ethdev::PortId,
+                     rx_queue_id: Option<ethdev::QueueId>,
+                     conf: &EthRxAdapterQueueConf)
+                     -> Result<&Self> {
+        rte_check!(unsafe {
+            rte_event_eth_rx_adapter_queue_add(self.0,
+                                               u8::from(port_id) as u16,
+                                               rx_queue_id.map_or(-1, |id| id as i32),
+                                               conf)
+        }; ok => { self })
+    }
+
+    /// Delete receive queue from an event adapter, `None` deletes all the Rx queues of the device.
+    pub fn queue_del(&self,
+                     port_id: ethdev::PortId,
+                     rx_queue_id: Option<ethdev::QueueId>)
+                     -> Result<&Self> {
+        rte_check!(unsafe {
+            rte_event_eth_rx_adapter_queue_del(self.0,
+                                               u8::from(port_id) as u16,
+                                               rx_queue_id.map_or(-1, |id| id as i32))
+        }; ok => { self })
+    }
+
+    /// Start ethernet Rx event adapter
+    pub fn start(&self) -> Result<&Self> {
+        rte_check!(unsafe { rte_event_eth_rx_adapter_start(self.0) }; ok => { self })
+    }
+
+    /// Stop ethernet Rx event adapter
+    pub fn stop(&self) -> Result<&Self> {
+        rte_check!(unsafe { rte_event_eth_rx_adapter_stop(self.0) }; ok => { self })
+    }
+
+    /// Free an event adapter
+    pub fn free(self) -> Result<()> {
+        let ret = unsafe { rte_event_eth_rx_adapter_free(self.0) };
+
+        rte_check!(ret)
+    }
+}
+
+extern "C" {
+    fn rte_event_dev_count() -> libc::uint8_t;
+
+    fn rte_event_dev_get_dev_id(name: *const libc::c_char) -> libc::c_int;
+
+    fn rte_event_dev_info_get(dev_id: libc::uint8_t, dev_info: *mut EventDevInfo) -> libc::c_int;
+
+    fn rte_event_dev_configure(dev_id: libc::uint8_t,
+                               dev_conf: *const EventDevConf)
+                               -> libc::c_int;
+
+    fn rte_event_queue_default_conf_get(dev_id: libc::uint8_t,
+                                        queue_id: libc::uint8_t,
+                                        queue_conf: *mut EventQueueConf)
+                                        -> libc::c_int;
+
+    fn rte_event_queue_setup(dev_id: libc::uint8_t,
+                             queue_id: libc::uint8_t,
+                             queue_conf: *const EventQueueConf)
+                             -> libc::c_int;
+
+    fn rte_event_port_default_conf_get(dev_id: libc::uint8_t,
+                                       port_id: libc::uint8_t,
+                                       port_conf: *mut EventPortConf)
+                                       -> libc::c_int;
+
+    fn rte_event_port_setup(dev_id: libc::uint8_t,
+                            port_id: libc::uint8_t,
+                            port_conf: *const EventPortConf)
+                            -> libc::c_int;
+
+    fn rte_event_port_link(dev_id: libc::uint8_t,
+                           port_id: libc::uint8_t,
+                           queues: *const libc::uint8_t,
+                           priorities: *const libc::uint8_t,
+                           nb_links: libc::uint16_t)
+                           -> libc::c_int;
+
+    fn rte_event_dev_start(dev_id: libc::uint8_t) -> libc::c_int;
+
+    fn rte_event_dev_stop(dev_id: libc::uint8_t);
+
+    fn rte_event_dev_close(dev_id: libc::uint8_t) -> libc::c_int;
+
+    fn rte_event_eth_rx_adapter_create(id: libc::uint8_t,
+                                       dev_id: libc::uint8_t,
+                                       port_config: *mut EventPortConf)
+                                       -> libc::c_int;
+
+    fn rte_event_eth_rx_adapter_free(id: libc::uint8_t) -> libc::c_int;
+
+    fn rte_event_eth_rx_adapter_queue_add(id: libc::uint8_t,
+                                          eth_dev_id: libc::uint16_t,
+                                          rx_queue_id: libc::int32_t,
+                                          conf: *const EthRxAdapterQueueConf)
+                                          -> libc::c_int;
+
+    fn rte_event_eth_rx_adapter_queue_del(id: libc::uint8_t,
+                                          eth_dev_id: libc::uint16_t,
+                                          rx_queue_id: libc::int32_t)
+                                          -> libc::c_int;
+
+    fn rte_event_eth_rx_adapter_start(id: libc::uint8_t) -> libc::c_int;
+
+    fn rte_event_eth_rx_adapter_stop(id: libc::uint8_t) -> libc::c_int;
+
+    fn _rte_event_enqueue_burst(dev_id: libc::uint8_t,
+                                port_id: libc::uint8_t,
+                                ev: *const Event,
+                                nb_events: libc::uint16_t)
+                                -> libc::uint16_t;
+
+    fn _rte_event_dequeue_burst(dev_id: libc::uint8_t,
+                                port_id: libc::uint8_t,
+                                ev: *mut Event,
+                                nb_events: libc::uint16_t,
+                                timeout_ticks: libc::uint64_t)
+                                -> libc::uint16_t;
+}
@@ -0,0 +1,322 @@
+use std::fmt;
+use std::mem;
+use std::ptr;
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_void};
+
+use libc;
+
+use errors::{Error, Result};
+use memory::SOCKET_ID_ANY;
+use mempool::RawMemoryPoolPtr;
+
+pub const RTE_NODE_NAMESIZE: usize = 64;
+
+const RTE_NODE_ID_INVALID: u32 = ::std::u32::MAX;
+const RTE_GRAPH_ID_INVALID: u16 = ::std::u16::MAX;
+
+bitflags! {
+    pub flags NodeFlags: u64 {
+        /// The node is a source node, which is called on every walk of the graph.
+        const RTE_NODE_SOURCE_F = 1 << 0,
+    }
+}
+
+/// A graph instance, used by the node process callbacks.
+pub enum RawGraph {}
+
+pub type RawGraphPtr = *mut RawGraph;
+
+/// A node instance of the graph, used by the node process callbacks.
+pub enum RawNode {}
+
+pub type RawNodePtr = *mut RawNode;
+
+/// The identifier of a registered node.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeId(u32);
+
+impl NodeId {
+    /// Lookup a registered node by its name.
+    pub fn lookup(name: &str) -> Result<NodeId> {
+        let id = unsafe { rte_node_from_name(try!(to_cptr!(name))) };
+
+        if id == RTE_NODE_ID_INVALID {
+            Err(Error::OsError(libc::ENOENT))
+        } else {
+            Ok(NodeId(id))
+        }
+    }
+
+    /// The name of the node.
+    pub fn name(&self) -> Result<String> {
+        let name = unsafe { rte_node_id_to_name(self.0) };
+
+        rte_check!(name, NonNull; ok => {
+            unsafe { CStr::from_ptr(name).to_string_lossy().into_owned() }
+        })
+    }
+}
+
+impl fmt::Display for NodeId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl From<NodeId> for u32 {
+    fn from(id: NodeId) -> Self {
+        id.0
+    }
+}
+
+/// A node type which could be registered to the graph library.
+///
+/// The process callback is called with a burst of objects enqueued to the node.
+///
+pub trait NodeFactory {
+    /// The unique name of the node.
+    fn name() -> &'static str;
+
+    /// The flags of the node.
+    fn flags() -> NodeFlags {
+        NodeFlags::empty()
+    }
+
+    /// The names of the next nodes, in the order of the edges.
+    fn next_nodes() -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Process a burst of objects, returns the number of processed objects.
+    fn process(graph: RawGraphPtr,
+               node: RawNodePtr,
+               objs: *mut *mut c_void,
+               nb_objs: u16)
+               -> u16;
+
+    /// Register the node to the graph library.
+    fn register() -> Result<NodeId>
+        where Self: Sized
+    {
+        register::<Self>()
+    }
+}
+
+#[repr(C)]
+struct RawNodeRegister {
+    name: [c_char; RTE_NODE_NAMESIZE],
+    flags: u64,
+    process: Option<extern "C" fn(RawGraphPtr, RawNodePtr, *mut *mut c_void, u16) -> u16>,
+    init: Option<extern "C" fn(*const RawGraph, RawNodePtr) -> libc::c_int>,
+    fini: Option<extern "C" fn(*const RawGraph, RawNodePtr)>,
+    id: u32,
+    parent_id: u32,
+    nb_edges: u16,
+    next_nodes: [*const c_char; 0],
+}
+
+extern "C" fn process_trampoline<F: NodeFactory>(graph: RawGraphPtr,
+                                                 node: RawNodePtr,
+                                                 objs: *mut *mut c_void,
+                                                 nb_objs: u16)
+                                                 -> u16 {
+    F::process(graph, node, objs, nb_objs)
+}
+
+/// Register the node type to the graph library.
+pub fn register<F: NodeFactory>() -> Result<NodeId> {
+    let name = F::name().as_bytes();
+
+    if name.len() >= RTE_NODE_NAMESIZE {
+        return Err(Error::OsError(libc::ENAMETOOLONG));
+    }
+
+    let next_nodes: Vec<CString> = try!(F::next_nodes()
+        .iter()
+        .map(|name| CString::new(*name).map_err(Error::from))
+        .collect());
+
+    // the descriptor ends with a flexible array of the next node names
+    let size = mem::size_of::<RawNodeRegister>() +
+               next_nodes.len() * mem::size_of::<*const c_char>();
+    let mut buf = vec![0u64; (size + 7) / 8];
+
+    unsafe {
+        let reg = buf.as_mut_ptr() as *mut RawNodeRegister;
+
+        ptr::copy_nonoverlapping(name.as_ptr() as *const c_char,
+                                 (*reg).name.as_mut_ptr(),
+                                 name.len());
+
+        (*reg).flags = F::flags().bits;
+        (*reg).process = Some(process_trampoline::<F>);
+        (*reg).nb_edges = next_nodes.len() as u16;
+
+        let next = (*reg).next_nodes.as_mut_ptr();
+
+        for (i, name) in next_nodes.iter().enumerate() {
+            *next.offset(i as isize) = name.as_ptr();
+        }
+
+        let id = __rte_node_register(reg);
+
+        if id == RTE_NODE_ID_INVALID {
+            Err(Error::rte_error())
+        } else {
+            Ok(NodeId(id))
+        }
+    }
+}
+
+/// The configuration of the Ethernet RX and TX nodes of a port.
+#[derive(Copy, Clone, Debug)]
+pub struct EthNodeConf<'a> {
+    pub port_id: u16,
+    pub num_rx_queues: u16,
+    pub num_tx_queues: u16,
+    /// The mempools of the RX queues.
+    pub mempools: &'a [RawMemoryPoolPtr],
+}
+
+#[repr(C)]
+struct RawEthNodeConf {
+    port_id: u16,
+    num_rx_queues: u16,
+    num_tx_queues: u16,
+    mp: *const RawMemoryPoolPtr,
+    mp_count: u16,
+}
+
+/// Setup the built-in Ethernet nodes of the ports for `nb_graphs` graphs.
+///
+/// A source node named `eth_rx_node_name(port, queue)` is created for every RX queue,
+/// and a sink node named `eth_tx_node_name(port)` for every port.
+///
+pub fn eth_config(confs: &[EthNodeConf], nb_graphs: u16) -> Result<()> {
+    let mut raw_confs: Vec<RawEthNodeConf> = confs.iter()
+        .map(|conf| {
+            RawEthNodeConf {
+                port_id: conf.port_id,
+                num_rx_queues: conf.num_rx_queues,
+                num_tx_queues: conf.num_tx_queues,
+                mp: conf.mempools.as_ptr(),
+                mp_count: conf.mempools.len() as u16,
+            }
+        })
+        .collect();
+
+    let ret = unsafe {
+        rte_node_eth_config(raw_confs.as_mut_ptr(), raw_confs.len() as u16, nb_graphs)
+    };
+
+    rte_check!(ret)
+}
+
+/// The name of the Ethernet RX source node of the queue.
+pub fn eth_rx_node_name(port_id: u16, queue_id: u16) -> String {
+    format!("ethdev_rx-{}-{}", port_id, queue_id)
+}
+
+/// The name of the Ethernet TX sink node of the port.
+pub fn eth_tx_node_name(port_id: u16) -> String {
+    format!("ethdev_tx-{}", port_id)
+}
+
+#[repr(C)]
+struct RawGraphParam {
+    socket_id: libc::c_int,
+    nb_node_patterns: u16,
+    node_patterns: *const *const c_char,
+}
+
+/// A graph of nodes, destroyed when dropped.
+pub struct Graph {
+    id: u16,
+    graph: RawGraphPtr,
+}
+
+impl Drop for Graph {
+    fn drop(&mut self) {
+        let ret = unsafe { rte_graph_destroy(self.id) };
+
+        if ret < 0 {
+            warn!("fail to destroy graph {}, error {}", self.id, ret);
+        }
+    }
+}
+
+impl Graph {
+    /// Create a graph with the nodes and all the nodes reachable through their edges.
+    pub fn create(name: &str, nodes: &[NodeId]) -> Result<Graph> {
+        let mut names = Vec::with_capacity(nodes.len());
+
+        for node in nodes {
+            names.push(try!(CString::new(try!(node.name()))));
+        }
+
+        let patterns: Vec<*const c_char> = names.iter().map(|name| name.as_ptr()).collect();
+        let mut param = RawGraphParam {
+            socket_id: SOCKET_ID_ANY,
+            nb_node_patterns: patterns.len() as u16,
+            node_patterns: patterns.as_ptr(),
+        };
+
+        let id = unsafe { rte_graph_create(try!(to_cptr!(name)), &mut param) };
+
+        if id == RTE_GRAPH_ID_INVALID {
+            return Err(Error::rte_error());
+        }
+
+        let graph = unsafe { rte_graph_lookup(rte_graph_id_to_name(id)) };
+
+        if graph.is_null() {
+            unsafe { rte_graph_destroy(id) };
+
+            return Err(Error::rte_error());
+        }
+
+        Ok(Graph {
+            id: id,
+            graph: graph,
+        })
+    }
+
+    /// The identifier of the graph.
+    pub fn id(&self) -> u16 {
+        self.id
+    }
+
+    /// Extract the raw pointer from an underlying object.
+    pub fn as_raw(&self) -> RawGraphPtr {
+        self.graph
+    }
+
+    /// Walk the graph once, calling the source nodes and the nodes with pending objects.
+    ///
+    /// Returns the number of objects processed by the nodes.
+    ///
+    pub fn run(&self) -> Result<u64> {
+        Ok(unsafe { _rte_graph_walk(self.graph) })
+    }
+}
+
+extern "C" {
+    fn __rte_node_register(node: *const RawNodeRegister) -> u32;
+
+    fn rte_node_from_name(name: *const c_char) -> u32;
+
+    fn rte_node_id_to_name(id: u32) -> *mut c_char;
+
+    fn rte_node_eth_config(cfg: *mut RawEthNodeConf, cnt: u16, nb_graphs: u16) -> libc::c_int;
+
+    fn rte_graph_create(name: *const c_char, prm: *mut RawGraphParam) -> u16;
+
+    fn rte_graph_destroy(id: u16) -> libc::c_int;
+
+    fn rte_graph_id_to_name(id: u16) -> *mut c_char;
+
+    fn rte_graph_lookup(name: *const c_char) -> RawGraphPtr;
+
+    fn _rte_graph_walk(graph: RawGraphPtr) -> u64;
+}
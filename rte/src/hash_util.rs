@@ -0,0 +1,117 @@
+use std::hash::Hasher;
+use std::os::raw::c_void;
+
+use libc;
+
+/// Calculate the Jenkins hash of the data.
+pub fn jhash(data: &[u8], initval: u32) -> u32 {
+    unsafe { _rte_jhash(data.as_ptr() as *const c_void, data.len() as u32, initval) }
+}
+
+/// Calculate the Jenkins hash of two 32 bits words.
+pub fn jhash_2words(a: u32, b: u32, initval: u32) -> u32 {
+    unsafe { _rte_jhash_2words(a, b, initval) }
+}
+
+/// Calculate the Jenkins hash of three 32 bits words.
+pub fn jhash_3words(a: u32, b: u32, c: u32, initval: u32) -> u32 {
+    unsafe { _rte_jhash_3words(a, b, c, initval) }
+}
+
+/// Calculate the CRC32C hash of the data, using the SSE4.2 instructions if available.
+///
+/// The result is not inverted, as the hash tables of DPDK use it.
+///
+pub fn hash_crc(data: &[u8], init_val: u32) -> u32 {
+    unsafe { _rte_hash_crc(data.as_ptr() as *const c_void, data.len() as u32, init_val) }
+}
+
+/// Update the little-endian CRC32 (IEEE 802.3) of the data.
+///
+/// The CRC is neither pre nor post inverted, so the Ethernet FCS is `!crc32_le(!0, buf)`.
+///
+pub fn crc32_le(crc: u32, buf: &[u8]) -> u32 {
+    buf.iter().fold(crc, |crc, b| {
+        (0..8).fold(crc ^ (*b as u32),
+                    |crc, _| (crc >> 1) ^ (0xedb88320 & (!(crc & 1)).wrapping_add(1)))
+    })
+}
+
+/// A hasher which uses the Jenkins hash of DPDK, e.g. for `HashMap`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DpdkHasher {
+    hash: u32,
+}
+
+impl DpdkHasher {
+    pub fn new(initval: u32) -> DpdkHasher {
+        DpdkHasher { hash: initval }
+    }
+}
+
+impl Hasher for DpdkHasher {
+    fn finish(&self) -> u64 {
+        self.hash as u64
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        self.hash = jhash(bytes, self.hash);
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.hash = jhash_2words(i, 0, self.hash);
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.hash = jhash_2words(i as u32, (i >> 32) as u32, self.hash);
+    }
+}
+
+extern "C" {
+    fn _rte_jhash(key: *const c_void,
+                  length: libc::uint32_t,
+                  initval: libc::uint32_t)
+                  -> libc::uint32_t;
+
+    fn _rte_jhash_2words(a: libc::uint32_t,
+                         b: libc::uint32_t,
+                         initval: libc::uint32_t)
+                         -> libc::uint32_t;
+
+    fn _rte_jhash_3words(a: libc::uint32_t,
+                         b: libc::uint32_t,
+                         c: libc::uint32_t,
+                         initval: libc::uint32_t)
+                         -> libc::uint32_t;
+
+    fn _rte_hash_crc(data: *const c_void,
+                     data_len: libc::uint32_t,
+                     init_val: libc::uint32_t)
+                     -> libc::uint32_t;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::hash::BuildHasherDefault;
+
+    use super::*;
+
+    #[test]
+    fn test_hash_util() {
+        assert_eq!(!crc32_le(!0, b"123456789"), 0xcbf43926);
+        assert_eq!(!hash_crc(b"123456789", !0), 0xe3069283);
+
+        assert_eq!(jhash_2words(1, 2, 0), jhash_3words(1, 2, 0, 0));
+        assert_eq!(jhash(b"hello", 0), jhash(b"hello", 0));
+        assert!(jhash(b"hello", 0) != jhash(b"hello", 1));
+
+        let mut m: HashMap<u32, &str, BuildHasherDefault<DpdkHasher>> = HashMap::default();
+
+        m.insert(1, "one");
+        m.insert(2, "two");
+
+        assert_eq!(m.get(&1), Some(&"one"));
+        assert_eq!(m.get(&2), Some(&"two"));
+    }
+}
@@ -0,0 +1,174 @@
+use std::mem;
+use std::ptr;
+use std::slice;
+
+use ffi;
+
+use errors::{Error, Result};
+use ether::{EtherHdr, ETHER_TYPE_IPV4_BE};
+use ip::{self, Ipv4Hdr, Ipv4HdrBuilder, IPV4_HDR_LEN};
+use mbuf::{self, PktMbuf, PktMbufPool};
+use mempool;
+
+/// ICMP Header.
+pub type IcmpHdr = ffi::Struct_icmp_hdr;
+
+/// Echo reply.
+pub const IP_ICMP_ECHO_REPLY: u8 = 0;
+/// Echo request.
+pub const IP_ICMP_ECHO_REQUEST: u8 = 8;
+
+const IPPROTO_ICMP: u8 = 1;
+
+const ETHER_HDR_LEN: usize = 14;
+const ICMP_HDR_LEN: usize = 8;
+
+/// An ICMP echo request.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IcmpEchoRequest {
+    pub id: u16,
+    pub seq: u16,
+    pub data: Vec<u8>,
+}
+
+impl IcmpEchoRequest {
+    /// Build the request from the source to the destination in a new mbuf.
+    pub fn build(&self,
+                 src_ip: u32,
+                 dst_ip: u32,
+                 pool: &mut mempool::RawMemoryPool)
+                 -> Result<mbuf::RawMbufPtr> {
+        build_echo_request(src_ip, dst_ip, self.id, self.seq, &self.data, pool)
+    }
+}
+
+/// An ICMP echo reply, the addresses are in the host byte order.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IcmpEchoReply {
+    pub src_ip: u32,
+    pub dst_ip: u32,
+    pub id: u16,
+    pub seq: u16,
+    pub data: Vec<u8>,
+}
+
+/// Build an Ethernet, IPv4 and ICMP echo request in a new mbuf allocated from the pool.
+///
+/// The addresses are given in the host byte order, the Ethernet addresses are zeroed
+/// and must be filled before the packet is sent.
+///
+/// The ICMP checksum is computed in software and the IPv4 checksum is offloaded
+/// to the hardware with `PKT_TX_IPV4 | PKT_TX_IP_CKSUM`.
+///
+pub fn build_echo_request(src_ip: u32,
+                          dst_ip: u32,
+                          id: u16,
+                          seq: u16,
+                          data: &[u8],
+                          pool: &mut mempool::RawMemoryPool)
+                          -> Result<mbuf::RawMbufPtr> {
+    let ip_len = IPV4_HDR_LEN + ICMP_HDR_LEN + data.len();
+
+    if ip_len > 0xFFFF {
+        return Err(Error::OsError(::libc::EINVAL));
+    }
+
+    let m = pool.alloc();
+
+    if m.is_null() {
+        return Err(Error::rte_error());
+    }
+
+    let p = match unsafe { (*m).append(ETHER_HDR_LEN + ip_len) } {
+        Ok(p) => p,
+        Err(err) => {
+            unsafe { (*m).free() };
+
+            return Err(err);
+        }
+    };
+
+    unsafe {
+        let mut eth: EtherHdr = mem::zeroed();
+
+        eth.ether_type = ETHER_TYPE_IPV4_BE;
+
+        ptr::write_unaligned(p as *mut EtherHdr, eth);
+
+        let ip = Ipv4HdrBuilder::new()
+            .src(src_ip)
+            .dst(dst_ip)
+            .protocol(IPPROTO_ICMP)
+            .build(ip_len as u16);
+
+        ptr::write_unaligned(p.offset(ETHER_HDR_LEN as isize) as *mut Ipv4Hdr, ip);
+
+        let icmp = p.offset((ETHER_HDR_LEN + IPV4_HDR_LEN) as isize);
+
+        ptr::write_unaligned(icmp as *mut IcmpHdr,
+                             IcmpHdr {
+                                 icmp_type: IP_ICMP_ECHO_REQUEST,
+                                 icmp_code: 0,
+                                 icmp_cksum: 0,
+                                 icmp_ident: id.to_be(),
+                                 icmp_seq_nb: seq.to_be(),
+                             });
+        ptr::copy_nonoverlapping(data.as_ptr(), icmp.offset(ICMP_HDR_LEN as isize), data.len());
+
+        let cksum = !ip::raw_cksum(slice::from_raw_parts(icmp, ICMP_HDR_LEN + data.len()));
+
+        ptr::write_unaligned(icmp.offset(2) as *mut u16, cksum.to_be());
+
+        // l2_len and l3_len used by the IPv4 checksum offload
+        *(*m).tx_offload() = (ETHER_HDR_LEN as u64) | ((IPV4_HDR_LEN as u64) << 7);
+        (*m).ol_flags |= (mbuf::PKT_TX_IPV4 | mbuf::PKT_TX_IP_CKSUM).bits;
+    }
+
+    Ok(m)
+}
+
+/// Parse the ICMP echo reply in the mbuf.
+///
+/// Returns `None` if the packet is not a contiguous IPv4 echo reply with a valid ICMP checksum.
+///
+pub fn parse_echo_reply(m: &mbuf::RawMbuf) -> Option<IcmpEchoReply> {
+    let data_len = m.data_len as usize;
+
+    if data_len < ETHER_HDR_LEN + IPV4_HDR_LEN + ICMP_HDR_LEN {
+        return None;
+    }
+
+    let m = m as *const mbuf::RawMbuf;
+
+    unsafe {
+        let eth = pktmbuf_mtod!(m, *const EtherHdr);
+
+        if (*eth).ether_type != ETHER_TYPE_IPV4_BE {
+            return None;
+        }
+
+        let ip = ptr::read_unaligned(pktmbuf_mtod_offset!(m, *const Ipv4Hdr, ETHER_HDR_LEN));
+        let ihl = ((ip.version_ihl & 0x0F) as usize) * 4;
+        let ip_len = u16::from_be(ip.total_length) as usize;
+
+        if (ip.version_ihl >> 4) != 4 || ip.next_proto_id != IPPROTO_ICMP || ihl < IPV4_HDR_LEN ||
+           ip_len < ihl + ICMP_HDR_LEN || ETHER_HDR_LEN + ip_len > data_len {
+            return None;
+        }
+
+        let icmp = slice::from_raw_parts(pktmbuf_mtod_offset!(m, *const u8, ETHER_HDR_LEN + ihl),
+                                         ip_len - ihl);
+
+        if icmp[0] != IP_ICMP_ECHO_REPLY || icmp[1] != 0 || ip::raw_cksum(icmp) != 0xFFFF {
+            return None;
+        }
+
+        Some(IcmpEchoReply {
+            src_ip: u32::from_be(ip.src_addr),
+            dst_ip: u32::from_be(ip.dst_addr),
+            id: ((icmp[4] as u16) << 8) | (icmp[5] as u16),
+            seq: ((icmp[6] as u16) << 8) | (icmp[7] as u16),
+            data: icmp[ICMP_HDR_LEN..].to_vec(),
+        })
+    }
+}
@@ -1,7 +1,208 @@
+use std::ptr;
+
 use ffi;
 
+use errors::{Error, Result};
+use mbuf::{self, PktMbuf};
+
 /// IPv4 Header
 pub type Ipv4Hdr = ffi::Struct_ipv4_hdr;
 
 /// IPv6 Header
 pub type Ipv6Hdr = ffi::Struct_ipv6_hdr;
+
+/// The length of the IPv4 header without options.
+pub const IPV4_HDR_LEN: usize = 20;
+
+/// The default version (4) and header length (5 words) of the IPv4 header.
+pub const IPV4_VHL_DEF: u8 = 0x45;
+
+/// The default time to live.
+pub const IPV4_DEFAULT_TTL: u8 = 64;
+
+/// Don't fragment flag.
+pub const IPV4_HDR_DF_FLAG: u16 = 1 << 14;
+
+/// Build the IPv4 header without options, the addresses are in the host byte order.
+///
+/// The checksum is left to zero, to be computed by the hardware or `ipv4_cksum`.
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Ipv4HdrBuilder {
+    src: u32,
+    dst: u32,
+    ttl: u8,
+    proto: u8,
+    dscp: u8,
+    ecn: u8,
+    id: u16,
+    df: bool,
+}
+
+impl Default for Ipv4HdrBuilder {
+    fn default() -> Self {
+        Ipv4HdrBuilder {
+            src: 0,
+            dst: 0,
+            ttl: IPV4_DEFAULT_TTL,
+            proto: 0,
+            dscp: 0,
+            ecn: 0,
+            id: 0,
+            df: false,
+        }
+    }
+}
+
+impl Ipv4HdrBuilder {
+    pub fn new() -> Self {
+        Ipv4HdrBuilder::default()
+    }
+
+    pub fn src(mut self, addr: u32) -> Self {
+        self.src = addr;
+        self
+    }
+
+    pub fn dst(mut self, addr: u32) -> Self {
+        self.dst = addr;
+        self
+    }
+
+    pub fn ttl(mut self, ttl: u8) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    pub fn protocol(mut self, proto: u8) -> Self {
+        self.proto = proto;
+        self
+    }
+
+    /// The 6 bits differentiated services code point.
+    pub fn dscp(mut self, dscp: u8) -> Self {
+        self.dscp = dscp & 0x3F;
+        self
+    }
+
+    /// The 2 bits explicit congestion notification.
+    pub fn ecn(mut self, ecn: u8) -> Self {
+        self.ecn = ecn & 0x03;
+        self
+    }
+
+    pub fn id(mut self, id: u16) -> Self {
+        self.id = id;
+        self
+    }
+
+    pub fn dont_fragment(mut self, df: bool) -> Self {
+        self.df = df;
+        self
+    }
+
+    /// Build the header of the packet with the total length, including the header.
+    pub fn build(&self, total_len: u16) -> Ipv4Hdr {
+        Ipv4Hdr {
+            version_ihl: IPV4_VHL_DEF,
+            type_of_service: (self.dscp << 2) | self.ecn,
+            total_length: total_len.to_be(),
+            packet_id: self.id.to_be(),
+            fragment_offset: if self.df { IPV4_HDR_DF_FLAG.to_be() } else { 0 },
+            time_to_live: self.ttl,
+            next_proto_id: self.proto,
+            hdr_checksum: 0,
+            src_addr: self.src.to_be(),
+            dst_addr: self.dst.to_be(),
+        }
+    }
+
+    /// Insert the header at the offset of the packet, e.g. after the Ethernet header.
+    ///
+    /// The headroom of the mbuf is used to move the first `offset` bytes,
+    /// and the total length is the length of the packet following the offset.
+    ///
+    pub fn write_to_mbuf(&self, m: &mut mbuf::RawMbuf, offset: usize) -> Result<()> {
+        if offset > m.data_len as usize ||
+           m.pkt_len as usize + IPV4_HDR_LEN - offset > ::std::u16::MAX as usize {
+            return Err(Error::OsError(::libc::EINVAL));
+        }
+
+        let p = try!(m.prepend(IPV4_HDR_LEN));
+        let hdr = self.build((m.pkt_len as usize - offset) as u16);
+
+        unsafe {
+            ptr::copy(p.offset(IPV4_HDR_LEN as isize), p, offset);
+            ptr::write_unaligned(p.offset(offset as isize) as *mut Ipv4Hdr, hdr);
+        }
+
+        Ok(())
+    }
+}
+
+/// Compute the checksum of the IPv4 header without options.
+///
+/// The result is in the network byte order, ready to be stored in `hdr_checksum`.
+///
+pub fn ipv4_cksum(hdr: &Ipv4Hdr) -> u16 {
+    let mut hdr = *hdr;
+
+    hdr.hdr_checksum = 0;
+
+    let bytes = unsafe {
+        ::std::slice::from_raw_parts(&hdr as *const Ipv4Hdr as *const u8, IPV4_HDR_LEN)
+    };
+
+    (!raw_cksum(bytes)).to_be()
+}
+
+/// Compute the ones' complement sum of the 16 bits words of the data in the network byte order.
+///
+/// The result is not inverted, the checksum of a header is `!raw_cksum(hdr)`.
+///
+pub fn raw_cksum(data: &[u8]) -> u16 {
+    let mut sum = data.chunks(2)
+        .fold(0u32, |sum, w| sum + ((w[0] as u32) << 8) + w.get(1).map_or(0, |b| *b as u32));
+
+    while sum > 0xFFFF {
+        sum = (sum & 0xFFFF) + (sum >> 16);
+    }
+
+    sum as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ipv4_hdr_builder() {
+        let mut hdr = Ipv4HdrBuilder::new()
+            .src(0xc0a80001)
+            .dst(0xc0a800c7)
+            .protocol(17)
+            .id(0x1c46)
+            .dscp(0x2e)
+            .ecn(1)
+            .dont_fragment(true)
+            .build(0x73);
+
+        assert_eq!(hdr.version_ihl, 0x45);
+        assert_eq!(hdr.type_of_service, 0xb9);
+        assert_eq!(u16::from_be(hdr.total_length), 0x73);
+        assert_eq!(u16::from_be(hdr.packet_id), 0x1c46);
+        assert_eq!(u16::from_be(hdr.fragment_offset), 0x4000);
+        assert_eq!(hdr.time_to_live, 64);
+        assert_eq!(hdr.next_proto_id, 17);
+        assert_eq!(hdr.hdr_checksum, 0);
+        assert_eq!(u32::from_be(hdr.src_addr), 0xc0a80001);
+        assert_eq!(u32::from_be(hdr.dst_addr), 0xc0a800c7);
+
+        hdr.type_of_service = 0;
+        hdr.packet_id = 0;
+        hdr.time_to_live = 0x40;
+        hdr.next_proto_id = 0x11;
+
+        assert_eq!(u16::from_be(ipv4_cksum(&hdr)), 0xb861);
+    }
+}
@@ -1,9 +1,10 @@
 use std::mem;
+use std::os::raw::{c_int, c_void};
 
 use ffi;
 
 use errors::Result;
-use lcore::LcoreId;
+use lcore::{self, LcoreId};
 
 pub type LcoreFunc<T> = extern "C" fn(*const T) -> i32;
 
@@ -45,3 +46,97 @@ pub fn wait_lcore(lcore_id: LcoreId) -> bool {
 pub fn mp_wait_lcore() {
     unsafe { ffi::rte_eal_mp_wait_lcore() }
 }
+
+/// The closure launched on the lcores with `mp_launch()`.
+///
+/// The handle waits until all the lcores finish their jobs before the closure is dropped.
+///
+pub struct MpLaunchHandle<F: Fn() -> i32 + Send + Sync + 'static> {
+    f: Box<F>,
+    lcores: Vec<LcoreId>,
+    master: Option<(LcoreId, i32)>,
+}
+
+impl<F: Fn() -> i32 + Send + Sync + 'static> MpLaunchHandle<F> {
+    /// The lcores which run the closure.
+    pub fn lcores(&self) -> &[LcoreId] {
+        &self.lcores
+    }
+
+    /// The MASTER lcore id and the value returned by the closure on it,
+    /// if it was launched on the MASTER lcore.
+    pub fn master(&self) -> Option<(LcoreId, i32)> {
+        self.master
+    }
+
+    /// Wait until all lcores finish their jobs, returns the lcore ids and the returned values.
+    ///
+    /// To be executed on the MASTER lcore only.
+    pub fn wait(mut self) -> Vec<(LcoreId, i32)> {
+        self.wait_lcores()
+    }
+
+    fn wait_lcores(&mut self) -> Vec<(LcoreId, i32)> {
+        let master = self.master;
+
+        self.lcores
+            .drain(..)
+            .map(|lcore_id| match master {
+                Some((master_id, ret)) if master_id == lcore_id => (lcore_id, ret),
+                _ => (lcore_id, unsafe { ffi::rte_eal_wait_lcore(lcore_id) }),
+            })
+            .collect()
+    }
+}
+
+impl<F: Fn() -> i32 + Send + Sync + 'static> Drop for MpLaunchHandle<F> {
+    fn drop(&mut self) {
+        let _ = self.wait_lcores();
+    }
+}
+
+extern "C" fn mp_launch_trampoline<F: Fn() -> i32>(arg: *mut c_void) -> c_int {
+    let f = unsafe { &*(arg as *const F) };
+
+    f()
+}
+
+/// Launch a closure on all lcores.
+///
+/// The closure is launched on the slave lcores first, then it is called on the MASTER lcore
+/// unless `skip_master`, and its returned value is kept since DPDK drops it.
+///
+/// The returned handle must be kept until the lcores finish their jobs,
+/// it waits for them when it is dropped. It is named `mp_launch` because
+/// `mp_remote_launch()` already launches a raw `LcoreFunc` on all lcores.
+///
+pub fn mp_launch<F>(f: F, skip_master: bool) -> Result<MpLaunchHandle<F>>
+    where F: Fn() -> i32 + Send + Sync + 'static
+{
+    let f = Box::new(f);
+    let lcores = lcore::foreach_lcores(|lcore_id| lcore_id, skip_master);
+
+    let ret = unsafe {
+        let trampoline = mp_launch_trampoline::<F> as extern "C" fn(*mut c_void) -> c_int;
+
+        ffi::rte_eal_mp_remote_launch(mem::transmute(trampoline),
+                                      &*f as *const F as *mut c_void,
+                                      ffi::Enum_rte_rmt_call_master_t::SKIP_MASTER)
+    };
+
+    try!(rte_check!(ret));
+
+    // the handle owns the closure before it is called on the MASTER lcore,
+    // so a panic there waits for the slave lcores before the closure is dropped.
+    let mut handle = MpLaunchHandle {
+        f: f,
+        lcores: lcores,
+        master: None,
+    };
+
+    if !skip_master {
+        handle.master = Some((lcore::master(), (*handle.f)()));
+    }
+
+    Ok(handle)
+}
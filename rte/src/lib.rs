@@ -8,6 +8,12 @@ extern crate libc;
 extern crate rand;
 extern crate errno;
 extern crate cfile;
+#[cfg(feature = "bytes")]
+extern crate bytes;
+#[cfg(feature = "tokio")]
+extern crate futures;
+#[cfg(feature = "tokio")]
+extern crate tokio;
 
 extern crate rte_sys as ffi;
 
@@ -18,6 +24,7 @@ pub mod macros;
 #[macro_use]
 pub mod byteorder;
 pub mod common;
+pub mod cpu;
 #[macro_use]
 pub mod debug;
 pub mod config;
@@ -27,22 +34,59 @@ pub mod malloc;
 pub mod memory;
 pub mod memzone;
 pub mod mempool;
+pub mod ring;
 #[macro_use]
 pub mod mbuf;
 pub mod lcore;
 pub mod cycles;
+pub mod delay;
 pub mod spinlock;
+pub mod sync;
 pub mod launch;
 pub mod eal;
 
 pub mod devargs;
 pub mod ethdev;
+#[cfg(feature = "tokio")]
+pub mod async_rx;
 pub mod pci;
+#[cfg(feature = "kni")]
 pub mod kni;
 pub mod bond;
+pub mod crypto;
+#[cfg(dpdk_ver_17_05)]
+pub mod distributor;
+#[cfg(all(feature = "eventdev", dpdk_ver_17_05))]
+pub mod eventdev;
+#[cfg(all(feature = "graph", dpdk_ver_20_05))]
+pub mod graph;
+pub mod meter;
+#[cfg(all(feature = "metrics", dpdk_ver_17_05))]
+pub mod metrics;
+#[cfg(all(feature = "mtr", dpdk_ver_21_05))]
+pub mod mtr;
+#[cfg(all(feature = "pdump", dpdk_ver_16_07))]
+pub mod pdump;
+pub mod pcap;
+pub mod hash_util;
+pub mod pipeline;
+pub mod reorder;
+pub mod thash;
+#[cfg(all(feature = "security", dpdk_ver_17_11))]
+pub mod security;
+#[cfg(all(feature = "telemetry", dpdk_ver_20_05))]
+pub mod telemetry;
+#[cfg(all(feature = "tm", dpdk_ver_17_11))]
+pub mod tm;
+#[cfg(all(feature = "trace", dpdk_ver_20_05))]
+#[macro_use]
+pub mod trace;
+#[cfg(all(feature = "vhost", dpdk_ver_17_05))]
+pub mod vhost;
 
 pub mod ether;
 pub mod arp;
+pub mod icmp;
 pub mod ip;
 
 #[macro_use]
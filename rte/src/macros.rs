@@ -44,3 +44,49 @@ macro_rules! offset_of {
         unsafe { offset_of_unsafe!($container, $field) }
     };
 }
+
+/// The version parsed by the build script from the `rte_version.h` of the DPDK headers in use.
+#[doc(hidden)]
+pub const DPDK_VERSION: (u32, u32, u32) = (parse_version(env!("DPDK_VERSION_YEAR")),
+                                           parse_version(env!("DPDK_VERSION_MONTH")),
+                                           parse_version(env!("DPDK_VERSION_MINOR")));
+
+const fn parse_version(s: &str) -> u32 {
+    let digits = s.as_bytes();
+    let mut n = 0;
+    let mut i = 0;
+
+    while i < digits.len() {
+        n = n * 10 + (digits[i] - b'0') as u32;
+        i += 1;
+    }
+
+    n
+}
+
+/// The version of the DPDK headers used to build the crate, as a `(year, month, minor)` tuple.
+///
+/// The build script also sets `#[cfg(dpdk_ver_<year>_<month>)]`, e.g. `dpdk_ver_20_11`,
+/// for each known release up to the detected version.
+#[macro_export]
+macro_rules! dpdk_version {
+    () => (
+        $crate::macros::DPDK_VERSION
+    )
+}
+
+/// Fail to compile if the DPDK headers are older than the given `year` and `month` release.
+///
+/// ```
+/// # #[macro_use] extern crate rte;
+/// require_dpdk_version!(16, 4);
+/// # fn main() {}
+/// ```
+#[macro_export]
+macro_rules! require_dpdk_version {
+    ($year:expr, $month:expr) => (
+        #[allow(dead_code)]
+        const _: u32 = $crate::macros::DPDK_VERSION.0 * 100 + $crate::macros::DPDK_VERSION.1 -
+                       ($year * 100 + $month);
+    )
+}
@@ -1,12 +1,16 @@
 use std::mem;
 use std::ptr;
+use std::alloc::{GlobalAlloc, Layout, System};
 use std::os::unix::io::AsRawFd;
-use std::os::raw::c_void;
+use std::os::raw::{c_int, c_void};
 
 use cfile;
 
 use ffi;
 
+use eal;
+use memory::{SocketId, SOCKET_ID_ANY};
+
 #[macro_export]
 macro_rules! rte_new {
     ($t:ty) => (unsafe {
@@ -117,6 +121,11 @@ pub fn free(ptr: *mut c_void) {
     unsafe { ffi::rte_free(ptr as *mut c_void) }
 }
 
+/// Return the physical address of a virtual address obtained through rte_malloc
+pub fn virt2phy(addr: *const c_void) -> ffi::phys_addr_t {
+    unsafe { rte_malloc_virt2phy(addr) }
+}
+
 /// Get heap statistics for the specified heap.
 pub fn get_socket_stats(socket_id: i32) -> Option<ffi::Struct_rte_malloc_socket_stats> {
     unsafe {
@@ -140,3 +149,83 @@ pub fn dump_stats<S: AsRawFd>(s: &S, tag: Option<&str>) {
         }
     }
 }
+
+/// A global allocator which allocates from the huge-page area of memory.
+///
+/// The memory is allocated from the system allocator until EAL is initialized,
+/// and it is freed to the allocator which owns its address.
+///
+/// ```rust,ignore
+/// #[global_allocator]
+/// static A: DpdkAllocator = DpdkAllocator::socket_any();
+/// ```
+pub struct DpdkAllocator {
+    socket_id: SocketId,
+}
+
+impl DpdkAllocator {
+    /// An allocator which allocates from any NUMA socket.
+    pub const fn socket_any() -> DpdkAllocator {
+        DpdkAllocator { socket_id: SOCKET_ID_ANY }
+    }
+
+    /// An allocator which allocates from the specified NUMA socket.
+    pub const fn new_socket(socket_id: SocketId) -> DpdkAllocator {
+        DpdkAllocator { socket_id: socket_id }
+    }
+
+    pub fn socket_id(&self) -> SocketId {
+        self.socket_id
+    }
+}
+
+unsafe impl GlobalAlloc for DpdkAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if !eal::is_initialized() {
+            return System.alloc(layout);
+        }
+
+        ffi::rte_malloc_socket(ptr::null(),
+                               layout.size() as u64,
+                               layout.align() as u32,
+                               self.socket_id) as *mut u8
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        if !eal::is_initialized() {
+            return System.alloc_zeroed(layout);
+        }
+
+        ffi::rte_zmalloc_socket(ptr::null(),
+                                layout.size() as u64,
+                                layout.align() as u32,
+                                self.socket_id) as *mut u8
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        if is_rte_heap(ptr) {
+            ffi::rte_free(ptr as *mut c_void)
+        } else {
+            System.dealloc(ptr, layout)
+        }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        if is_rte_heap(ptr) {
+            ffi::rte_realloc(ptr as *mut c_void, new_size as u64, layout.align() as u32) as *mut u8
+        } else {
+            System.realloc(ptr, layout, new_size)
+        }
+    }
+}
+
+/// Whether the memory is allocated from the memory segments of EAL.
+fn is_rte_heap(ptr: *mut u8) -> bool {
+    eal::is_initialized() && unsafe { _rte_mem_is_rte_addr(ptr as *const c_void) } != 0
+}
+
+extern "C" {
+    fn rte_malloc_virt2phy(addr: *const c_void) -> ffi::phys_addr_t;
+
+    fn _rte_mem_is_rte_addr(addr: *const c_void) -> c_int;
+}
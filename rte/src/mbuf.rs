@@ -1,3 +1,7 @@
+use std::fmt;
+use std::mem;
+use std::ptr;
+use std::ffi::CStr;
 use std::os::unix::io::AsRawFd;
 
 use libc;
@@ -5,8 +9,10 @@ use cfile;
 
 use ffi;
 
-use errors::Result;
+use errors::{Error, Result};
 use mempool;
+use hash_util;
+use ip;
 
 // Packet Offload Features Flags. It also carry packet type information.
 // Critical resources. Both rx/tx shared these bits. Be cautious on any change
@@ -153,6 +159,160 @@ bitflags! {
     }
 }
 
+bitflags! {
+    /// The packet type, parsed by the hardware or the PMD.
+    pub flags PacketTypeFlags: u32 {
+        const RTE_PTYPE_UNKNOWN                 = 0x00000000,
+
+        const RTE_PTYPE_L2_ETHER                = 0x00000001,
+        const RTE_PTYPE_L2_ETHER_TIMESYNC       = 0x00000002,
+        const RTE_PTYPE_L2_ETHER_ARP            = 0x00000003,
+        const RTE_PTYPE_L2_ETHER_LLDP           = 0x00000004,
+        const RTE_PTYPE_L2_MASK                 = 0x0000000f,
+
+        const RTE_PTYPE_L3_IPV4                 = 0x00000010,
+        const RTE_PTYPE_L3_IPV4_EXT             = 0x00000030,
+        const RTE_PTYPE_L3_IPV6                 = 0x00000040,
+        const RTE_PTYPE_L3_IPV4_EXT_UNKNOWN     = 0x00000090,
+        const RTE_PTYPE_L3_IPV6_EXT             = 0x000000c0,
+        const RTE_PTYPE_L3_IPV6_EXT_UNKNOWN     = 0x000000e0,
+        const RTE_PTYPE_L3_MASK                 = 0x000000f0,
+
+        const RTE_PTYPE_L4_TCP                  = 0x00000100,
+        const RTE_PTYPE_L4_UDP                  = 0x00000200,
+        const RTE_PTYPE_L4_FRAG                 = 0x00000300,
+        const RTE_PTYPE_L4_SCTP                 = 0x00000400,
+        const RTE_PTYPE_L4_ICMP                 = 0x00000500,
+        const RTE_PTYPE_L4_NONFRAG              = 0x00000600,
+        const RTE_PTYPE_L4_MASK                 = 0x00000f00,
+
+        const RTE_PTYPE_TUNNEL_IP               = 0x00001000,
+        const RTE_PTYPE_TUNNEL_GRE              = 0x00002000,
+        const RTE_PTYPE_TUNNEL_VXLAN            = 0x00003000,
+        const RTE_PTYPE_TUNNEL_NVGRE            = 0x00004000,
+        const RTE_PTYPE_TUNNEL_GENEVE           = 0x00005000,
+        const RTE_PTYPE_TUNNEL_GRENAT           = 0x00006000,
+        const RTE_PTYPE_TUNNEL_MASK             = 0x0000f000,
+
+        const RTE_PTYPE_INNER_L2_ETHER          = 0x00010000,
+        const RTE_PTYPE_INNER_L2_ETHER_VLAN     = 0x00020000,
+        const RTE_PTYPE_INNER_L2_MASK           = 0x000f0000,
+
+        const RTE_PTYPE_INNER_L3_IPV4           = 0x00100000,
+        const RTE_PTYPE_INNER_L3_IPV4_EXT       = 0x00200000,
+        const RTE_PTYPE_INNER_L3_IPV6           = 0x00300000,
+        const RTE_PTYPE_INNER_L3_IPV4_EXT_UNKNOWN = 0x00400000,
+        const RTE_PTYPE_INNER_L3_IPV6_EXT       = 0x00500000,
+        const RTE_PTYPE_INNER_L3_IPV6_EXT_UNKNOWN = 0x00600000,
+        const RTE_PTYPE_INNER_L3_MASK           = 0x00f00000,
+
+        const RTE_PTYPE_INNER_L4_TCP            = 0x01000000,
+        const RTE_PTYPE_INNER_L4_UDP            = 0x02000000,
+        const RTE_PTYPE_INNER_L4_FRAG           = 0x03000000,
+        const RTE_PTYPE_INNER_L4_SCTP           = 0x04000000,
+        const RTE_PTYPE_INNER_L4_ICMP           = 0x05000000,
+        const RTE_PTYPE_INNER_L4_NONFRAG        = 0x06000000,
+        const RTE_PTYPE_INNER_L4_MASK           = 0x0f000000,
+    }
+}
+
+impl PacketTypeFlags {
+    /// The masks of the layers which the packet type belongs to.
+    pub fn layer_mask(&self) -> PacketTypeFlags {
+        [RTE_PTYPE_L2_MASK,
+         RTE_PTYPE_L3_MASK,
+         RTE_PTYPE_L4_MASK,
+         RTE_PTYPE_TUNNEL_MASK,
+         RTE_PTYPE_INNER_L2_MASK,
+         RTE_PTYPE_INNER_L3_MASK,
+         RTE_PTYPE_INNER_L4_MASK]
+            .iter()
+            .filter(|mask| self.intersects(**mask))
+            .fold(PacketTypeFlags::empty(), |acc, mask| acc | *mask)
+    }
+}
+
+macro_rules! ptype_names {
+    ($($ptype:ident),*) => (&[$(($ptype, stringify!($ptype))),*])
+}
+
+/// The packet types of each layer and their names, used by the `Debug` of the mbuf.
+const PTYPE_NAMES: &'static [(PacketTypeFlags, &'static str)] =
+    ptype_names!(RTE_PTYPE_L2_ETHER,
+                 RTE_PTYPE_L2_ETHER_TIMESYNC,
+                 RTE_PTYPE_L2_ETHER_ARP,
+                 RTE_PTYPE_L2_ETHER_LLDP,
+                 RTE_PTYPE_L3_IPV4,
+                 RTE_PTYPE_L3_IPV4_EXT,
+                 RTE_PTYPE_L3_IPV6,
+                 RTE_PTYPE_L3_IPV4_EXT_UNKNOWN,
+                 RTE_PTYPE_L3_IPV6_EXT,
+                 RTE_PTYPE_L3_IPV6_EXT_UNKNOWN,
+                 RTE_PTYPE_L4_TCP,
+                 RTE_PTYPE_L4_UDP,
+                 RTE_PTYPE_L4_FRAG,
+                 RTE_PTYPE_L4_SCTP,
+                 RTE_PTYPE_L4_ICMP,
+                 RTE_PTYPE_L4_NONFRAG,
+                 RTE_PTYPE_TUNNEL_IP,
+                 RTE_PTYPE_TUNNEL_GRE,
+                 RTE_PTYPE_TUNNEL_VXLAN,
+                 RTE_PTYPE_TUNNEL_NVGRE,
+                 RTE_PTYPE_TUNNEL_GENEVE,
+                 RTE_PTYPE_TUNNEL_GRENAT,
+                 RTE_PTYPE_INNER_L2_ETHER,
+                 RTE_PTYPE_INNER_L2_ETHER_VLAN,
+                 RTE_PTYPE_INNER_L3_IPV4,
+                 RTE_PTYPE_INNER_L3_IPV4_EXT,
+                 RTE_PTYPE_INNER_L3_IPV6,
+                 RTE_PTYPE_INNER_L3_IPV4_EXT_UNKNOWN,
+                 RTE_PTYPE_INNER_L3_IPV6_EXT,
+                 RTE_PTYPE_INNER_L3_IPV6_EXT_UNKNOWN,
+                 RTE_PTYPE_INNER_L4_TCP,
+                 RTE_PTYPE_INNER_L4_UDP,
+                 RTE_PTYPE_INNER_L4_FRAG,
+                 RTE_PTYPE_INNER_L4_SCTP,
+                 RTE_PTYPE_INNER_L4_ICMP,
+                 RTE_PTYPE_INNER_L4_NONFRAG);
+
+/// The outer layers of the packet type and the inner layers they are moved to in a tunnel,
+/// the first entry is used when a layer is mapped to several ones.
+const PTYPE_OUTER_INNER: &'static [(PacketTypeFlags, PacketTypeFlags)] =
+    &[(RTE_PTYPE_L2_ETHER, RTE_PTYPE_INNER_L2_ETHER),
+      (RTE_PTYPE_L2_ETHER, RTE_PTYPE_INNER_L2_ETHER_VLAN),
+      (RTE_PTYPE_L3_IPV4, RTE_PTYPE_INNER_L3_IPV4),
+      (RTE_PTYPE_L3_IPV4_EXT, RTE_PTYPE_INNER_L3_IPV4_EXT),
+      (RTE_PTYPE_L3_IPV4_EXT_UNKNOWN, RTE_PTYPE_INNER_L3_IPV4_EXT_UNKNOWN),
+      (RTE_PTYPE_L3_IPV6, RTE_PTYPE_INNER_L3_IPV6),
+      (RTE_PTYPE_L3_IPV6_EXT, RTE_PTYPE_INNER_L3_IPV6_EXT),
+      (RTE_PTYPE_L3_IPV6_EXT_UNKNOWN, RTE_PTYPE_INNER_L3_IPV6_EXT_UNKNOWN),
+      (RTE_PTYPE_L4_TCP, RTE_PTYPE_INNER_L4_TCP),
+      (RTE_PTYPE_L4_UDP, RTE_PTYPE_INNER_L4_UDP),
+      (RTE_PTYPE_L4_FRAG, RTE_PTYPE_INNER_L4_FRAG),
+      (RTE_PTYPE_L4_SCTP, RTE_PTYPE_INNER_L4_SCTP),
+      (RTE_PTYPE_L4_ICMP, RTE_PTYPE_INNER_L4_ICMP),
+      (RTE_PTYPE_L4_NONFRAG, RTE_PTYPE_INNER_L4_NONFRAG)];
+
+/// Map the outer L2/L3/L4 layers of the packet type to the inner layers, the others are dropped.
+fn ptype_outer_to_inner(ptype: u32) -> u32 {
+    [RTE_PTYPE_L2_MASK, RTE_PTYPE_L3_MASK, RTE_PTYPE_L4_MASK]
+        .iter()
+        .filter_map(|mask| {
+            PTYPE_OUTER_INNER.iter().find(|&&(outer, _)| outer.bits == ptype & mask.bits)
+        })
+        .fold(0, |acc, &(_, inner)| acc | inner.bits)
+}
+
+/// Map the inner L2/L3/L4 layers of the packet type to the outer layers, the others are dropped.
+fn ptype_inner_to_outer(ptype: u32) -> u32 {
+    [RTE_PTYPE_INNER_L2_MASK, RTE_PTYPE_INNER_L3_MASK, RTE_PTYPE_INNER_L4_MASK]
+        .iter()
+        .filter_map(|mask| {
+            PTYPE_OUTER_INNER.iter().find(|&&(_, inner)| inner.bits == ptype & mask.bits)
+        })
+        .fold(0, |acc, &(outer, _)| acc | outer.bits)
+}
+
 /**
  * Some NICs need at least 2KB buffer to RX standard Ethernet frame without
  * splitting it into multiple segments.
@@ -239,6 +399,76 @@ pub trait PktMbuf {
 
     /// Dump an mbuf structure to the console.
     fn dump<S: AsRawFd>(&self, s: &S, len: usize);
+
+    /// The Ethernet type of the frame, in the host byte order.
+    fn eth_type(&self) -> Option<u16>;
+
+    /// The protocol of the IPv4 or IPv6 packet, following the VLAN tags and IPv6 extension headers.
+    fn ip_proto(&self) -> Option<u8>;
+
+    /// The source and destination ports of the TCP or UDP packet, in the host byte order.
+    fn l4_ports(&self) -> Option<(u16, u16)>;
+
+    fn is_ipv4(&self) -> bool;
+
+    fn is_ipv6(&self) -> bool;
+
+    fn is_tcp(&self) -> bool {
+        self.ip_proto() == Some(IPPROTO_TCP)
+    }
+
+    fn is_udp(&self) -> bool {
+        self.ip_proto() == Some(IPPROTO_UDP)
+    }
+
+    /// Store the value in the 8 bytes of the user data of the mbuf.
+    ///
+    /// The value must not borrow anything, since it outlives the borrow in the mbuf.
+    /// Panics if the value is larger than 8 bytes.
+    ///
+    fn set_user_data<T: Copy + 'static>(&mut self, val: T);
+
+    /// Read the value stored in the user data of the mbuf.
+    ///
+    /// Panics if the value is larger than 8 bytes.
+    ///
+    fn user_data<T: Copy + 'static>(&self) -> T;
+
+    /// The private area following the mbuf structure,
+    /// `None` if the area is smaller than `T` or not aligned for it.
+    fn private_data<T>(&self) -> Option<&T>;
+
+    /// The mutable private area following the mbuf structure,
+    /// `None` if the area is smaller than `T` or not aligned for it.
+    fn private_data_mut<T>(&mut self) -> Option<&mut T>;
+
+    /// The VLAN TCI stripped on receive, or inserted on transmit.
+    fn vlan_tci(&self) -> u16;
+
+    /// Set the VLAN TCI inserted on transmit, with `PKT_TX_VLAN_PKT`.
+    fn set_vlan_tci(&mut self, tci: u16);
+
+    /// The outer VLAN TCI of the QinQ packet.
+    fn outer_vlan_tci(&self) -> u16;
+
+    /// Set the outer VLAN TCI inserted on transmit, with `PKT_TX_QINQ_PKT`.
+    fn set_outer_vlan_tci(&mut self, tci: u16);
+
+    /// The VLAN identifier of the VLAN TCI.
+    fn vlan_id(&self) -> u16 {
+        self.vlan_tci() & 0x0FFF
+    }
+
+    /// The priority code point of the VLAN TCI.
+    fn vlan_pcp(&self) -> u8 {
+        ((self.vlan_tci() >> 13) & 0x7) as u8
+    }
+
+    /// Iterate the segments of the packet, starting from this mbuf.
+    fn segments(&self) -> MbufChainIter;
+
+    /// Format the mbuf and its chained segments with `{:?}`.
+    fn debug(&self) -> MbufDebug;
 }
 
 impl PktMbuf for RawMbuf {
@@ -284,6 +514,457 @@ impl PktMbuf for RawMbuf {
             }
         }
     }
+
+    fn eth_type(&self) -> Option<u16> {
+        let data = first_segment(self);
+
+        if data.len() < ETHER_HDR_LEN {
+            None
+        } else {
+            Some(be16(data, ETHER_TYPE_OFFSET))
+        }
+    }
+
+    fn ip_proto(&self) -> Option<u8> {
+        l4_offset(first_segment(self)).map(|(proto, _)| proto)
+    }
+
+    fn l4_ports(&self) -> Option<(u16, u16)> {
+        let data = first_segment(self);
+
+        match l4_offset(data) {
+            Some((proto, Some(off))) if (proto == IPPROTO_TCP || proto == IPPROTO_UDP) &&
+                                        data.len() >= off + 4 => {
+                Some((be16(data, off), be16(data, off + 2)))
+            }
+            _ => None,
+        }
+    }
+
+    fn is_ipv4(&self) -> bool {
+        l3_offset(first_segment(self)).map_or(false, |(ether_type, _)| {
+            ether_type == ETHER_TYPE_IPV4
+        })
+    }
+
+    fn is_ipv6(&self) -> bool {
+        l3_offset(first_segment(self)).map_or(false, |(ether_type, _)| {
+            ether_type == ETHER_TYPE_IPV6
+        })
+    }
+
+    fn set_user_data<T: Copy + 'static>(&mut self, val: T) {
+        assert!(mem::size_of::<T>() <= mem::size_of::<u64>(),
+                "user data of {} bytes",
+                mem::size_of::<T>());
+
+        unsafe {
+            *self.udata64() = 0;
+
+            ptr::write_unaligned(self.udata64() as *mut T, val)
+        }
+    }
+
+    fn user_data<T: Copy + 'static>(&self) -> T {
+        assert!(mem::size_of::<T>() <= mem::size_of::<u64>(),
+                "user data of {} bytes",
+                mem::size_of::<T>());
+
+        unsafe { ptr::read_unaligned(self._bindgen_data_3_.as_ptr() as *const T) }
+    }
+
+    fn private_data<T>(&self) -> Option<&T> {
+        let p = unsafe { (self as *const RawMbuf).offset(1) as *const T };
+
+        if mem::size_of::<T>() <= self.priv_size as usize &&
+           (p as usize) % mem::align_of::<T>() == 0 {
+            Some(unsafe { &*p })
+        } else {
+            None
+        }
+    }
+
+    fn private_data_mut<T>(&mut self) -> Option<&mut T> {
+        let p = unsafe { (self as *mut RawMbuf).offset(1) as *mut T };
+
+        if mem::size_of::<T>() <= self.priv_size as usize &&
+           (p as usize) % mem::align_of::<T>() == 0 {
+            Some(unsafe { &mut *p })
+        } else {
+            None
+        }
+    }
+
+    fn vlan_tci(&self) -> u16 {
+        self.vlan_tci
+    }
+
+    fn set_vlan_tci(&mut self, tci: u16) {
+        self.vlan_tci = tci;
+        self.ol_flags |= PKT_TX_VLAN_PKT.bits;
+    }
+
+    fn outer_vlan_tci(&self) -> u16 {
+        self.vlan_tci_outer
+    }
+
+    fn set_outer_vlan_tci(&mut self, tci: u16) {
+        self.vlan_tci_outer = tci;
+        self.ol_flags |= PKT_TX_QINQ_PKT.bits;
+    }
+
+    fn segments(&self) -> MbufChainIter {
+        MbufChainIter(Some(self))
+    }
+
+    fn debug(&self) -> MbufDebug {
+        MbufDebug(self)
+    }
+}
+
+/// An iterator over the segments of a chained mbuf.
+pub struct MbufChainIter<'a>(Option<&'a RawMbuf>);
+
+impl<'a> Iterator for MbufChainIter<'a> {
+    type Item = &'a RawMbuf;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.map(|m| {
+            self.0 = unsafe { m.next.as_ref() };
+
+            m
+        })
+    }
+}
+
+/// The number of payload bytes shown by `MbufDebug`.
+const DEBUG_PAYLOAD_LEN: usize = 64;
+
+/// Debug formatting of an mbuf, the chained segments are shown in its `next` field.
+pub struct MbufDebug<'a>(&'a RawMbuf);
+
+impl<'a> fmt::Debug for MbufDebug<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let m = self.0;
+        let data = first_segment(m);
+        let payload = &data[..data.len().min(DEBUG_PAYLOAD_LEN)];
+
+        f.debug_struct("RawMbuf")
+            .field("port", &m.port)
+            .field("nb_segs", &m.nb_segs)
+            .field("pkt_len", &m.pkt_len)
+            .field("data_len", &m.data_len)
+            .field("data_off", &m.data_off)
+            .field("ol_flags", &OffloadFlagsDebug(m.ol_flags))
+            .field("packet_type", &PacketTypeDebug(m._bindgen_data_2_[0]))
+            .field("payload", &HexDebug(payload))
+            .field("next", &m.segments().nth(1).map(MbufDebug))
+            .finish()
+    }
+}
+
+struct OffloadFlagsDebug(u64);
+
+impl fmt::Debug for OffloadFlagsDebug {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let flag_name = |mask: u64| {
+            let name = unsafe {
+                let name = ffi::rte_get_rx_ol_flag_name(mask);
+
+                if name.is_null() {
+                    ffi::rte_get_tx_ol_flag_name(mask)
+                } else {
+                    name
+                }
+            };
+
+            if name.is_null() {
+                format!("{:#x}", mask)
+            } else {
+                unsafe { CStr::from_ptr(name).to_string_lossy().into_owned() }
+            }
+        };
+
+        // the L4 checksum request is a 2 bits field instead of the flags
+        let l4 = self.0 & PKT_TX_L4_MASK.bits;
+        let names = (0..64)
+            .map(|bit| 1u64 << bit)
+            .filter(|mask| self.0 & mask != 0 && mask & PKT_TX_L4_MASK.bits == 0)
+            .chain(if l4 != 0 { Some(l4) } else { None })
+            .map(flag_name)
+            .collect::<Vec<String>>();
+
+        if names.is_empty() {
+            write!(f, "(empty)")
+        } else {
+            write!(f, "{}", names.join(" | "))
+        }
+    }
+}
+
+struct PacketTypeDebug(u32);
+
+impl fmt::Debug for PacketTypeDebug {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let names = (0..8)
+            .map(|layer| self.0 & (0xf << (layer * 4)))
+            .filter(|ptype| *ptype != 0)
+            .map(|ptype| {
+                PTYPE_NAMES.iter()
+                    .find(|&&(value, _)| value.bits == ptype)
+                    .map(|&(_, name)| name.trim_start_matches("RTE_PTYPE_").to_owned())
+                    .unwrap_or_else(|| format!("{:#x}", ptype))
+            })
+            .collect::<Vec<String>>();
+
+        if names.is_empty() {
+            write!(f, "UNKNOWN")
+        } else {
+            write!(f, "{}", names.join(" "))
+        }
+    }
+}
+
+struct HexDebug<'a>(&'a [u8]);
+
+impl<'a> fmt::Debug for HexDebug<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, b) in self.0.iter().enumerate() {
+            if i > 0 {
+                try!(write!(f, " "));
+            }
+
+            try!(write!(f, "{:02x}", b));
+        }
+
+        Ok(())
+    }
+}
+
+/// The packet data of a mbuf as a `bytes::Buf`, reading its segments without copy.
+///
+/// It takes the ownership of the mbuf, which is freed when dropped.
+///
+#[cfg(feature = "bytes")]
+pub struct MbufBytes {
+    m: RawMbufPtr,
+    seg: *const RawMbuf,
+    off: usize,
+    remaining: usize,
+}
+
+#[cfg(feature = "bytes")]
+impl MbufBytes {
+    pub fn new(m: RawMbufPtr) -> MbufBytes {
+        let remaining = unsafe { m.as_ref() }.map_or(0, |m| m.pkt_len as usize);
+
+        let mut buf = MbufBytes {
+            m: m,
+            seg: m,
+            off: 0,
+            remaining: remaining,
+        };
+
+        buf.skip_consumed();
+
+        buf
+    }
+
+    /// Move to the next segment with data when the current one is consumed.
+    fn skip_consumed(&mut self) {
+        while let Some(seg) = unsafe { self.seg.as_ref() } {
+            if self.off < seg.data_len as usize {
+                break;
+            }
+
+            self.seg = seg.next;
+            self.off = 0;
+        }
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl bytes::Buf for MbufBytes {
+    fn remaining(&self) -> usize {
+        self.remaining
+    }
+
+    fn chunk(&self) -> &[u8] {
+        match unsafe { self.seg.as_ref() } {
+            Some(seg) => &first_segment(seg)[self.off..],
+            None => &[],
+        }
+    }
+
+    fn advance(&mut self, mut cnt: usize) {
+        assert!(cnt <= self.remaining,
+                "advance {} bytes past the remaining {} bytes",
+                cnt,
+                self.remaining);
+
+        self.remaining -= cnt;
+
+        while cnt > 0 {
+            let n = ::std::cmp::min(cnt, self.chunk().len());
+
+            // the segments are shorter than the packet length
+            if n == 0 {
+                break;
+            }
+
+            self.off += n;
+            cnt -= n;
+
+            self.skip_consumed();
+        }
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl Drop for MbufBytes {
+    fn drop(&mut self) {
+        if let Some(m) = unsafe { self.m.as_mut() } {
+            m.free();
+        }
+    }
+}
+
+/// Copy the packet data of all the segments to a `bytes::Bytes`, then free the mbuf.
+#[cfg(feature = "bytes")]
+pub fn into_bytes(m: RawMbufPtr) -> bytes::Bytes {
+    let mut buf = MbufBytes::new(m);
+
+    bytes::Buf::copy_to_bytes(&mut buf, bytes::Buf::remaining(&buf))
+}
+
+/// Convert a mbuf pointer to a reference, fails with `Error::NullPtr` if it is null.
+///
+/// # Safety
+///
+/// A non-null pointer must point to a valid mbuf, which is not freed or accessed
+/// through another reference while the returned reference is alive.
+///
+pub unsafe fn check_ptr<'a>(p: RawMbufPtr) -> Result<&'a mut RawMbuf> {
+    p.as_mut().ok_or(Error::NullPtr)
+}
+
+/// Check the mbuf header in the debug builds, panics if the mbuf is corrupted.
+///
+/// Besides the null pointer, the buffer, data offset and segments are checked,
+/// then the header and its segments are checked by `rte_mbuf_sanity_check`.
+///
+/// # Safety
+///
+/// A non-null pointer must point to a readable mbuf header, e.g. allocated from a mempool,
+/// which is not modified by another thread during the check.
+///
+#[inline]
+pub unsafe fn assert_valid(p: RawMbufPtr) {
+    if cfg!(debug_assertions) {
+        let m = check_ptr(p).expect("null mbuf pointer");
+
+        assert!(!m.buf_addr.is_null(), "mbuf {:p} without buffer", p);
+        assert!(m.data_off < m.buf_len,
+                "mbuf {:p} data offset {} out of buffer length {}",
+                p,
+                m.data_off,
+                m.buf_len);
+        assert!(m.nb_segs >= 1, "mbuf {:p} without segment", p);
+
+        ffi::rte_mbuf_sanity_check(p, 1)
+    }
+}
+
+/// The data of the first segment of the packet.
+fn first_segment(m: &RawMbuf) -> &[u8] {
+    if m.buf_addr.is_null() {
+        &[]
+    } else {
+        unsafe {
+            ::std::slice::from_raw_parts((m.buf_addr as *const u8).offset(m.data_off as isize),
+                                         m.data_len as usize)
+        }
+    }
+}
+
+#[inline]
+fn be16(data: &[u8], off: usize) -> u16 {
+    ((data[off] as u16) << 8) | (data[off + 1] as u16)
+}
+
+/// The Ethernet type and offset of the L3 header, following the VLAN and QinQ tags.
+fn l3_offset(data: &[u8]) -> Option<(u16, usize)> {
+    let mut off = ETHER_TYPE_OFFSET;
+
+    loop {
+        if data.len() < off + 2 {
+            return None;
+        }
+
+        match be16(data, off) {
+            ETHER_TYPE_VLAN | ETHER_TYPE_QINQ | ETHER_TYPE_QINQ1 => off += VLAN_HDR_LEN,
+            ether_type => return Some((ether_type, off + 2)),
+        }
+    }
+}
+
+/// The protocol and offset of the L4 header of the IPv4 or IPv6 packet.
+///
+/// The offset is `None` for the non-first fragments, which have no L4 header.
+///
+fn l4_offset(data: &[u8]) -> Option<(u8, Option<usize>)> {
+    match l3_offset(data) {
+        Some((ETHER_TYPE_IPV4, off)) => {
+            if data.len() < off + IPV4_HDR_LEN || (data[off] >> 4) != 4 {
+                return None;
+            }
+
+            let ihl = ((data[off] & 0x0F) as usize) * 4;
+
+            if ihl < IPV4_HDR_LEN {
+                None
+            } else if (be16(data, off + 6) & IPV4_HDR_OFFSET_MASK) != 0 {
+                Some((data[off + 9], None))
+            } else {
+                Some((data[off + 9], Some(off + ihl)))
+            }
+        }
+        Some((ETHER_TYPE_IPV6, off)) => {
+            if data.len() < off + IPV6_HDR_LEN || (data[off] >> 4) != 6 {
+                return None;
+            }
+
+            let mut proto = data[off + 6];
+            let mut off = off + IPV6_HDR_LEN;
+
+            loop {
+                match proto {
+                    IPPROTO_HOPOPTS | IPPROTO_ROUTING | IPPROTO_DSTOPTS => {
+                        if data.len() < off + 2 {
+                            return None;
+                        }
+
+                        proto = data[off];
+                        off += (data[off + 1] as usize + 1) * 8;
+                    }
+                    IPPROTO_FRAGMENT => {
+                        if data.len() < off + 8 {
+                            return None;
+                        }
+
+                        if (be16(data, off + 2) & 0xFFF8) != 0 {
+                            return Some((data[off], None));
+                        }
+
+                        proto = data[off];
+                        off += 8;
+                    }
+                    _ => return Some((proto, Some(off))),
+                }
+            }
+        }
+        _ => None,
+    }
 }
 
 pub trait PktMbufPool {
@@ -328,6 +1009,609 @@ pub fn pktmbuf_pool_create(name: &str,
     rte_check!(p, NonNull)
 }
 
+/// Create a mbuf pool with the given mempool ops, e.g. `ring_mp_mc` or `stack`.
+///
+/// This function is the same as `pktmbuf_pool_create()`, except that
+/// the mempool ops is selected instead of the default one of the platform.
+#[cfg(dpdk_ver_18_05)]
+pub fn pktmbuf_pool_create_by_ops(name: &str,
+                                  n: u32,
+                                  cache_size: u32,
+                                  priv_size: u16,
+                                  data_room_size: u16,
+                                  socket_id: i32,
+                                  ops_name: &str)
+                                  -> Result<mempool::RawMemoryPoolPtr> {
+    let p = unsafe {
+        rte_pktmbuf_pool_create_by_ops(try!(to_cptr!(name)),
+                                       n,
+                                       cache_size,
+                                       priv_size,
+                                       data_room_size,
+                                       socket_id,
+                                       try!(to_cptr!(ops_name)))
+    };
+
+    rte_check!(p, NonNull)
+}
+
+const ETHER_HDR_LEN: usize = 14;
+const ETHER_TYPE_OFFSET: usize = 12;
+const MPLS_HDR_LEN: usize = 4;
+
+const IPV4_HDR_LEN: usize = 20;
+const UDP_HDR_LEN: usize = 8;
+const VXLAN_HDR_LEN: usize = 8;
+const VXLAN_ENCAP_LEN: usize = ETHER_HDR_LEN + IPV4_HDR_LEN + UDP_HDR_LEN + VXLAN_HDR_LEN;
+
+const ETHER_TYPE_IPV4: u16 = 0x0800;
+const ETHER_TYPE_IPV6: u16 = 0x86DD;
+const ETHER_TYPE_MPLS: u16 = 0x8847;
+const ETHER_TYPE_TEB: u16 = 0x6558;
+const ETHER_TYPE_VLAN: u16 = 0x8100;
+const ETHER_TYPE_QINQ: u16 = 0x88A8;
+const ETHER_TYPE_QINQ1: u16 = 0x9100;
+
+const VLAN_HDR_LEN: usize = 4;
+const IPV6_HDR_LEN: usize = 40;
+const IPV4_HDR_OFFSET_MASK: u16 = 0x1FFF;
+
+const IPPROTO_HOPOPTS: u8 = 0;
+const IPPROTO_TCP: u8 = 6;
+const IPPROTO_ROUTING: u8 = 43;
+const IPPROTO_FRAGMENT: u8 = 44;
+const IPPROTO_DSTOPTS: u8 = 60;
+
+/// A MPLS label stack entry.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct MplsLabel {
+    /// The 20 bits label value.
+    pub label: u32,
+    /// The 3 bits traffic class.
+    pub tc: u8,
+    /// The bottom of the label stack.
+    pub s: bool,
+    pub ttl: u8,
+}
+
+impl MplsLabel {
+    pub fn new(label: u32, tc: u8, s: bool, ttl: u8) -> MplsLabel {
+        MplsLabel {
+            label: label & 0xFFFFF,
+            tc: tc & 0x7,
+            s: s,
+            ttl: ttl,
+        }
+    }
+}
+
+impl From<u32> for MplsLabel {
+    fn from(v: u32) -> Self {
+        MplsLabel::new(v >> 12, (v >> 9) as u8, (v & 0x100) != 0, v as u8)
+    }
+}
+
+impl From<MplsLabel> for u32 {
+    fn from(l: MplsLabel) -> Self {
+        ((l.label & 0xFFFFF) << 12) | (((l.tc & 0x7) as u32) << 9) | ((l.s as u32) << 8) |
+        (l.ttl as u32)
+    }
+}
+
+/// Push a MPLS label between the Ethernet header and the payload of the packet.
+///
+/// The Ethernet type of the packet is changed to MPLS unicast.
+///
+pub fn mpls_push(m: &mut RawMbuf, label: u32, tc: u8, s: bool, ttl: u8) -> Result<()> {
+    if (m.data_len as usize) < ETHER_HDR_LEN {
+        return Err(Error::OsError(libc::EINVAL));
+    }
+
+    let p = try!(m.prepend(MPLS_HDR_LEN));
+    let shim: u32 = MplsLabel::new(label, tc, s, ttl).into();
+
+    unsafe {
+        // move the destination and source addresses to the new start of the packet
+        ptr::copy(p.offset(MPLS_HDR_LEN as isize), p, ETHER_TYPE_OFFSET);
+
+        write_be16(p, ETHER_TYPE_OFFSET, ETHER_TYPE_MPLS);
+        write_be32(p, ETHER_HDR_LEN, shim);
+    }
+
+    Ok(())
+}
+
+/// Pop the top MPLS label following the Ethernet header of the packet.
+///
+/// When the label is the bottom of the stack, the Ethernet type is restored
+/// from the IP version of the payload, otherwise it is kept as MPLS unicast.
+///
+pub fn mpls_pop(m: &mut RawMbuf) -> Result<MplsLabel> {
+    let data_len = m.data_len as usize;
+
+    if data_len < ETHER_HDR_LEN + MPLS_HDR_LEN {
+        return Err(Error::OsError(libc::EINVAL));
+    }
+
+    let p: *mut u8 = pktmbuf_mtod!(m as *mut RawMbuf, *mut u8);
+
+    let label = unsafe {
+        let ether_type = read_be16(p, ETHER_TYPE_OFFSET);
+
+        if ether_type != ETHER_TYPE_MPLS && ether_type != ETHER_TYPE_MPLS + 1 {
+            return Err(Error::OsError(libc::EINVAL));
+        }
+
+        MplsLabel::from(read_be32(p, ETHER_HDR_LEN))
+    };
+
+    let ether_type = if !label.s {
+        ETHER_TYPE_MPLS
+    } else if data_len > ETHER_HDR_LEN + MPLS_HDR_LEN {
+        match unsafe { *p.offset((ETHER_HDR_LEN + MPLS_HDR_LEN) as isize) } >> 4 {
+            4 => ETHER_TYPE_IPV4,
+            6 => ETHER_TYPE_IPV6,
+            _ => ETHER_TYPE_MPLS,
+        }
+    } else {
+        ETHER_TYPE_MPLS
+    };
+
+    unsafe {
+        ptr::copy(p, p.offset(MPLS_HDR_LEN as isize), ETHER_TYPE_OFFSET);
+    }
+
+    let p = try!(m.consume(MPLS_HDR_LEN));
+
+    unsafe {
+        write_be16(p, ETHER_TYPE_OFFSET, ether_type);
+    }
+
+    Ok(label)
+}
+
+/// The UDP destination port assigned to VXLAN by IANA.
+pub const VXLAN_DEFAULT_PORT: u16 = 4789;
+
+const VXLAN_FLAGS_VNI: u32 = 0x08000000;
+const VXLAN_SRC_PORT_MIN: u16 = 49152;
+
+const IPPROTO_UDP: u8 = 17;
+const IPV4_DEFAULT_TTL: u8 = 64;
+
+// the layout of the tx_offload bitfield
+const TX_OFFLOAD_L2_LEN_MASK: u64 = 0x7F;
+const TX_OFFLOAD_OUTER_L3_LEN_SHIFT: u64 = 40;
+const TX_OFFLOAD_OUTER_L2_LEN_SHIFT: u64 = 49;
+const TX_OFFLOAD_OUTER_MASK: u64 = 0xFFFF << TX_OFFLOAD_OUTER_L3_LEN_SHIFT;
+
+/// Encapsulate the Ethernet frame into the VXLAN tunnel of the VNI.
+///
+/// The outer Ethernet, IPv4, UDP and VXLAN headers are prepended to the packet,
+/// the outer MAC addresses are copied from the inner frame
+/// and the addresses and ports are given in the host byte order.
+/// When `src_port` is zero, it is computed from the hash of the inner headers,
+/// so the flows are spread over the receive queues of the remote tunnel endpoint.
+///
+/// The outer IPv4 checksum is left to the hardware and the packet type is updated
+/// with the inner layers, so the inner headers don't need to be parsed again.
+///
+pub fn vxlan_encap(inner: &mut RawMbuf,
+                   vni: u32,
+                   outer_src_ip: u32,
+                   outer_dst_ip: u32,
+                   src_port: u16,
+                   dst_port: u16)
+                   -> Result<()> {
+    let inner_len = inner.pkt_len as usize;
+
+    if vni > 0xFFFFFF || (inner.data_len as usize) < ETHER_HDR_LEN ||
+       inner_len + VXLAN_ENCAP_LEN - ETHER_HDR_LEN > 0xFFFF {
+        return Err(Error::OsError(libc::EINVAL));
+    }
+
+    let src_port = if src_port == 0 {
+        let data: *mut u8 = pktmbuf_mtod!(inner as *mut RawMbuf, *mut u8);
+        let len = (inner.data_len as usize).min(ETHER_HDR_LEN + IPV4_HDR_LEN + UDP_HDR_LEN);
+        let hash = hash_util::jhash(unsafe { ::std::slice::from_raw_parts(data, len) }, 0);
+
+        VXLAN_SRC_PORT_MIN | (hash as u16 & !VXLAN_SRC_PORT_MIN)
+    } else {
+        src_port
+    };
+
+    let p = try!(inner.prepend(VXLAN_ENCAP_LEN));
+
+    unsafe {
+        // outer Ethernet header
+        ptr::copy_nonoverlapping(p.offset(VXLAN_ENCAP_LEN as isize), p, ETHER_TYPE_OFFSET);
+        write_be16(p, ETHER_TYPE_OFFSET, ETHER_TYPE_IPV4);
+
+        // outer IPv4 header, the checksum is computed by the hardware
+        let ip = p.offset(ETHER_HDR_LEN as isize);
+
+        ptr::write_bytes(ip, 0, IPV4_HDR_LEN);
+        *ip = 0x45;
+        write_be16(ip, 2, (inner_len + VXLAN_ENCAP_LEN - ETHER_HDR_LEN) as u16);
+        *ip.offset(8) = IPV4_DEFAULT_TTL;
+        *ip.offset(9) = IPPROTO_UDP;
+        write_be32(ip, 12, outer_src_ip);
+        write_be32(ip, 16, outer_dst_ip);
+
+        // UDP header, the checksum is optional for IPv4
+        let udp = ip.offset(IPV4_HDR_LEN as isize);
+
+        write_be16(udp, 0, src_port);
+        write_be16(udp, 2, dst_port);
+        write_be16(udp, 4, (inner_len + UDP_HDR_LEN + VXLAN_HDR_LEN) as u16);
+        write_be16(udp, 6, 0);
+
+        // VXLAN header
+        let vxlan = udp.offset(UDP_HDR_LEN as isize);
+
+        write_be32(vxlan, 0, VXLAN_FLAGS_VNI);
+        write_be32(vxlan, 4, vni << 8);
+
+        // the inner L2 length includes the UDP and VXLAN headers
+        let tx_offload = *inner.tx_offload();
+        let l2_len = (tx_offload & TX_OFFLOAD_L2_LEN_MASK).max(ETHER_HDR_LEN as u64) +
+                     (UDP_HDR_LEN + VXLAN_HDR_LEN) as u64;
+
+        *inner.tx_offload() = (tx_offload & !(TX_OFFLOAD_OUTER_MASK | TX_OFFLOAD_L2_LEN_MASK)) |
+                              (l2_len & TX_OFFLOAD_L2_LEN_MASK) |
+                              ((IPV4_HDR_LEN as u64) << TX_OFFLOAD_OUTER_L3_LEN_SHIFT) |
+                              ((ETHER_HDR_LEN as u64) << TX_OFFLOAD_OUTER_L2_LEN_SHIFT);
+
+        let ptype = *inner.packet_type();
+
+        *inner.packet_type() = ptype_outer_to_inner(ptype) |
+                               (RTE_PTYPE_L2_ETHER | RTE_PTYPE_L3_IPV4 | RTE_PTYPE_L4_UDP |
+                                RTE_PTYPE_TUNNEL_VXLAN)
+            .bits;
+    }
+
+    inner.ol_flags |= (PKT_TX_OUTER_IP_CKSUM | PKT_TX_OUTER_IPV4).bits;
+
+    Ok(())
+}
+
+/// Decapsulate the Ethernet frame from the VXLAN tunnel, returns the VNI.
+///
+/// The outer headers must be Ethernet, IPv4 without options and UDP,
+/// the packet type and the offload lengths are restored to the inner layers.
+///
+pub fn vxlan_decap(m: &mut RawMbuf) -> Result<u32> {
+    if (m.data_len as usize) < VXLAN_ENCAP_LEN + ETHER_HDR_LEN {
+        return Err(Error::OsError(libc::EINVAL));
+    }
+
+    let p: *mut u8 = pktmbuf_mtod!(m as *mut RawMbuf, *mut u8);
+
+    let vni = unsafe {
+        let ip = p.offset(ETHER_HDR_LEN as isize);
+        let vxlan = ip.offset((IPV4_HDR_LEN + UDP_HDR_LEN) as isize);
+
+        if read_be16(p, ETHER_TYPE_OFFSET) != ETHER_TYPE_IPV4 || *ip != 0x45 ||
+           *ip.offset(9) != IPPROTO_UDP ||
+           (read_be32(vxlan, 0) & VXLAN_FLAGS_VNI) == 0 {
+            return Err(Error::OsError(libc::EINVAL));
+        }
+
+        read_be32(vxlan, 4) >> 8
+    };
+
+    try!(m.consume(VXLAN_ENCAP_LEN));
+
+    unsafe {
+        let tx_offload = *m.tx_offload();
+        let l2_len = (tx_offload & TX_OFFLOAD_L2_LEN_MASK)
+            .saturating_sub((UDP_HDR_LEN + VXLAN_HDR_LEN) as u64);
+
+        *m.tx_offload() = (tx_offload & !(TX_OFFLOAD_OUTER_MASK | TX_OFFLOAD_L2_LEN_MASK)) |
+                          l2_len;
+
+        let ptype = *m.packet_type();
+
+        *m.packet_type() = ptype_inner_to_outer(ptype);
+    }
+
+    m.ol_flags &= !(PKT_TX_OUTER_IP_CKSUM | PKT_TX_OUTER_IPV4 | PKT_TX_OUTER_IPV6 |
+                    PKT_RX_EIP_CKSUM_BAD)
+        .bits;
+
+    Ok(vni)
+}
+
+const GRE_HDR_LEN: usize = 4;
+const GRE_FLAG_CSUM: u16 = 0x8000;
+const GRE_FLAG_KEY: u16 = 0x2000;
+const GRE_FLAG_SEQ: u16 = 0x1000;
+const GRE_VERSION_MASK: u16 = 0x0007;
+
+const IPPROTO_GRE: u8 = 47;
+
+/// The GRE header of a tunneled packet.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct GreHdr {
+    /// The Ethernet type of the payload, `ETHER_TYPE_TEB` for the Ethernet frames.
+    pub protocol_type: u16,
+    /// The checksum of the GRE header and payload.
+    pub checksum: Option<u16>,
+    pub key: Option<u32>,
+    pub seq: Option<u32>,
+}
+
+impl GreHdr {
+    /// The length of the header with its optional fields.
+    pub fn len(&self) -> usize {
+        GRE_HDR_LEN + if self.checksum.is_some() { 4 } else { 0 } +
+        if self.key.is_some() { 4 } else { 0 } + if self.seq.is_some() { 4 } else { 0 }
+    }
+}
+
+/// Encapsulate the packet into the GRE tunnel.
+///
+/// When `protocol_type` is `ETHER_TYPE_TEB`, the whole Ethernet frame is tunneled,
+/// otherwise only its L3 payload and the Ethernet header is reused as the outer one.
+/// The outer addresses are given in the host byte order.
+///
+pub fn gre_encap(inner: &mut RawMbuf,
+                 key: Option<u32>,
+                 seq: Option<u32>,
+                 protocol_type: u16,
+                 outer_src: u32,
+                 outer_dst: u32)
+                 -> Result<()> {
+    encap_gre(inner, key, seq, protocol_type, outer_src, outer_dst, false)
+}
+
+/// Encapsulate the packet into the GRE tunnel with the checksum,
+/// which is computed in software since the NICs don't offload it.
+///
+/// The packet must be contiguous.
+///
+pub fn gre_encap_with_checksum(inner: &mut RawMbuf,
+                               key: Option<u32>,
+                               seq: Option<u32>,
+                               protocol_type: u16,
+                               outer_src: u32,
+                               outer_dst: u32)
+                               -> Result<()> {
+    encap_gre(inner, key, seq, protocol_type, outer_src, outer_dst, true)
+}
+
+fn encap_gre(inner: &mut RawMbuf,
+             key: Option<u32>,
+             seq: Option<u32>,
+             protocol_type: u16,
+             outer_src: u32,
+             outer_dst: u32,
+             checksum: bool)
+             -> Result<()> {
+    if (inner.data_len as usize) < ETHER_HDR_LEN || (checksum && !inner.is_contiguous()) {
+        return Err(Error::OsError(libc::EINVAL));
+    }
+
+    let hdr = GreHdr {
+        protocol_type: protocol_type,
+        checksum: if checksum { Some(0) } else { None },
+        key: key,
+        seq: seq,
+    };
+    let gre_len = hdr.len();
+    let teb = protocol_type == ETHER_TYPE_TEB;
+
+    // the Ethernet header of the inner packet is replaced unless the frame is tunneled
+    let payload_len = inner.pkt_len as usize - if teb { 0 } else { ETHER_HDR_LEN };
+    let encap_len = IPV4_HDR_LEN + gre_len + if teb { ETHER_HDR_LEN } else { 0 };
+
+    if IPV4_HDR_LEN + gre_len + payload_len > 0xFFFF {
+        return Err(Error::OsError(libc::EINVAL));
+    }
+
+    let p = try!(inner.prepend(encap_len));
+
+    unsafe {
+        // outer Ethernet header
+        ptr::copy_nonoverlapping(p.offset(encap_len as isize), p, ETHER_TYPE_OFFSET);
+        write_be16(p, ETHER_TYPE_OFFSET, ETHER_TYPE_IPV4);
+
+        // outer IPv4 header, the checksum is computed by the hardware
+        let ip = p.offset(ETHER_HDR_LEN as isize);
+
+        ptr::write_bytes(ip, 0, IPV4_HDR_LEN);
+        *ip = 0x45;
+        write_be16(ip, 2, (IPV4_HDR_LEN + gre_len + payload_len) as u16);
+        *ip.offset(8) = IPV4_DEFAULT_TTL;
+        *ip.offset(9) = IPPROTO_GRE;
+        write_be32(ip, 12, outer_src);
+        write_be32(ip, 16, outer_dst);
+
+        // GRE header and the optional fields
+        let gre = ip.offset(IPV4_HDR_LEN as isize);
+        let mut flags = 0;
+        let mut off = GRE_HDR_LEN;
+
+        if checksum {
+            flags |= GRE_FLAG_CSUM;
+            write_be32(gre, off, 0);
+            off += 4;
+        }
+
+        if let Some(key) = key {
+            flags |= GRE_FLAG_KEY;
+            write_be32(gre, off, key);
+            off += 4;
+        }
+
+        if let Some(seq) = seq {
+            flags |= GRE_FLAG_SEQ;
+            write_be32(gre, off, seq);
+        }
+
+        write_be16(gre, 0, flags);
+        write_be16(gre, 2, protocol_type);
+
+        if checksum {
+            let csum = !ip::raw_cksum(::std::slice::from_raw_parts(gre, gre_len + payload_len));
+
+            write_be16(gre, GRE_HDR_LEN, csum);
+        }
+
+        // the inner L2 length includes the GRE header
+        let tx_offload = *inner.tx_offload();
+        let l2_len = if teb {
+            (tx_offload & TX_OFFLOAD_L2_LEN_MASK).max(ETHER_HDR_LEN as u64) + gre_len as u64
+        } else {
+            gre_len as u64
+        };
+
+        *inner.tx_offload() = (tx_offload & !(TX_OFFLOAD_OUTER_MASK | TX_OFFLOAD_L2_LEN_MASK)) |
+                              (l2_len & TX_OFFLOAD_L2_LEN_MASK) |
+                              ((IPV4_HDR_LEN as u64) << TX_OFFLOAD_OUTER_L3_LEN_SHIFT) |
+                              ((ETHER_HDR_LEN as u64) << TX_OFFLOAD_OUTER_L2_LEN_SHIFT);
+
+        // the inner L2 header is only carried by the transparent Ethernet bridging
+        let ptype = *inner.packet_type() & if teb { !0 } else { !RTE_PTYPE_L2_MASK.bits };
+        let tunnel = if teb { RTE_PTYPE_TUNNEL_GRENAT } else { RTE_PTYPE_TUNNEL_GRE };
+
+        *inner.packet_type() = ptype_outer_to_inner(ptype) |
+                               (RTE_PTYPE_L2_ETHER | RTE_PTYPE_L3_IPV4 | tunnel).bits;
+    }
+
+    inner.ol_flags |= (PKT_TX_OUTER_IP_CKSUM | PKT_TX_OUTER_IPV4).bits;
+
+    Ok(())
+}
+
+/// Decapsulate the packet from the GRE tunnel, returns the GRE header.
+///
+/// The outer IPv4 and GRE headers are stripped, and the outer Ethernet header too
+/// when the payload is an Ethernet frame, otherwise its type is set to the GRE protocol.
+///
+pub fn gre_decap(m: &mut RawMbuf) -> Result<GreHdr> {
+    let data_len = m.data_len as usize;
+
+    if data_len < ETHER_HDR_LEN + IPV4_HDR_LEN + GRE_HDR_LEN {
+        return Err(Error::OsError(libc::EINVAL));
+    }
+
+    let p: *mut u8 = pktmbuf_mtod!(m as *mut RawMbuf, *mut u8);
+
+    let hdr = unsafe {
+        let ip = p.offset(ETHER_HDR_LEN as isize);
+        let gre = ip.offset(IPV4_HDR_LEN as isize);
+
+        if read_be16(p, ETHER_TYPE_OFFSET) != ETHER_TYPE_IPV4 || *ip != 0x45 ||
+           *ip.offset(9) != IPPROTO_GRE {
+            return Err(Error::OsError(libc::EINVAL));
+        }
+
+        let flags = read_be16(gre, 0);
+        let mut hdr = GreHdr {
+            protocol_type: read_be16(gre, 2),
+            checksum: None,
+            key: None,
+            seq: None,
+        };
+
+        if (flags & GRE_VERSION_MASK) != 0 {
+            return Err(Error::OsError(libc::ENOTSUP));
+        }
+
+        // the length of the optional fields is known from the flags
+        let opt_len = |f: u16| if (flags & f) != 0 { 4 } else { 0 };
+
+        if ETHER_HDR_LEN + IPV4_HDR_LEN + GRE_HDR_LEN + opt_len(GRE_FLAG_CSUM) +
+           opt_len(GRE_FLAG_KEY) + opt_len(GRE_FLAG_SEQ) > data_len {
+            return Err(Error::OsError(libc::EINVAL));
+        }
+
+        let mut off = GRE_HDR_LEN;
+
+        if (flags & GRE_FLAG_CSUM) != 0 {
+            hdr.checksum = Some(read_be16(gre, off));
+            off += 4;
+        }
+
+        if (flags & GRE_FLAG_KEY) != 0 {
+            hdr.key = Some(read_be32(gre, off));
+            off += 4;
+        }
+
+        if (flags & GRE_FLAG_SEQ) != 0 {
+            hdr.seq = Some(read_be32(gre, off));
+        }
+
+        hdr
+    };
+
+    let gre_len = hdr.len();
+    let teb = hdr.protocol_type == ETHER_TYPE_TEB;
+
+    if teb {
+        try!(m.consume(ETHER_HDR_LEN + IPV4_HDR_LEN + gre_len));
+    } else {
+        let decap_len = IPV4_HDR_LEN + gre_len;
+
+        unsafe {
+            ptr::copy_nonoverlapping(p, p.offset(decap_len as isize), ETHER_TYPE_OFFSET);
+        }
+
+        let p = try!(m.consume(decap_len));
+
+        unsafe {
+            write_be16(p, ETHER_TYPE_OFFSET, hdr.protocol_type);
+        }
+    }
+
+    unsafe {
+        let tx_offload = *m.tx_offload();
+        let l2_len = if teb {
+            (tx_offload & TX_OFFLOAD_L2_LEN_MASK).saturating_sub(gre_len as u64)
+        } else {
+            ETHER_HDR_LEN as u64
+        };
+
+        *m.tx_offload() = (tx_offload & !(TX_OFFLOAD_OUTER_MASK | TX_OFFLOAD_L2_LEN_MASK)) |
+                          l2_len;
+
+        let ptype = ptype_inner_to_outer(*m.packet_type());
+
+        *m.packet_type() = if teb {
+            ptype
+        } else {
+            ptype | RTE_PTYPE_L2_ETHER.bits
+        };
+    }
+
+    m.ol_flags &= !(PKT_TX_OUTER_IP_CKSUM | PKT_TX_OUTER_IPV4 | PKT_TX_OUTER_IPV6 |
+                    PKT_RX_EIP_CKSUM_BAD)
+        .bits;
+
+    Ok(hdr)
+}
+
+#[inline]
+unsafe fn read_be16(p: *const u8, off: usize) -> u16 {
+    u16::from_be(ptr::read_unaligned(p.offset(off as isize) as *const u16))
+}
+
+#[inline]
+unsafe fn read_be32(p: *const u8, off: usize) -> u32 {
+    u32::from_be(ptr::read_unaligned(p.offset(off as isize) as *const u32))
+}
+
+#[inline]
+unsafe fn write_be16(p: *mut u8, off: usize, v: u16) {
+    ptr::write_unaligned(p.offset(off as isize) as *mut u16, v.to_be())
+}
+
+#[inline]
+unsafe fn write_be32(p: *mut u8, off: usize, v: u32) {
+    ptr::write_unaligned(p.offset(off as isize) as *mut u32, v.to_be())
+}
+
 extern "C" {
     fn _rte_pktmbuf_alloc(mp: mempool::RawMemoryPoolPtr) -> RawMbufPtr;
 
@@ -347,4 +1631,14 @@ extern "C" {
     fn _rte_pktmbuf_adj(m: RawMbufPtr, len: libc::uint16_t) -> *mut libc::c_uchar;
 
     fn _rte_pktmbuf_trim(m: RawMbufPtr, len: libc::uint16_t) -> libc::c_int;
+
+    #[cfg(dpdk_ver_18_05)]
+    fn rte_pktmbuf_pool_create_by_ops(name: *const libc::c_char,
+                                      n: libc::c_uint,
+                                      cache_size: libc::c_uint,
+                                      priv_size: libc::uint16_t,
+                                      data_room_size: libc::uint16_t,
+                                      socket_id: libc::c_int,
+                                      ops_name: *const libc::c_char)
+                                      -> mempool::RawMemoryPoolPtr;
 }
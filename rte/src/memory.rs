@@ -1,7 +1,77 @@
+use std::slice;
+
+use ffi;
+
+use malloc;
+
 pub type SocketId = i32;
 
 pub const SOCKET_ID_ANY: SocketId = -1;
 
+/// A physically contiguous segment of huge pages mapped by EAL.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct HugepageInfo {
+    /// Start virtual address.
+    pub vaddr: usize,
+    /// Start physical address.
+    pub iova: u64,
+    /// Length of the segment.
+    pub len: usize,
+    /// Size of the huge pages.
+    pub hugepage_sz: u64,
+    /// NUMA socket ID.
+    pub socket_id: SocketId,
+}
+
+/// Get the layout of the huge page segments mapped by EAL.
+pub fn hugepages_info() -> Vec<HugepageInfo> {
+    let segs = unsafe { ffi::rte_eal_get_physmem_layout() };
+
+    if segs.is_null() {
+        return Vec::new();
+    }
+
+    unsafe { slice::from_raw_parts(segs, ffi::RTE_MAX_MEMSEG as usize) }
+        .iter()
+        .map(|seg| {
+            HugepageInfo {
+                vaddr: seg._bindgen_data_1_[0] as usize,
+                iova: seg.phys_addr,
+                len: seg.len as usize,
+                hugepage_sz: seg.hugepage_sz,
+                socket_id: seg.socket_id,
+            }
+        })
+        .take_while(|seg| seg.vaddr != 0)
+        .collect()
+}
+
+/// Get the total size of the huge pages mapped on the socket, or all sockets with `SOCKET_ID_ANY`.
+pub fn total_memory(socket_id: SocketId) -> u64 {
+    if socket_id == SOCKET_ID_ANY {
+        unsafe { ffi::rte_eal_get_physmem_size() }
+    } else {
+        hugepages_info()
+            .iter()
+            .filter(|seg| seg.socket_id == socket_id)
+            .map(|seg| seg.len as u64)
+            .sum()
+    }
+}
+
+/// Get the free size of the malloc heap on the socket, or all sockets with `SOCKET_ID_ANY`.
+pub fn free_memory(socket_id: SocketId) -> u64 {
+    let heap_free = |socket_id| {
+        malloc::get_socket_stats(socket_id).map_or(0, |stats| stats.heap_freesz_bytes as u64)
+    };
+
+    if socket_id == SOCKET_ID_ANY {
+        (0..ffi::RTE_MAX_NUMA_NODES as SocketId).map(heap_free).sum()
+    } else {
+        heap_free(socket_id)
+    }
+}
+
 pub trait AsRef<'a, T: 'a> {
     fn as_ref(self) -> Option<&'a T>;
 }
@@ -1,13 +1,22 @@
 use std::mem;
+#[cfg(dpdk_ver_18_05)]
+use std::ptr;
 use std::ffi::CStr;
 use std::os::raw::c_void;
+#[cfg(dpdk_ver_16_07)]
+use std::os::raw::{c_char, c_int, c_uint};
 use std::os::unix::io::AsRawFd;
 
+#[cfg(dpdk_ver_18_05)]
+use libc;
+
 use cfile;
 
 use ffi;
 
 use errors::Result;
+#[cfg(dpdk_ver_18_05)]
+use errors::Error;
 use memory::SocketId;
 
 bitflags! {
@@ -81,6 +90,22 @@ pub trait MemoryPoolDebug: MemoryPool {
         self.count() == 0
     }
 
+    /// Return the usage and cache statistics of the mempool.
+    ///
+    /// The default implementation only counts the objects, without the per-lcore caches.
+    ///
+    fn stats(&self) -> MempoolStats {
+        let available = self.count();
+        let in_use = self.free_count();
+
+        MempoolStats {
+            total_objects: available + in_use,
+            available: available,
+            in_use: in_use,
+            ..Default::default()
+        }
+    }
+
     /// Check the consistency of mempool objects.
     ///
     /// Verify the coherency of fields in the mempool structure.
@@ -144,10 +169,126 @@ pub fn create<T, O>(name: &str,
     rte_check!(p, NonNull)
 }
 
-pub fn lookup(name: &str) -> Result<RawMemoryPoolPtr> {
+/// Search a mempool created by this or another process from its name.
+pub fn lookup(name: &str) -> Option<RawMemoryPoolPtr> {
+    let name = match to_cptr!(name) {
+        Ok(name) => name,
+        Err(_) => return None,
+    };
+    let p = unsafe { ffi::rte_mempool_lookup(name) };
+
+    if p.is_null() { None } else { Some(p) }
+}
+
+/// Search a mempool which must exist, e.g. created by the primary process.
+///
+/// Fails with `ENOENT` if the mempool is not found.
+///
+pub fn lookup_existing(name: &str) -> Result<&'static mut RawMemoryPool> {
     let p = unsafe { ffi::rte_mempool_lookup(try!(to_cptr!(name))) };
 
-    rte_check!(p, NonNull)
+    rte_check!(p, NonNull; ok => { unsafe { &mut *p } })
+}
+
+/// Search a mempool from its name, or create it with `create` if it is not found.
+pub fn lookup_or_create<F>(name: &str, create: F) -> Result<RawMemoryPoolPtr>
+    where F: FnOnce(&str) -> Result<RawMemoryPoolPtr>
+{
+    match lookup(name) {
+        Some(p) => Ok(p),
+        None => create(name),
+    }
+}
+
+/// Free the mempool and its memory, all the objects must have been returned to the pool.
+#[cfg(dpdk_ver_16_07)]
+pub fn free(pool: RawMemoryPoolPtr) {
+    unsafe { rte_mempool_free(pool) }
+}
+
+/// Create a mbuf pool in a memory region allocated outside of DPDK,
+/// e.g. the DMA memory of a FPGA driver.
+///
+/// `elt_size` is the size of each mbuf, including the `rte_mbuf` header,
+/// the `priv_size` bytes of private area and the data room.
+///
+/// Each entry of `iova_table` gives the IO address of the region at an offset,
+/// the entries are sorted by offset, the first one starts at the offset 0
+/// and the memory is IOVA contiguous up to the offset of the next entry.
+///
+/// The partially created pool is freed on error.
+///
+#[cfg(dpdk_ver_18_05)]
+pub fn from_external_memory(name: &str,
+                            n: u32,
+                            elt_size: u32,
+                            cache_size: u32,
+                            priv_size: u16,
+                            socket_id: SocketId,
+                            vaddr: *mut c_void,
+                            len: usize,
+                            iova_table: &[(usize, u64)])
+                            -> Result<RawMemoryPoolPtr> {
+    let hdr_size = mem::size_of::<ffi::Struct_rte_mbuf>() as u32 + priv_size as u32;
+
+    if vaddr.is_null() || elt_size < hdr_size || elt_size - hdr_size > u16::max_value() as u32 ||
+       iova_table.first().map_or(true, |&(off, _)| off != 0) ||
+       iova_table.windows(2).any(|w| w[0].0 >= w[1].0) ||
+       iova_table.last().map_or(true, |&(off, _)| off >= len) {
+        return Err(Error::OsError(libc::EINVAL));
+    }
+
+    let mp = unsafe {
+        rte_mempool_create_empty(try!(to_cptr!(name)),
+                                 n,
+                                 elt_size,
+                                 cache_size,
+                                 mem::size_of::<ffi::Struct_rte_pktmbuf_pool_private>() as u32,
+                                 socket_id,
+                                 0)
+    };
+
+    let mp = try!(rte_check!(mp, NonNull));
+
+    let ret = unsafe {
+        rte_mempool_set_ops_byname(mp, rte_mbuf_best_mempool_ops(), ptr::null_mut())
+    };
+
+    if ret < 0 {
+        unsafe { rte_mempool_free(mp) };
+
+        return Err(Error::from_ret(ret));
+    }
+
+    let mut mbp_priv = ffi::Struct_rte_pktmbuf_pool_private {
+        mbuf_data_room_size: (elt_size - hdr_size) as u16,
+        mbuf_priv_size: priv_size,
+    };
+
+    unsafe { ffi::rte_pktmbuf_pool_init(mp, &mut mbp_priv as *mut _ as *mut c_void) };
+
+    for (i, &(off, iova)) in iova_table.iter().enumerate() {
+        let end = iova_table.get(i + 1).map_or(len, |&(next, _)| next);
+
+        let ret = unsafe {
+            rte_mempool_populate_iova(mp,
+                                      (vaddr as *mut u8).offset(off as isize) as *mut c_char,
+                                      iova,
+                                      end - off,
+                                      None,
+                                      ptr::null_mut())
+        };
+
+        if ret < 0 {
+            unsafe { rte_mempool_free(mp) };
+
+            return Err(Error::from_ret(ret));
+        }
+    }
+
+    unsafe { _rte_pktmbuf_pool_init_objs(mp) };
+
+    Ok(mp)
 }
 
 /// Dump the status of all mempools on the console
@@ -166,6 +307,135 @@ pub fn walk<T>(callback: Option<MemoryPoolWalkCallback<T>>, arg: Option<&T>) {
     }
 }
 
+/// Check whether the mempool ops of the given name, e.g. `ring_mp_mc` or `stack`,
+/// has been registered.
+#[cfg(dpdk_ver_16_07)]
+pub fn pool_ops_supported(pool_ops_name: &str) -> bool {
+    match to_cptr!(pool_ops_name) {
+        Ok(name) => unsafe { _rte_mempool_ops_is_registered(name) != 0 },
+        Err(_) => false,
+    }
+}
+
+/// The names of the registered mempool ops, in the order of their index.
+#[cfg(dpdk_ver_16_07)]
+pub fn registered_ops() -> Vec<String> {
+    (0..unsafe { _rte_mempool_ops_count() })
+        .map(|idx| unsafe { _rte_mempool_ops_get_name(idx) })
+        .map(|name| unsafe { CStr::from_ptr(name).to_string_lossy().into_owned() })
+        .collect()
+}
+
+/// Check whether the mempool ops of the given name is available,
+/// the same as `pool_ops_supported`.
+#[cfg(dpdk_ver_16_07)]
+pub fn has_ops(name: &str) -> bool {
+    pool_ops_supported(name)
+}
+
+/// Set the ops of an empty mempool, created with `rte_mempool_create_empty`,
+/// before it is populated.
+#[cfg(dpdk_ver_16_07)]
+pub fn set_ops_byname(pool: &mut RawMemoryPool,
+                      pool_ops_name: &str,
+                      pool_config: Option<&mut c_void>)
+                      -> Result<()> {
+    let ret = unsafe {
+        rte_mempool_set_ops_byname(pool,
+                                   try!(to_cptr!(pool_ops_name)),
+                                   mem::transmute(pool_config))
+    };
+
+    rte_check!(ret)
+}
+
+/// The usage and cache statistics of a mempool.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MempoolStats {
+    /// Number of objects of the mempool.
+    pub total_objects: u32,
+    /// Number of available objects, including the ones in the per-lcore caches.
+    pub available: u32,
+    /// Number of objects allocated from the mempool.
+    pub in_use: u32,
+    /// Number of available objects in the per-lcore caches.
+    pub cache_available: u32,
+    /// Number of available objects in the cache of each lcore, indexed by the lcore id.
+    pub per_lcore_cache: Vec<u32>,
+}
+
+struct ObjIterContext<'a> {
+    #[cfg(not(dpdk_ver_16_07))]
+    header_size: usize,
+    f: &'a mut dyn FnMut(*mut c_void),
+}
+
+// Before 16.07, the objects are iterated in the memory chunk of the pool,
+// and the callback gets the start of the object header.
+#[cfg(not(dpdk_ver_16_07))]
+unsafe extern "C" fn obj_iter_trampoline(arg: *mut c_void,
+                                         obj_start: *mut c_void,
+                                         _obj_end: *mut c_void,
+                                         _obj_index: u32) {
+    let ctx = &mut *(arg as *mut ObjIterContext);
+
+    (ctx.f)((obj_start as *mut u8).offset(ctx.header_size as isize) as *mut c_void)
+}
+
+#[cfg(not(dpdk_ver_16_07))]
+unsafe fn raw_obj_iter(pool: &RawMemoryPool, ctx: &mut ObjIterContext) -> u32 {
+    let elt_sz = (pool.header_size + pool.elt_size + pool.trailer_size) as ffi::size_t;
+
+    ffi::rte_mempool_obj_iter(pool.elt_va_start as *mut c_void,
+                              pool.size,
+                              elt_sz,
+                              1,
+                              pool.elt_pa.as_ptr(),
+                              pool.pg_num,
+                              pool.pg_shift,
+                              Some(obj_iter_trampoline),
+                              ctx as *mut ObjIterContext as *mut c_void)
+}
+
+// Since 16.07, the objects are iterated in the pool, and the callback gets the object itself.
+#[cfg(dpdk_ver_16_07)]
+unsafe extern "C" fn obj_iter_trampoline(_mp: RawMemoryPoolPtr,
+                                         arg: *mut c_void,
+                                         obj: *mut c_void,
+                                         _obj_index: c_uint) {
+    let ctx = &mut *(arg as *mut ObjIterContext);
+
+    (ctx.f)(obj)
+}
+
+#[cfg(dpdk_ver_16_07)]
+unsafe fn raw_obj_iter(pool: &RawMemoryPool, ctx: &mut ObjIterContext) -> u32 {
+    rte_mempool_obj_iter(pool as *const RawMemoryPool as RawMemoryPoolPtr,
+                         Some(obj_iter_trampoline),
+                         ctx as *mut ObjIterContext as *mut c_void)
+}
+
+/// Call the closure for each object of the mempool, whether it is allocated or free.
+///
+pub fn obj_iter<T, F: FnMut(*mut T)>(pool: &RawMemoryPool, mut f: F) -> u32 {
+    let mut cb = |obj: *mut c_void| f(obj as *mut T);
+    let mut ctx = ObjIterContext {
+        #[cfg(not(dpdk_ver_16_07))]
+        header_size: pool.header_size as usize,
+        f: &mut cb,
+    };
+
+    unsafe { raw_obj_iter(pool, &mut ctx) }
+}
+
+/// Call the closure with a reference to each object of the mempool.
+///
+/// The function is unsafe because the objects must have been initialized as `T`,
+/// e.g. by the object constructor of the mempool.
+pub unsafe fn obj_iter_cached<T: Clone, F: FnMut(&T)>(pool: &RawMemoryPool, mut f: F) -> u32 {
+    obj_iter(pool, |obj: *mut T| f(&*obj))
+}
+
 impl MemoryPool for RawMemoryPool {
     #[inline]
     fn name(&self) -> &str {
@@ -195,6 +465,23 @@ impl MemoryPoolDebug for RawMemoryPool {
         self.size == self.count()
     }
 
+    fn stats(&self) -> MempoolStats {
+        let per_lcore_cache: Vec<u32> = if self.cache_size == 0 {
+            Vec::new()
+        } else {
+            self.local_cache.iter().map(|cache| cache.len).collect()
+        };
+        let available = self.count();
+
+        MempoolStats {
+            total_objects: self.size,
+            available: available,
+            in_use: self.size - available,
+            cache_available: per_lcore_cache.iter().sum(),
+            per_lcore_cache: per_lcore_cache,
+        }
+    }
+
     fn audit(&self) {
         unsafe { ffi::rte_mempool_audit(self) }
     }
@@ -227,3 +514,57 @@ impl MemoryPoolDebug for RawMemoryPool {
         }
     }
 }
+
+extern "C" {
+    #[cfg(dpdk_ver_16_07)]
+    fn rte_mempool_set_ops_byname(mp: RawMemoryPoolPtr,
+                                  name: *const c_char,
+                                  pool_config: *mut c_void)
+                                  -> c_int;
+
+    #[cfg(dpdk_ver_16_07)]
+    fn _rte_mempool_ops_is_registered(name: *const c_char) -> c_int;
+
+    #[cfg(dpdk_ver_16_07)]
+    fn _rte_mempool_ops_count() -> c_uint;
+
+    #[cfg(dpdk_ver_16_07)]
+    fn _rte_mempool_ops_get_name(idx: c_uint) -> *const c_char;
+
+    #[cfg(dpdk_ver_16_07)]
+    fn rte_mempool_create_empty(name: *const c_char,
+                                n: c_uint,
+                                elt_size: c_uint,
+                                cache_size: c_uint,
+                                private_data_size: c_uint,
+                                socket_id: c_int,
+                                flags: c_uint)
+                                -> RawMemoryPoolPtr;
+
+    #[cfg(dpdk_ver_18_05)]
+    fn rte_mempool_populate_iova(mp: RawMemoryPoolPtr,
+                                 vaddr: *mut c_char,
+                                 iova: u64,
+                                 len: libc::size_t,
+                                 free_cb: Option<unsafe extern "C" fn(*mut c_void, *mut c_void)>,
+                                 opaque: *mut c_void)
+                                 -> c_int;
+
+    #[cfg(dpdk_ver_16_07)]
+    fn rte_mempool_free(mp: RawMemoryPoolPtr);
+
+    #[cfg(dpdk_ver_16_07)]
+    fn rte_mempool_obj_iter(mp: RawMemoryPoolPtr,
+                            obj_cb: Option<unsafe extern "C" fn(RawMemoryPoolPtr,
+                                                                *mut c_void,
+                                                                *mut c_void,
+                                                                c_uint)>,
+                            obj_cb_arg: *mut c_void)
+                            -> u32;
+
+    #[cfg(dpdk_ver_18_05)]
+    fn rte_mbuf_best_mempool_ops() -> *const c_char;
+
+    #[cfg(dpdk_ver_18_05)]
+    fn _rte_pktmbuf_pool_init_objs(mp: RawMemoryPoolPtr) -> c_uint;
+}
@@ -1,5 +1,11 @@
+use std::ffi::CStr;
+
+use ffi;
 use ffi::Struct_rte_memzone;
 
+use errors::Result;
+use memory::SocketId;
+
 /// RTE Memzone
 ///
 /// The goal of the memzone allocator is to reserve contiguous portions of physical memory.
@@ -19,3 +25,126 @@ pub struct MemoryZone(*const Struct_rte_memzone);
 pub fn from_raw(zone: *const Struct_rte_memzone) -> MemoryZone {
     MemoryZone(zone)
 }
+
+bitflags! {
+    pub flags MemzoneFlags: u32 {
+        /// Use 2MB pages.
+        const RTE_MEMZONE_2MB            = 0x00000001,
+        /// Use 1GB pages.
+        const RTE_MEMZONE_1GB            = 0x00000002,
+        /// Use available page size if the requested one is not available.
+        const RTE_MEMZONE_SIZE_HINT_ONLY = 0x00000004,
+        /// Use 16MB pages.
+        const RTE_MEMZONE_16MB           = 0x00000100,
+        /// Use 16GB pages.
+        const RTE_MEMZONE_16GB           = 0x00000200,
+        /// Use 256KB pages.
+        const RTE_MEMZONE_256KB          = 0x00010000,
+        /// Use 256MB pages.
+        const RTE_MEMZONE_256MB          = 0x00020000,
+        /// Use 512MB pages.
+        const RTE_MEMZONE_512MB          = 0x00040000,
+        /// Use 4GB pages.
+        const RTE_MEMZONE_4GB            = 0x00080000,
+    }
+}
+
+pub type RawMemzone = Struct_rte_memzone;
+
+/// A named zone of physically contiguous memory.
+///
+/// A zone reserved by `reserve()` should be released with `free()`,
+/// dropping it keeps the memory reserved and logs a warning.
+///
+pub struct Memzone {
+    mz: *const RawMemzone,
+    owned: bool,
+}
+
+impl Drop for Memzone {
+    fn drop(&mut self) {
+        if self.owned {
+            warn!("memzone {} dropped without free", self.name());
+        }
+    }
+}
+
+impl Memzone {
+    /// Reserve a portion of physical memory.
+    pub fn reserve(name: &str,
+                   len: usize,
+                   socket_id: SocketId,
+                   flags: MemzoneFlags)
+                   -> Result<Memzone> {
+        let mz = unsafe {
+            ffi::rte_memzone_reserve(try!(to_cptr!(name)),
+                                     len as ffi::size_t,
+                                     socket_id,
+                                     flags.bits)
+        };
+
+        rte_check!(mz, NonNull; ok => {
+            Memzone {
+                mz: mz,
+                owned: true,
+            }
+        })
+    }
+
+    /// Lookup for a memzone reserved by this or another process.
+    pub fn lookup(name: &str) -> Option<Memzone> {
+        let name = match to_cptr!(name) {
+            Ok(name) => name,
+            Err(_) => return None,
+        };
+        let mz = unsafe { ffi::rte_memzone_lookup(name) };
+
+        if mz.is_null() {
+            None
+        } else {
+            Some(Memzone {
+                mz: mz,
+                owned: false,
+            })
+        }
+    }
+
+    /// Name of the memory zone.
+    pub fn name(&self) -> &str {
+        unsafe { CStr::from_ptr((*self.mz).name.as_ptr()).to_str().unwrap_or("") }
+    }
+
+    /// Start virtual address.
+    pub fn as_ptr<T>(&self) -> *mut T {
+        unsafe { (*self.mz)._bindgen_data_1_[0] as *mut T }
+    }
+
+    /// Start physical address.
+    pub fn iova(&self) -> u64 {
+        unsafe { (*self.mz).phys_addr }
+    }
+
+    /// Length of the memzone.
+    pub fn len(&self) -> usize {
+        unsafe { (*self.mz).len as usize }
+    }
+
+    /// NUMA socket ID.
+    pub fn socket_id(&self) -> SocketId {
+        unsafe { (*self.mz).socket_id }
+    }
+
+    /// Extract the raw pointer from an underlying object.
+    pub fn as_raw(&self) -> *const RawMemzone {
+        self.mz
+    }
+
+    /// Free the memzone, it must not be used by another process anymore.
+    pub fn free(mut self) -> Result<()> {
+        self.owned = false;
+
+        let ret = unsafe { ffi::rte_memzone_free(self.mz) };
+
+        rte_check!(ret)
+    }
+}
@@ -0,0 +1,155 @@
+use std::mem;
+
+use libc;
+
+use errors::Result;
+
+/// Meter color
+#[repr(u32)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum MeterColor {
+    Green = 0, // e_RTE_METER_GREEN
+    Yellow = 1, // e_RTE_METER_YELLOW
+    Red = 2, // e_RTE_METER_RED
+}
+
+impl From<u32> for MeterColor {
+    fn from(v: u32) -> Self {
+        match v {
+            0 => MeterColor::Green,
+            1 => MeterColor::Yellow,
+            _ => MeterColor::Red,
+        }
+    }
+}
+
+/// srTCM parameters per metered traffic flow.
+///
+/// The CIR is measured in bytes of IP packet per second, including the IP header,
+/// but not including the L2 header. The CBS and the EBS are measured in bytes of IP packet,
+/// at least one of them has to be greater than zero.
+///
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default)]
+pub struct SrTcmProfile {
+    /// Committed Information Rate (bytes/second)
+    pub cir: u64,
+    /// Committed Burst Size (bytes)
+    pub cbs: u64,
+    /// Excess Burst Size (bytes)
+    pub ebs: u64,
+}
+
+/// trTCM parameters per metered traffic flow.
+///
+/// The PIR has to be greater than or equal to the CIR,
+/// both the CBS and the PBS have to be greater than zero.
+///
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default)]
+pub struct TrTcmProfile {
+    /// Committed Information Rate (bytes/second)
+    pub cir: u64,
+    /// Peak Information Rate (bytes/second)
+    pub pir: u64,
+    /// Committed Burst Size (bytes)
+    pub cbs: u64,
+    /// Peak Burst Size (bytes)
+    pub pbs: u64,
+}
+
+/// Single Rate Three Color Marker (srTCM), RFC 2697
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct SrTcm {
+    data: [u64; 7],
+}
+
+impl SrTcm {
+    /// Create a srTCM run-time context for the profile.
+    pub fn new(profile: &SrTcmProfile) -> Result<SrTcm> {
+        let mut m: SrTcm = unsafe { mem::zeroed() };
+        let mut params = *profile;
+
+        let ret = unsafe { rte_meter_srtcm_config(&mut m, &mut params) };
+
+        rte_check!(ret; ok => { m })
+    }
+
+    /// Color the packet in color blind mode.
+    ///
+    /// `time` is the current CPU time stamp, measured in CPU cycles.
+    ///
+    pub fn color(&mut self, pkt_len: u32, time: u64) -> MeterColor {
+        MeterColor::from(unsafe { _rte_meter_srtcm_color_blind_check(self, time, pkt_len) })
+    }
+
+    /// Color the packet in color aware mode.
+    pub fn color_aware(&mut self, pkt_len: u32, time: u64, color: MeterColor) -> MeterColor {
+        MeterColor::from(unsafe {
+            _rte_meter_srtcm_color_aware_check(self, time, pkt_len, color as u32)
+        })
+    }
+}
+
+/// Two Rate Three Color Marker (trTCM), RFC 2698
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct TrTcm {
+    data: [u64; 10],
+}
+
+impl TrTcm {
+    /// Create a trTCM run-time context for the profile.
+    pub fn new(profile: &TrTcmProfile) -> Result<TrTcm> {
+        let mut m: TrTcm = unsafe { mem::zeroed() };
+        let mut params = *profile;
+
+        let ret = unsafe { rte_meter_trtcm_config(&mut m, &mut params) };
+
+        rte_check!(ret; ok => { m })
+    }
+
+    /// Color the packet in color blind mode.
+    ///
+    /// `time` is the current CPU time stamp, measured in CPU cycles.
+    ///
+    pub fn color(&mut self, pkt_len: u32, time: u64) -> MeterColor {
+        MeterColor::from(unsafe { _rte_meter_trtcm_color_blind_check(self, time, pkt_len) })
+    }
+
+    /// Color the packet in color aware mode.
+    pub fn color_aware(&mut self, pkt_len: u32, time: u64, color: MeterColor) -> MeterColor {
+        MeterColor::from(unsafe {
+            _rte_meter_trtcm_color_aware_check(self, time, pkt_len, color as u32)
+        })
+    }
+}
+
+extern "C" {
+    fn rte_meter_srtcm_config(m: *mut SrTcm, params: *mut SrTcmProfile) -> libc::c_int;
+
+    fn rte_meter_trtcm_config(m: *mut TrTcm, params: *mut TrTcmProfile) -> libc::c_int;
+
+    fn _rte_meter_srtcm_color_blind_check(m: *mut SrTcm,
+                                          time: libc::uint64_t,
+                                          pkt_len: libc::uint32_t)
+                                          -> libc::uint32_t;
+
+    fn _rte_meter_srtcm_color_aware_check(m: *mut SrTcm,
+                                          time: libc::uint64_t,
+                                          pkt_len: libc::uint32_t,
+                                          pkt_color: libc::uint32_t)
+                                          -> libc::uint32_t;
+
+    fn _rte_meter_trtcm_color_blind_check(m: *mut TrTcm,
+                                          time: libc::uint64_t,
+                                          pkt_len: libc::uint32_t)
+                                          -> libc::uint32_t;
+
+    fn _rte_meter_trtcm_color_aware_check(m: *mut TrTcm,
+                                          time: libc::uint64_t,
+                                          pkt_len: libc::uint32_t,
+                                          pkt_color: libc::uint32_t)
+                                          -> libc::uint32_t;
+}
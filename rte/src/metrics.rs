@@ -0,0 +1,197 @@
+//! Named metrics of the ports, exposed to the other processes and the telemetry.
+//!
+//! The metrics must be initialised with `init()` in the primary process,
+//! before they are registered and updated.
+
+use std::ptr;
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+
+use libc;
+
+use errors::{Error, Result};
+use memory::SocketId;
+
+/// The maximum length of a metric name, including the terminating NUL.
+const RTE_METRICS_MAX_NAME_LEN: usize = 64;
+
+#[repr(C)]
+struct RawMetricName {
+    name: [c_char; RTE_METRICS_MAX_NAME_LEN],
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Default)]
+struct RawMetricValue {
+    key: libc::uint16_t,
+    value: libc::uint64_t,
+}
+
+/// Initialise the metrics library on the socket.
+pub fn init(socket_id: SocketId) {
+    unsafe { rte_metrics_init(socket_id) }
+}
+
+/// The value of a metric of the port.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MetricValue {
+    pub key: u16,
+    pub name: String,
+    pub value: u64,
+}
+
+/// A registered metric.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Metric {
+    key: u16,
+}
+
+impl Metric {
+    /// Register a metric, returns the existing one if the name is already registered.
+    pub fn register(name: &str) -> Result<Metric> {
+        let ret = unsafe { rte_metrics_reg_name(try!(to_cptr!(name))) };
+
+        rte_check!(ret; ok => { Metric { key: ret as u16 } })
+    }
+
+    /// The key of the metric.
+    pub fn key(&self) -> u16 {
+        self.key
+    }
+
+    /// Update the value of the metric of the port.
+    pub fn update(&self, port_id: u16, value: u64) -> Result<()> {
+        let ret = unsafe { rte_metrics_update_value(port_id as libc::c_int, self.key, value) };
+
+        rte_check!(ret)
+    }
+
+    /// Get the values of all the metrics of the port.
+    pub fn get_all(port_id: u16) -> Result<Vec<MetricValue>> {
+        let ret = unsafe { rte_metrics_get_names(ptr::null_mut(), 0) };
+        let nb_names = try!(rte_check!(ret; ok => { ret as usize }));
+
+        let mut names = Vec::with_capacity(nb_names);
+
+        let ret = unsafe { rte_metrics_get_names(names.as_mut_ptr(), nb_names as u16) };
+
+        try!(rte_check!(ret; ok => { unsafe { names.set_len(ret as usize) } }));
+
+        let ret = unsafe { rte_metrics_get_values(port_id as libc::c_int, ptr::null_mut(), 0) };
+        let nb_values = try!(rte_check!(ret; ok => { ret as usize }));
+
+        let mut values = vec![RawMetricValue::default(); nb_values];
+
+        let ret = unsafe {
+            rte_metrics_get_values(port_id as libc::c_int, values.as_mut_ptr(), nb_values as u16)
+        };
+
+        rte_check!(ret; ok => {
+            values.truncate(ret as usize);
+
+            values.iter()
+                .map(|v| {
+                    let name = names.get(v.key as usize)
+                        .map(|name: &RawMetricName| unsafe { CStr::from_ptr(name.name.as_ptr()) })
+                        .map(|name| name.to_string_lossy().into_owned())
+                        .unwrap_or_default();
+
+                    MetricValue {
+                        key: v.key,
+                        name: name,
+                        value: v.value,
+                    }
+                })
+                .collect()
+        })
+    }
+}
+
+/// A set of metrics registered together, whose keys are contiguous.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct MetricSet {
+    key: u16,
+    count: u16,
+}
+
+impl MetricSet {
+    /// Register a set of metrics.
+    pub fn register(names: &[&str]) -> Result<MetricSet> {
+        let names = try!(names.iter()
+            .map(|name| CString::new(*name))
+            .collect::<::std::result::Result<Vec<CString>, _>>());
+        let ptrs = names.iter().map(|name| name.as_ptr()).collect::<Vec<*const c_char>>();
+
+        let ret = unsafe { rte_metrics_reg_names(ptrs.as_ptr(), ptrs.len() as u16) };
+
+        rte_check!(ret; ok => {
+            MetricSet {
+                key: ret as u16,
+                count: ptrs.len() as u16,
+            }
+        })
+    }
+
+    /// The metric of the set at the index.
+    pub fn metric(&self, index: u16) -> Option<Metric> {
+        if index < self.count {
+            Some(Metric { key: self.key + index })
+        } else {
+            None
+        }
+    }
+
+    /// The number of the metrics in the set.
+    pub fn len(&self) -> usize {
+        self.count as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Update the values of all the metrics of the set, in the registered order.
+    ///
+    /// Fails with `EINVAL` if the number of values doesn't match the set.
+    ///
+    pub fn update(&self, port_id: u16, values: &[u64]) -> Result<()> {
+        if values.len() != self.count as usize {
+            return Err(Error::OsError(libc::EINVAL));
+        }
+
+        let ret = unsafe {
+            rte_metrics_update_values(port_id as libc::c_int,
+                                      self.key,
+                                      values.as_ptr(),
+                                      values.len() as u32)
+        };
+
+        rte_check!(ret)
+    }
+}
+
+extern "C" {
+    fn rte_metrics_init(socket_id: libc::c_int);
+
+    fn rte_metrics_reg_name(name: *const c_char) -> libc::c_int;
+
+    fn rte_metrics_reg_names(names: *const *const c_char, cnt_names: libc::uint16_t) -> libc::c_int;
+
+    fn rte_metrics_get_names(names: *mut RawMetricName, capacity: libc::uint16_t) -> libc::c_int;
+
+    fn rte_metrics_get_values(port_id: libc::c_int,
+                              values: *mut RawMetricValue,
+                              capacity: libc::uint16_t)
+                              -> libc::c_int;
+
+    fn rte_metrics_update_value(port_id: libc::c_int,
+                                key: libc::uint16_t,
+                                value: libc::uint64_t)
+                                -> libc::c_int;
+
+    fn rte_metrics_update_values(port_id: libc::c_int,
+                                 key: libc::uint16_t,
+                                 values: *const libc::uint64_t,
+                                 count: libc::uint32_t)
+                                 -> libc::c_int;
+}
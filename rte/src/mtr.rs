@@ -0,0 +1,352 @@
+//! Metering and policing of the traffic flows on the Ethernet devices.
+//!
+//! A meter object is built with a meter profile, which defines the token buckets
+//! of the metering algorithm, and a meter policy, which defines the actions
+//! for the packets of each color.
+
+use std::mem;
+use std::ptr;
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_void};
+
+use libc;
+
+use errors::Result;
+use ethdev::PortId;
+
+#[repr(C)]
+struct RawMtrError {
+    type_: libc::c_uint,
+    cause: *const c_void,
+    message: *const c_char,
+}
+
+impl Default for RawMtrError {
+    fn default() -> Self {
+        unsafe { mem::zeroed() }
+    }
+}
+
+fn mtr_check(port_id: PortId, ret: libc::c_int, err: &RawMtrError) -> Result<()> {
+    if ret < 0 && !err.message.is_null() {
+        debug!("meter of port {} failed, {}",
+               port_id,
+               unsafe { CStr::from_ptr(err.message).to_string_lossy() });
+    }
+
+    rte_check!(ret)
+}
+
+const RTE_MTR_SRTCM_RFC2697: libc::c_uint = 1;
+const RTE_MTR_TRTCM_RFC2698: libc::c_uint = 2;
+
+/// The metering algorithm and its token buckets, in bytes or packets.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MtrProfileParams {
+    /// Single Rate Three Color Marker, RFC 2697
+    Srtcm {
+        /// Committed Information Rate
+        cir: u64,
+        /// Committed Burst Size
+        cbs: u64,
+        /// Excess Burst Size
+        ebs: u64,
+        packet_mode: bool,
+    },
+    /// Two Rate Three Color Marker, RFC 2698
+    Trtcm {
+        /// Committed Information Rate
+        cir: u64,
+        /// Peak Information Rate
+        pir: u64,
+        /// Committed Burst Size
+        cbs: u64,
+        /// Peak Burst Size
+        pbs: u64,
+        packet_mode: bool,
+    },
+}
+
+/// The meter profiles of an Ethernet device.
+pub struct MtrProfile;
+
+impl MtrProfile {
+    /// Add a meter profile which can be used by the meter objects.
+    pub fn add(port_id: PortId, profile_id: u32, profile: &MtrProfileParams) -> Result<()> {
+        let (alg, cir, pir, cbs, ebs, packet_mode) = match *profile {
+            MtrProfileParams::Srtcm { cir, cbs, ebs, packet_mode } => {
+                (RTE_MTR_SRTCM_RFC2697, cir, 0, cbs, ebs, packet_mode)
+            }
+            MtrProfileParams::Trtcm { cir, pir, cbs, pbs, packet_mode } => {
+                (RTE_MTR_TRTCM_RFC2698, cir, pir, cbs, pbs, packet_mode)
+            }
+        };
+        let mut err = RawMtrError::default();
+
+        let ret = unsafe {
+            _rte_mtr_meter_profile_add(u8::from(port_id) as u16,
+                                       profile_id,
+                                       alg,
+                                       cir,
+                                       pir,
+                                       cbs,
+                                       ebs,
+                                       packet_mode as libc::c_int,
+                                       &mut err)
+        };
+
+        mtr_check(port_id, ret, &err)
+    }
+
+    /// Delete a meter profile which is not used by any meter object.
+    pub fn delete(port_id: PortId, profile_id: u32) -> Result<()> {
+        let mut err = RawMtrError::default();
+
+        let ret = unsafe {
+            rte_mtr_meter_profile_delete(u8::from(port_id) as u16, profile_id, &mut err)
+        };
+
+        mtr_check(port_id, ret, &err)
+    }
+}
+
+/// The action applied to the packets of a color.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FlowAction {
+    /// Pass the packets to the next processing stage.
+    Passthru,
+    /// Drop the packets.
+    Drop,
+    /// Steer the packets to a RX queue.
+    Queue(u16),
+    /// Mark the packets with an id reported in the mbuf.
+    Mark(u32),
+}
+
+const RTE_FLOW_ACTION_TYPE_END: libc::c_uint = 0;
+const RTE_FLOW_ACTION_TYPE_PASSTHRU: libc::c_uint = 2;
+const RTE_FLOW_ACTION_TYPE_MARK: libc::c_uint = 4;
+const RTE_FLOW_ACTION_TYPE_QUEUE: libc::c_uint = 6;
+const RTE_FLOW_ACTION_TYPE_DROP: libc::c_uint = 7;
+
+#[repr(C)]
+struct RawFlowAction {
+    type_: libc::c_uint,
+    conf: *const c_void,
+}
+
+#[repr(C)]
+struct RawMeterPolicyParams {
+    actions: [*const RawFlowAction; 3],
+}
+
+/// The meter policies of an Ethernet device.
+pub struct MeterPolicy;
+
+impl MeterPolicy {
+    /// Add a meter policy with the actions of the green, yellow and red packets.
+    pub fn add(port_id: PortId, policy_id: u32, actions: &[Option<FlowAction>; 3]) -> Result<()> {
+        // the `rte_flow_action_queue` and `rte_flow_action_mark` configurations
+        let confs = actions.iter()
+            .map(|action| {
+                match *action {
+                    Some(FlowAction::Queue(index)) => index as u32,
+                    Some(FlowAction::Mark(id)) => id,
+                    _ => 0,
+                }
+            })
+            .collect::<Vec<u32>>();
+
+        let lists = actions.iter()
+            .zip(confs.iter())
+            .map(|(action, conf)| {
+                action.map(|action| {
+                    let (type_, conf) = match action {
+                        FlowAction::Passthru => (RTE_FLOW_ACTION_TYPE_PASSTHRU, ptr::null()),
+                        FlowAction::Drop => (RTE_FLOW_ACTION_TYPE_DROP, ptr::null()),
+                        FlowAction::Queue(_) => {
+                            (RTE_FLOW_ACTION_TYPE_QUEUE, conf as *const u32 as *const c_void)
+                        }
+                        FlowAction::Mark(_) => {
+                            (RTE_FLOW_ACTION_TYPE_MARK, conf as *const u32 as *const c_void)
+                        }
+                    };
+
+                    [RawFlowAction {
+                         type_: type_,
+                         conf: conf,
+                     },
+                     RawFlowAction {
+                         type_: RTE_FLOW_ACTION_TYPE_END,
+                         conf: ptr::null(),
+                     }]
+                })
+            })
+            .collect::<Vec<Option<[RawFlowAction; 2]>>>();
+
+        let mut policy = RawMeterPolicyParams { actions: [ptr::null(); 3] };
+
+        for (p, list) in policy.actions.iter_mut().zip(lists.iter()) {
+            if let Some(ref list) = *list {
+                *p = list.as_ptr();
+            }
+        }
+
+        let mut err = RawMtrError::default();
+
+        let ret = unsafe {
+            rte_mtr_meter_policy_add(u8::from(port_id) as u16, policy_id, &mut policy, &mut err)
+        };
+
+        mtr_check(port_id, ret, &err)
+    }
+
+    /// Delete a meter policy which is not used by any meter object.
+    pub fn delete(port_id: PortId, policy_id: u32) -> Result<()> {
+        let mut err = RawMtrError::default();
+
+        let ret = unsafe {
+            rte_mtr_meter_policy_delete(u8::from(port_id) as u16, policy_id, &mut err)
+        };
+
+        mtr_check(port_id, ret, &err)
+    }
+}
+
+/// The parameters of a meter object.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct MtrParams {
+    /// The meter profile added with `MtrProfile::add()`.
+    pub meter_profile_id: u32,
+    /// The meter policy added with `MeterPolicy::add()`.
+    pub meter_policy_id: u32,
+    /// Use the color of the previous meter of the packets as the input color.
+    pub use_prev_mtr_color: bool,
+    /// The `RTE_MTR_STATS_*` statistics to enable.
+    pub stats_mask: u64,
+}
+
+/// A meter object of an Ethernet device, which must be destroyed explicitly.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Mtr {
+    port_id: PortId,
+    mtr_id: u32,
+}
+
+impl Mtr {
+    /// Create a meter object, the metering is enabled if `enable` is set.
+    pub fn create(port_id: PortId, mtr_id: u32, params: &MtrParams, enable: bool) -> Result<Mtr> {
+        let mut err = RawMtrError::default();
+
+        let ret = unsafe {
+            _rte_mtr_create(u8::from(port_id) as u16,
+                            mtr_id,
+                            params.meter_profile_id,
+                            params.meter_policy_id,
+                            params.use_prev_mtr_color as libc::c_int,
+                            enable as libc::c_int,
+                            params.stats_mask,
+                            &mut err)
+        };
+
+        try!(mtr_check(port_id, ret, &err));
+
+        Ok(Mtr {
+            port_id: port_id,
+            mtr_id: mtr_id,
+        })
+    }
+
+    pub fn port_id(&self) -> PortId {
+        self.port_id
+    }
+
+    pub fn mtr_id(&self) -> u32 {
+        self.mtr_id
+    }
+
+    /// Enable the metering of the meter object.
+    pub fn enable(&self) -> Result<()> {
+        let mut err = RawMtrError::default();
+
+        let ret = unsafe {
+            rte_mtr_meter_enable(u8::from(self.port_id) as u16, self.mtr_id, &mut err)
+        };
+
+        mtr_check(self.port_id, ret, &err)
+    }
+
+    /// Disable the metering, all the packets are colored green.
+    pub fn disable(&self) -> Result<()> {
+        let mut err = RawMtrError::default();
+
+        let ret = unsafe {
+            rte_mtr_meter_disable(u8::from(self.port_id) as u16, self.mtr_id, &mut err)
+        };
+
+        mtr_check(self.port_id, ret, &err)
+    }
+
+    /// Destroy the meter object, which must not be used by any flow.
+    pub fn destroy(self) -> Result<()> {
+        let mut err = RawMtrError::default();
+
+        let ret = unsafe { rte_mtr_destroy(u8::from(self.port_id) as u16, self.mtr_id, &mut err) };
+
+        mtr_check(self.port_id, ret, &err)
+    }
+}
+
+extern "C" {
+    fn rte_mtr_meter_profile_delete(port_id: libc::uint16_t,
+                                    meter_profile_id: libc::uint32_t,
+                                    error: *mut RawMtrError)
+                                    -> libc::c_int;
+
+    fn rte_mtr_meter_policy_add(port_id: libc::uint16_t,
+                                policy_id: libc::uint32_t,
+                                policy: *mut RawMeterPolicyParams,
+                                error: *mut RawMtrError)
+                                -> libc::c_int;
+
+    fn rte_mtr_meter_policy_delete(port_id: libc::uint16_t,
+                                   policy_id: libc::uint32_t,
+                                   error: *mut RawMtrError)
+                                   -> libc::c_int;
+
+    fn rte_mtr_meter_enable(port_id: libc::uint16_t,
+                            mtr_id: libc::uint32_t,
+                            error: *mut RawMtrError)
+                            -> libc::c_int;
+
+    fn rte_mtr_meter_disable(port_id: libc::uint16_t,
+                             mtr_id: libc::uint32_t,
+                             error: *mut RawMtrError)
+                             -> libc::c_int;
+
+    fn rte_mtr_destroy(port_id: libc::uint16_t,
+                       mtr_id: libc::uint32_t,
+                       error: *mut RawMtrError)
+                       -> libc::c_int;
+
+    fn _rte_mtr_meter_profile_add(port_id: libc::uint16_t,
+                                  meter_profile_id: libc::uint32_t,
+                                  alg: libc::c_uint,
+                                  cir: libc::uint64_t,
+                                  pir: libc::uint64_t,
+                                  cbs: libc::uint64_t,
+                                  ebs: libc::uint64_t,
+                                  packet_mode: libc::c_int,
+                                  error: *mut RawMtrError)
+                                  -> libc::c_int;
+
+    fn _rte_mtr_create(port_id: libc::uint16_t,
+                       mtr_id: libc::uint32_t,
+                       meter_profile_id: libc::uint32_t,
+                       meter_policy_id: libc::uint32_t,
+                       use_prev_mtr_color: libc::c_int,
+                       meter_enable: libc::c_int,
+                       stats_mask: libc::uint64_t,
+                       error: *mut RawMtrError)
+                       -> libc::c_int;
+}
@@ -0,0 +1,199 @@
+use std::io;
+use std::io::prelude::*;
+use std::cmp;
+use std::ptr;
+use std::slice;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use cycles;
+use mbuf::{self, PktMbuf};
+use ring::{Ring, RawRing};
+
+const PCAP_MAGIC: u32 = 0xa1b2c3d4;
+const PCAP_VERSION_MAJOR: u16 = 2;
+const PCAP_VERSION_MINOR: u16 = 4;
+const LINKTYPE_ETHERNET: u32 = 1;
+
+pub const DEFAULT_SNAPLEN: u32 = 65535;
+
+const BURST_SIZE: usize = 32;
+
+/// Write packets in the libpcap file format.
+///
+/// The timestamp of packets is derived from the wall clock when the writer was created
+/// and the TSC cycles elapsed since then, so no system call is needed per packet.
+///
+pub struct PcapWriter<W: Write> {
+    w: W,
+    snaplen: u32,
+    start_time: Duration,
+    start_cycles: u64,
+    tsc_hz: u64,
+}
+
+impl<W: Write> PcapWriter<W> {
+    /// Create a writer and write the global header of the capture file.
+    pub fn new(w: W, snaplen: u32) -> io::Result<PcapWriter<W>> {
+        let start_time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or(Duration::new(0, 0));
+
+        let mut writer = PcapWriter {
+            w: w,
+            snaplen: snaplen,
+            start_time: start_time,
+            start_cycles: cycles::rdtsc(),
+            tsc_hz: cycles::get_tsc_hz(),
+        };
+
+        try!(writer.write_header());
+
+        Ok(writer)
+    }
+
+    fn write_header(&mut self) -> io::Result<()> {
+        let mut hdr = [0u8; 24];
+
+        put_u32(&mut hdr[0..], PCAP_MAGIC);
+        put_u16(&mut hdr[4..], PCAP_VERSION_MAJOR);
+        put_u16(&mut hdr[6..], PCAP_VERSION_MINOR);
+        put_u32(&mut hdr[8..], 0); // thiszone
+        put_u32(&mut hdr[12..], 0); // sigfigs
+        put_u32(&mut hdr[16..], self.snaplen);
+        put_u32(&mut hdr[20..], LINKTYPE_ETHERNET);
+
+        self.w.write_all(&hdr)
+    }
+
+    /// The wall-clock time of the current TSC cycle counter.
+    pub fn now(&self) -> Duration {
+        let cycles = cycles::rdtsc().wrapping_sub(self.start_cycles);
+        let hz = if self.tsc_hz == 0 { 1 } else { self.tsc_hz };
+
+        let nanos = (cycles % hz) * 1_000_000_000 / hz;
+
+        self.start_time + Duration::new(cycles / hz, nanos as u32)
+    }
+
+    /// Write a packet with the timestamp, truncated to the snapshot length.
+    pub fn write_packet_at(&mut self, ts: Duration, data: &[u8], orig_len: u32) -> io::Result<()> {
+        let caplen = cmp::min(data.len() as u32, self.snaplen);
+        let mut hdr = [0u8; 16];
+
+        put_u32(&mut hdr[0..], ts.as_secs() as u32);
+        put_u32(&mut hdr[4..], ts.subsec_nanos() / 1000);
+        put_u32(&mut hdr[8..], caplen);
+        put_u32(&mut hdr[12..], orig_len);
+
+        try!(self.w.write_all(&hdr));
+
+        self.w.write_all(&data[..caplen as usize])
+    }
+
+    /// Write a packet with the current timestamp.
+    pub fn write_packet(&mut self, data: &[u8]) -> io::Result<()> {
+        let ts = self.now();
+
+        self.write_packet_at(ts, data, data.len() as u32)
+    }
+
+    /// Write all the segments of a mbuf as a packet with the current timestamp.
+    pub fn write_mbuf(&mut self, m: &mbuf::RawMbuf) -> io::Result<()> {
+        let ts = self.now();
+        let mut data = Vec::with_capacity(m.pkt_len as usize);
+        let mut seg: *const mbuf::RawMbuf = m;
+
+        while !seg.is_null() && data.len() < self.snaplen as usize {
+            unsafe {
+                let p = pktmbuf_mtod!(seg, *const u8);
+
+                data.extend_from_slice(slice::from_raw_parts(p, (*seg).data_len as usize));
+
+                seg = (*seg).next;
+            }
+        }
+
+        self.write_packet_at(ts, &data, m.pkt_len)
+    }
+
+    /// Dequeue all the mbufs from the ring, write and free them.
+    ///
+    /// Returns the number of written packets.
+    ///
+    pub fn drain(&mut self, ring: &mut RawRing) -> io::Result<usize> {
+        let mut mbufs: [mbuf::RawMbufPtr; BURST_SIZE] = [ptr::null_mut(); BURST_SIZE];
+        let mut written = 0;
+
+        loop {
+            let n = ring.dequeue_burst(&mut mbufs);
+
+            if n == 0 {
+                break;
+            }
+
+            for i in 0..n {
+                let m = unsafe { &mut *mbufs[i] };
+                let res = self.write_mbuf(m);
+
+                m.free();
+
+                if res.is_err() {
+                    for j in i + 1..n {
+                        unsafe { (*mbufs[j]).free() };
+                    }
+
+                    return res.map(|_| written);
+                }
+
+                written += 1;
+            }
+        }
+
+        Ok(written)
+    }
+
+    /// Flush the underlying writer.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.w.flush()
+    }
+
+    /// Unwrap the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.w
+    }
+}
+
+fn put_u16(buf: &mut [u8], v: u16) {
+    buf[0] = v as u8;
+    buf[1] = (v >> 8) as u8;
+}
+
+fn put_u32(buf: &mut [u8], v: u32) {
+    buf[0] = v as u8;
+    buf[1] = (v >> 8) as u8;
+    buf[2] = (v >> 16) as u8;
+    buf[3] = (v >> 24) as u8;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_pcap_writer() {
+        let mut w = PcapWriter::new(Vec::new(), 4).unwrap();
+
+        w.write_packet_at(Duration::new(1, 2000), &[1, 2, 3, 4, 5, 6], 60).unwrap();
+
+        let buf = w.into_inner();
+
+        assert_eq!(buf.len(), 24 + 16 + 4);
+        assert_eq!(&buf[..8], &[0xd4, 0xc3, 0xb2, 0xa1, 2, 0, 4, 0]);
+        assert_eq!(&buf[16..24], &[4, 0, 0, 0, 1, 0, 0, 0]);
+        assert_eq!(&buf[24..40],
+                   &[1, 0, 0, 0, 2, 0, 0, 0, 4, 0, 0, 0, 60, 0, 0, 0]);
+        assert_eq!(&buf[40..], &[1, 2, 3, 4]);
+    }
+}
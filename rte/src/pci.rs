@@ -1,4 +1,6 @@
 
+use std::ffi::CStr;
+
 use ffi;
 
 pub type Addr = ffi::Struct_rte_pci_addr;
@@ -6,3 +8,77 @@ pub type Id = ffi::Struct_rte_pci_id;
 
 pub type RawPciDevice = ffi::Struct_rte_pci_device;
 pub type RawPciDevicePtr = *mut ffi::Struct_rte_pci_device;
+
+pub trait PciDevice {
+    /// The PCI location of the device.
+    fn addr(&self) -> &Addr;
+
+    /// The PCI vendor and device ids.
+    fn id(&self) -> &Id;
+
+    /// The NUMA node connection, -1 if unknown.
+    fn numa_node(&self) -> i32;
+
+    /// The number of SR-IOV virtual functions enabled on the device.
+    fn max_vfs(&self) -> u16;
+
+    /// The name of the driver associated with the device.
+    fn driver_name(&self) -> Option<&str>;
+
+    /// The `RTE_PCI_DRV_*` flags of the driver associated with the device.
+    fn drv_flags(&self) -> Option<u32>;
+
+    /// The name of the driver which probed the device,
+    /// `None` if the device has not been bound to a DPDK compatible kernel driver.
+    fn probe_driver(&self) -> Option<&str>;
+}
+
+impl PciDevice for RawPciDevice {
+    fn addr(&self) -> &Addr {
+        &self.addr
+    }
+
+    fn id(&self) -> &Id {
+        &self.id
+    }
+
+    fn numa_node(&self) -> i32 {
+        self.numa_node
+    }
+
+    fn max_vfs(&self) -> u16 {
+        self.max_vfs
+    }
+
+    fn driver_name(&self) -> Option<&str> {
+        if self.driver.is_null() {
+            return None;
+        }
+
+        unsafe {
+            let name = (*self.driver).name;
+
+            if name.is_null() {
+                None
+            } else {
+                CStr::from_ptr(name).to_str().ok()
+            }
+        }
+    }
+
+    fn drv_flags(&self) -> Option<u32> {
+        if self.driver.is_null() {
+            None
+        } else {
+            Some(unsafe { (*self.driver).drv_flags })
+        }
+    }
+
+    fn probe_driver(&self) -> Option<&str> {
+        match self.kdrv {
+            ffi::Enum_rte_kernel_driver::RTE_KDRV_UNKNOWN |
+            ffi::Enum_rte_kernel_driver::RTE_KDRV_NONE => None,
+            _ => self.driver_name(),
+        }
+    }
+}
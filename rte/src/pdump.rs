@@ -0,0 +1,124 @@
+use libc;
+
+use errors::Result;
+use ethdev::{PortId, QueueId, RawPortId};
+use mempool::RawMemoryPool;
+use ring::RawRing;
+
+/// Capture the packets of all the queues of the port.
+pub const RTE_PDUMP_ALL_QUEUES: QueueId = 0xffff;
+
+/// The direction of the captured packets
+#[repr(u32)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum PdumpDirection {
+    Rx = 1, // RTE_PDUMP_FLAG_RX
+    Tx = 2, // RTE_PDUMP_FLAG_TX
+    RxTx = 3, // RTE_PDUMP_FLAG_RXTX
+}
+
+/// Initialise the packet capture framework in the primary process.
+pub fn init() -> Result<()> {
+    // the socket path argument is removed in 19.05, NULL selects the default one before.
+    #[cfg(not(dpdk_ver_19_05))]
+    let ret = unsafe { rte_pdump_init(::std::ptr::null()) };
+    #[cfg(dpdk_ver_19_05)]
+    let ret = unsafe { rte_pdump_init() };
+
+    rte_check!(ret)
+}
+
+/// Uninitialise the packet capture framework.
+pub fn uninit() -> Result<()> {
+    let ret = unsafe { rte_pdump_uninit() };
+
+    rte_check!(ret)
+}
+
+/// Enable packet capturing on the queue of the port.
+///
+/// The copies of the captured packets are allocated from the mempool
+/// and enqueued to the ring, use `pcap::PcapWriter::drain` to write them to a file.
+/// The capture is disabled when the returned handle is dropped.
+///
+pub fn enable(port_id: PortId,
+              queue_id: QueueId,
+              direction: PdumpDirection,
+              ring: &mut RawRing,
+              mempool: &mut RawMemoryPool)
+              -> Result<PdumpHandle> {
+    let ret = unsafe {
+        rte_pdump_enable(u8::from(port_id) as RawPortId,
+                         queue_id,
+                         direction as u32,
+                         ring,
+                         mempool,
+                         ::std::ptr::null_mut())
+    };
+
+    rte_check!(ret; ok => {
+        PdumpHandle {
+            port_id: port_id,
+            queue_id: queue_id,
+            direction: direction,
+        }
+    })
+}
+
+/// The handle of an enabled packet capture, which disables the capture on drop.
+pub struct PdumpHandle {
+    port_id: PortId,
+    queue_id: QueueId,
+    direction: PdumpDirection,
+}
+
+impl PdumpHandle {
+    pub fn port_id(&self) -> PortId {
+        self.port_id
+    }
+
+    pub fn queue_id(&self) -> QueueId {
+        self.queue_id
+    }
+
+    pub fn direction(&self) -> PdumpDirection {
+        self.direction
+    }
+}
+
+impl Drop for PdumpHandle {
+    fn drop(&mut self) {
+        let ret = unsafe {
+            rte_pdump_disable(u8::from(self.port_id) as RawPortId,
+                              self.queue_id,
+                              self.direction as u32)
+        };
+
+        if ret < 0 {
+            warn!("fail to disable packet capture on port {}, ret={}", self.port_id, ret);
+        }
+    }
+}
+
+extern "C" {
+    #[cfg(not(dpdk_ver_19_05))]
+    fn rte_pdump_init(path: *const libc::c_char) -> libc::c_int;
+
+    #[cfg(dpdk_ver_19_05)]
+    fn rte_pdump_init() -> libc::c_int;
+
+    fn rte_pdump_uninit() -> libc::c_int;
+
+    fn rte_pdump_enable(port: RawPortId,
+                        queue: libc::uint16_t,
+                        flags: libc::uint32_t,
+                        ring: *mut RawRing,
+                        mp: *mut RawMemoryPool,
+                        filter: *mut libc::c_void)
+                        -> libc::c_int;
+
+    fn rte_pdump_disable(port: RawPortId,
+                         queue: libc::uint16_t,
+                         flags: libc::uint32_t)
+                         -> libc::c_int;
+}
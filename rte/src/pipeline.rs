@@ -0,0 +1,560 @@
+use std::mem;
+use std::ptr;
+use std::os::raw::{c_char, c_void};
+
+use libc;
+
+use errors::{Error, Result};
+use memory::SocketId;
+use ethdev::{PortId, QueueId};
+use ring::RawRingPtr;
+
+/// Packet processing pipeline, freed when dropped.
+///
+/// A pipeline is built from input ports connected to tables,
+/// the table entries forward the matched packets to output ports or other tables.
+///
+pub struct Pipeline {
+    p: *mut RawPipeline,
+    key_sizes: Vec<usize>,
+}
+
+pub enum RawPipeline {}
+
+/// The input port of a pipeline.
+#[derive(Copy, Clone, Debug)]
+pub enum PortInParams {
+    /// Receive packets from a RX queue of an Ethernet device.
+    Ethdev {
+        port_id: PortId,
+        queue_id: QueueId,
+        burst_size: u32,
+    },
+    /// Dequeue packets from a ring.
+    Ring { ring: RawRingPtr, burst_size: u32 },
+}
+
+/// The output port of a pipeline.
+#[derive(Copy, Clone, Debug)]
+pub enum PortOutParams {
+    /// Send packets to a TX queue of an Ethernet device.
+    Ethdev {
+        port_id: PortId,
+        queue_id: QueueId,
+        tx_burst_sz: u32,
+    },
+    /// Enqueue packets to a ring.
+    Ring { ring: RawRingPtr, tx_burst_sz: u32 },
+}
+
+/// The table of a pipeline.
+///
+/// The offsets are relative to the start of the mbuf, and point to the meta-data
+/// which must be filled in by the input port action or a previous table.
+///
+#[derive(Copy, Clone, Debug)]
+pub enum TableParams<'a> {
+    /// Hash table with the extendable buckets, matching a key of `key_size` bytes.
+    Hash {
+        key_size: u32,
+        n_keys: u32,
+        n_buckets: u32,
+        n_buckets_ext: u32,
+        signature_offset: u32,
+        key_offset: u32,
+    },
+    /// Longest prefix match table, matching an IPv4 address in the host byte order.
+    Lpm {
+        name: &'a str,
+        n_rules: u32,
+        number_tbl8s: u32,
+        offset: u32,
+    },
+}
+
+/// The action of a table entry.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TableAction {
+    /// Drop the packet.
+    Drop,
+    /// Send the packet to the output port.
+    Port(u32),
+    /// Send the packet to the table.
+    Table(u32),
+}
+
+/// The key of a LPM table entry.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct LpmKey {
+    /// IPv4 address in the host byte order.
+    pub ip: u32,
+    /// Depth of the prefix.
+    pub depth: u8,
+}
+
+impl LpmKey {
+    /// The key as bytes, used by `Pipeline::table_add_entry()`.
+    pub fn as_bytes(&self) -> &[u8] {
+        unsafe {
+            ::std::slice::from_raw_parts(self as *const _ as *const u8, mem::size_of::<LpmKey>())
+        }
+    }
+}
+
+impl Drop for Pipeline {
+    fn drop(&mut self) {
+        let ret = unsafe { rte_pipeline_free(self.p) };
+
+        if ret < 0 {
+            warn!("fail to free pipeline, error {}", ret);
+        }
+    }
+}
+
+impl Pipeline {
+    /// Create a pipeline, `offset_port_id` is the offset of the output port id
+    /// in the mbuf meta-data, used by the `RTE_PIPELINE_ACTION_PORT_META` action.
+    pub fn create(name: &str, socket_id: SocketId, offset_port_id: u32) -> Result<Pipeline> {
+        let mut params = RawPipelineParams {
+            name: try!(to_cptr!(name)),
+            socket_id: socket_id,
+            offset_port_id: offset_port_id,
+        };
+
+        let p = unsafe { rte_pipeline_create(&mut params) };
+
+        rte_check!(p, NonNull; ok => {
+            Pipeline {
+                p: p,
+                key_sizes: Vec::new(),
+            }
+        })
+    }
+
+    /// Extract the raw pointer from an underlying object.
+    pub fn as_raw(&self) -> *mut RawPipeline {
+        self.p
+    }
+
+    /// Create a table, returns the table index.
+    pub fn table_create(&mut self, params: &TableParams) -> Result<u32> {
+        let mut table_id = 0;
+
+        let (ret, key_size) = match *params {
+            TableParams::Hash { key_size,
+                                n_keys,
+                                n_buckets,
+                                n_buckets_ext,
+                                signature_offset,
+                                key_offset } => {
+                let mut arg = RawTableHashExtParams {
+                    key_size: key_size,
+                    n_keys: n_keys,
+                    n_buckets: n_buckets,
+                    n_buckets_ext: n_buckets_ext,
+                    f_hash: _rte_table_hash_crc,
+                    seed: 0,
+                    signature_offset: signature_offset,
+                    key_offset: key_offset,
+                };
+
+                (unsafe {
+                    self.create_table(&rte_table_hash_ext_ops,
+                                      &mut arg as *mut _ as *mut c_void,
+                                      &mut table_id)
+                },
+                 key_size as usize)
+            }
+            TableParams::Lpm { name, n_rules, number_tbl8s, offset } => {
+                let mut arg = RawTableLpmParams {
+                    name: try!(to_cptr!(name)),
+                    n_rules: n_rules,
+                    number_tbl8s: number_tbl8s,
+                    flags: 0,
+                    entry_unique_size: mem::size_of::<RawTableEntry>() as u32,
+                    offset: offset,
+                };
+
+                (unsafe {
+                    self.create_table(&rte_table_lpm_ops,
+                                      &mut arg as *mut _ as *mut c_void,
+                                      &mut table_id)
+                },
+                 mem::size_of::<LpmKey>())
+            }
+        };
+
+        rte_check!(ret; ok => {
+            self.key_sizes.push(key_size);
+
+            table_id
+        })
+    }
+
+    unsafe fn create_table(&self,
+                           ops: &RawTableOps,
+                           arg: *mut c_void,
+                           table_id: &mut u32)
+                           -> libc::c_int {
+        let mut params = RawTableParams {
+            ops: ops,
+            arg_create: arg,
+            f_action_hit: ptr::null(),
+            f_action_miss: ptr::null(),
+            arg_ah: ptr::null_mut(),
+            action_data_size: 0,
+        };
+
+        rte_pipeline_table_create(self.p, &mut params, table_id)
+    }
+
+    /// Create an input port, returns the port index.
+    pub fn port_in_create(&mut self, params: &PortInParams) -> Result<u32> {
+        let mut port_id = 0;
+
+        let ret = match *params {
+            PortInParams::Ethdev { port_id: dev, queue_id, burst_size } => {
+                let mut arg = RawPortEthdevReaderParams {
+                    port_id: u8::from(dev),
+                    queue_id: queue_id,
+                };
+
+                unsafe {
+                    self.create_port_in(&rte_port_ethdev_reader_ops,
+                                        &mut arg as *mut _ as *mut c_void,
+                                        burst_size,
+                                        &mut port_id)
+                }
+            }
+            PortInParams::Ring { ring, burst_size } => {
+                let mut arg = RawPortRingReaderParams { ring: ring };
+
+                unsafe {
+                    self.create_port_in(&rte_port_ring_reader_ops,
+                                        &mut arg as *mut _ as *mut c_void,
+                                        burst_size,
+                                        &mut port_id)
+                }
+            }
+        };
+
+        rte_check!(ret; ok => { port_id })
+    }
+
+    unsafe fn create_port_in(&self,
+                             ops: &RawPortOps,
+                             arg: *mut c_void,
+                             burst_size: u32,
+                             port_id: &mut u32)
+                             -> libc::c_int {
+        let mut params = RawPortInParams {
+            ops: ops,
+            arg_create: arg,
+            f_action: ptr::null(),
+            arg_ah: ptr::null_mut(),
+            burst_size: burst_size,
+        };
+
+        rte_pipeline_port_in_create(self.p, &mut params, port_id)
+    }
+
+    /// Create an output port, returns the port index.
+    pub fn port_out_create(&mut self, params: &PortOutParams) -> Result<u32> {
+        let mut port_id = 0;
+
+        let ret = match *params {
+            PortOutParams::Ethdev { port_id: dev, queue_id, tx_burst_sz } => {
+                let mut arg = RawPortEthdevWriterParams {
+                    port_id: u8::from(dev),
+                    queue_id: queue_id,
+                    tx_burst_sz: tx_burst_sz,
+                };
+
+                unsafe {
+                    self.create_port_out(&rte_port_ethdev_writer_ops,
+                                         &mut arg as *mut _ as *mut c_void,
+                                         &mut port_id)
+                }
+            }
+            PortOutParams::Ring { ring, tx_burst_sz } => {
+                let mut arg = RawPortRingWriterParams {
+                    ring: ring,
+                    tx_burst_sz: tx_burst_sz,
+                };
+
+                unsafe {
+                    self.create_port_out(&rte_port_ring_writer_ops,
+                                         &mut arg as *mut _ as *mut c_void,
+                                         &mut port_id)
+                }
+            }
+        };
+
+        rte_check!(ret; ok => { port_id })
+    }
+
+    unsafe fn create_port_out(&self,
+                              ops: &RawPortOps,
+                              arg: *mut c_void,
+                              port_id: &mut u32)
+                              -> libc::c_int {
+        let mut params = RawPortOutParams {
+            ops: ops,
+            arg_create: arg,
+            f_action: ptr::null(),
+            arg_ah: ptr::null_mut(),
+        };
+
+        rte_pipeline_port_out_create(self.p, &mut params, port_id)
+    }
+
+    /// Connect the input port to the table and enable it.
+    pub fn connect(&mut self, port_in_id: u32, table_id: u32) -> Result<()> {
+        let ret = unsafe { rte_pipeline_port_in_connect_to_table(self.p, port_in_id, table_id) };
+
+        try!(rte_check!(ret));
+
+        let ret = unsafe { rte_pipeline_port_in_enable(self.p, port_in_id) };
+
+        rte_check!(ret)
+    }
+
+    /// Add an entry to the table.
+    ///
+    /// Returns 1 if the key was already in the table and its entry has been updated,
+    /// or 0 if a new entry has been added.
+    ///
+    pub fn table_add_entry(&mut self,
+                           table_id: u32,
+                           key: &[u8],
+                           action: &TableAction)
+                           -> Result<u32> {
+        match self.key_sizes.get(table_id as usize) {
+            Some(&size) if size == key.len() => {}
+            _ => return Err(Error::OsError(libc::EINVAL)),
+        }
+
+        let mut key = key.to_vec();
+        let mut entry = RawTableEntry::from(action);
+        let mut key_found = 0;
+        let mut entry_ptr = ptr::null_mut();
+
+        let ret = unsafe {
+            rte_pipeline_table_entry_add(self.p,
+                                         table_id,
+                                         key.as_mut_ptr() as *mut c_void,
+                                         &mut entry,
+                                         &mut key_found,
+                                         &mut entry_ptr)
+        };
+
+        rte_check!(ret; ok => { key_found as u32 })
+    }
+
+    /// Set the action for the packets which miss all the entries of the table.
+    pub fn table_default_entry_add(&mut self, table_id: u32, action: &TableAction) -> Result<()> {
+        let mut entry = RawTableEntry::from(action);
+        let mut entry_ptr = ptr::null_mut();
+
+        let ret = unsafe {
+            rte_pipeline_table_default_entry_add(self.p, table_id, &mut entry, &mut entry_ptr)
+        };
+
+        rte_check!(ret)
+    }
+
+    /// Check the consistency of the pipeline, it should be called after the configuration.
+    pub fn check(&self) -> Result<()> {
+        let ret = unsafe { rte_pipeline_check(self.p) };
+
+        rte_check!(ret)
+    }
+
+    /// Run the pipeline once, reading a burst of packets from each enabled input port.
+    pub fn run_once(&mut self) -> Result<()> {
+        let ret = unsafe { rte_pipeline_run(self.p) };
+
+        rte_check!(ret)
+    }
+
+    /// Flush the buffered packets of all the output ports.
+    pub fn flush(&mut self) -> Result<()> {
+        let ret = unsafe { rte_pipeline_flush(self.p) };
+
+        rte_check!(ret)
+    }
+}
+
+const RTE_PIPELINE_ACTION_DROP: u32 = 0;
+const RTE_PIPELINE_ACTION_PORT: u32 = 1;
+const RTE_PIPELINE_ACTION_TABLE: u32 = 3;
+
+#[repr(C)]
+struct RawTableEntry {
+    action: u32,
+    id: u32,
+}
+
+impl<'a> From<&'a TableAction> for RawTableEntry {
+    fn from(action: &'a TableAction) -> Self {
+        let (action, id) = match *action {
+            TableAction::Drop => (RTE_PIPELINE_ACTION_DROP, 0),
+            TableAction::Port(port_id) => (RTE_PIPELINE_ACTION_PORT, port_id),
+            TableAction::Table(table_id) => (RTE_PIPELINE_ACTION_TABLE, table_id),
+        };
+
+        RawTableEntry {
+            action: action,
+            id: id,
+        }
+    }
+}
+
+#[repr(C)]
+struct RawPipelineParams {
+    name: *const c_char,
+    socket_id: libc::c_int,
+    offset_port_id: u32,
+}
+
+#[repr(C)]
+struct RawTableOps {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+struct RawPortOps {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+struct RawTableParams {
+    ops: *const RawTableOps,
+    arg_create: *mut c_void,
+    f_action_hit: *const c_void,
+    f_action_miss: *const c_void,
+    arg_ah: *mut c_void,
+    action_data_size: u32,
+}
+
+#[repr(C)]
+struct RawPortInParams {
+    ops: *const RawPortOps,
+    arg_create: *mut c_void,
+    f_action: *const c_void,
+    arg_ah: *mut c_void,
+    burst_size: u32,
+}
+
+#[repr(C)]
+struct RawPortOutParams {
+    ops: *const RawPortOps,
+    arg_create: *mut c_void,
+    f_action: *const c_void,
+    arg_ah: *mut c_void,
+}
+
+#[repr(C)]
+struct RawTableHashExtParams {
+    key_size: u32,
+    n_keys: u32,
+    n_buckets: u32,
+    n_buckets_ext: u32,
+    f_hash: unsafe extern "C" fn(key: *mut c_void, key_size: u32, seed: u64) -> u64,
+    seed: u64,
+    signature_offset: u32,
+    key_offset: u32,
+}
+
+#[repr(C)]
+struct RawTableLpmParams {
+    name: *const c_char,
+    n_rules: u32,
+    number_tbl8s: u32,
+    flags: libc::c_int,
+    entry_unique_size: u32,
+    offset: u32,
+}
+
+#[repr(C)]
+struct RawPortEthdevReaderParams {
+    port_id: u8,
+    queue_id: u16,
+}
+
+#[repr(C)]
+struct RawPortEthdevWriterParams {
+    port_id: u8,
+    queue_id: u16,
+    tx_burst_sz: u32,
+}
+
+#[repr(C)]
+struct RawPortRingReaderParams {
+    ring: RawRingPtr,
+}
+
+#[repr(C)]
+struct RawPortRingWriterParams {
+    ring: RawRingPtr,
+    tx_burst_sz: u32,
+}
+
+extern "C" {
+    static rte_table_hash_ext_ops: RawTableOps;
+    static rte_table_lpm_ops: RawTableOps;
+
+    static rte_port_ethdev_reader_ops: RawPortOps;
+    static rte_port_ethdev_writer_ops: RawPortOps;
+    static rte_port_ring_reader_ops: RawPortOps;
+    static rte_port_ring_writer_ops: RawPortOps;
+
+    fn _rte_table_hash_crc(key: *mut c_void, key_size: u32, seed: u64) -> u64;
+
+    fn rte_pipeline_create(params: *mut RawPipelineParams) -> *mut RawPipeline;
+
+    fn rte_pipeline_free(p: *mut RawPipeline) -> libc::c_int;
+
+    fn rte_pipeline_check(p: *mut RawPipeline) -> libc::c_int;
+
+    fn rte_pipeline_run(p: *mut RawPipeline) -> libc::c_int;
+
+    fn rte_pipeline_flush(p: *mut RawPipeline) -> libc::c_int;
+
+    fn rte_pipeline_table_create(p: *mut RawPipeline,
+                                 params: *mut RawTableParams,
+                                 table_id: *mut u32)
+                                 -> libc::c_int;
+
+    fn rte_pipeline_table_entry_add(p: *mut RawPipeline,
+                                    table_id: u32,
+                                    key: *mut c_void,
+                                    entry: *mut RawTableEntry,
+                                    key_found: *mut libc::c_int,
+                                    entry_ptr: *mut *mut RawTableEntry)
+                                    -> libc::c_int;
+
+    fn rte_pipeline_table_default_entry_add(p: *mut RawPipeline,
+                                            table_id: u32,
+                                            default_entry: *mut RawTableEntry,
+                                            default_entry_ptr: *mut *mut RawTableEntry)
+                                            -> libc::c_int;
+
+    fn rte_pipeline_port_in_create(p: *mut RawPipeline,
+                                   params: *mut RawPortInParams,
+                                   port_id: *mut u32)
+                                   -> libc::c_int;
+
+    fn rte_pipeline_port_in_connect_to_table(p: *mut RawPipeline,
+                                             port_id: u32,
+                                             table_id: u32)
+                                             -> libc::c_int;
+
+    fn rte_pipeline_port_in_enable(p: *mut RawPipeline, port_id: u32) -> libc::c_int;
+
+    fn rte_pipeline_port_out_create(p: *mut RawPipeline,
+                                    params: *mut RawPortOutParams,
+                                    port_id: *mut u32)
+                                    -> libc::c_int;
+}
@@ -0,0 +1,89 @@
+use std::os::raw::c_char;
+
+use libc;
+
+use errors::{Error, Result};
+use mbuf::RawMbufPtr;
+use memory::SocketId;
+
+pub enum RawReorderBuffer {}
+
+pub type RawReorderBufferPtr = *mut RawReorderBuffer;
+
+/// Reorder buffer
+///
+/// The packets are inserted out of order with the sequence number in their `seqn` field,
+/// and drained in order of the sequence numbers within the window of the buffer.
+///
+pub struct ReorderBuffer(RawReorderBufferPtr);
+
+unsafe impl Send for ReorderBuffer {}
+
+impl Drop for ReorderBuffer {
+    fn drop(&mut self) {
+        unsafe { rte_reorder_free(self.0) }
+    }
+}
+
+impl ReorderBuffer {
+    /// Create a reorder buffer, the size of the window must be a power of two.
+    pub fn create(name: &str, socket_id: SocketId, size: u32) -> Result<ReorderBuffer> {
+        if !size.is_power_of_two() {
+            return Err(Error::OsError(libc::EINVAL));
+        }
+
+        let p = unsafe {
+            rte_reorder_create(try!(to_cptr!(name)), socket_id as libc::c_uint, size)
+        };
+
+        rte_check!(p, NonNull; ok => { ReorderBuffer(p) })
+    }
+
+    /// Extract the raw pointer from an underlying object.
+    pub fn as_raw(&self) -> RawReorderBufferPtr {
+        self.0
+    }
+
+    /// Insert the packet, which `seqn` field must be set before.
+    ///
+    /// Fails with `ENOSPC` when the buffer is full,
+    /// and `ERANGE` when the sequence number is out of the window, e.g. too early or too late.
+    ///
+    pub fn insert(&mut self, m: RawMbufPtr) -> Result<()> {
+        let ret = unsafe { rte_reorder_insert(self.0, m) };
+
+        rte_check!(ret; err => { Error::rte_error() })
+    }
+
+    /// Drain the packets in order, returns the number of packets.
+    ///
+    /// The draining stops at a gap of the sequence numbers,
+    /// unless the buffer is full, so the window could move forward.
+    ///
+    pub fn drain(&mut self, mbufs: &mut [RawMbufPtr]) -> u32 {
+        unsafe { rte_reorder_drain(self.0, mbufs.as_mut_ptr(), mbufs.len() as u32) }
+    }
+
+    /// Reset the buffer, freeing the buffered packets.
+    pub fn reset(&mut self) {
+        unsafe { rte_reorder_reset(self.0) }
+    }
+}
+
+extern "C" {
+    fn rte_reorder_create(name: *const c_char,
+                          socket_id: libc::c_uint,
+                          size: libc::c_uint)
+                          -> RawReorderBufferPtr;
+
+    fn rte_reorder_reset(b: RawReorderBufferPtr);
+
+    fn rte_reorder_free(b: RawReorderBufferPtr);
+
+    fn rte_reorder_insert(b: RawReorderBufferPtr, mbuf: RawMbufPtr) -> libc::c_int;
+
+    fn rte_reorder_drain(b: RawReorderBufferPtr,
+                         mbufs: *mut RawMbufPtr,
+                         max_mbufs: libc::c_uint)
+                         -> libc::c_uint;
+}
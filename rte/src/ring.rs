@@ -0,0 +1,148 @@
+use std::ffi::CStr;
+use std::os::raw::c_void;
+use std::os::unix::io::AsRawFd;
+
+use libc;
+use cfile;
+
+use ffi;
+
+use errors::Result;
+use memory::SocketId;
+
+bitflags! {
+    pub flags RingFlags: u32 {
+        /// The default enqueue is "single-producer".
+        const RING_F_SP_ENQ = 0x0001,
+        /// The default dequeue is "single-consumer".
+        const RING_F_SC_DEQ = 0x0002,
+    }
+}
+
+pub type RawRing = ffi::Struct_rte_ring;
+pub type RawRingPtr = *mut ffi::Struct_rte_ring;
+
+/// RTE Ring
+///
+/// The Ring Manager is a fixed-size queue, implemented as a table of pointers.
+/// Head and tail pointers are modified atomically, allowing concurrent access to it.
+///
+pub trait Ring {
+    /// Name of the ring.
+    fn name(&self) -> &str;
+
+    /// Return the number of entries in a ring.
+    fn count(&self) -> u32;
+
+    /// Return the number of free entries in a ring.
+    fn free_count(&self) -> u32;
+
+    /// Test if a ring is full.
+    fn is_full(&self) -> bool;
+
+    /// Test if a ring is empty.
+    fn is_empty(&self) -> bool;
+
+    /// Enqueue several objects on a ring, returns the number of objects enqueued.
+    fn enqueue_burst<T>(&mut self, objs: &[*mut T]) -> usize;
+
+    /// Dequeue several objects from a ring, returns the number of objects dequeued.
+    fn dequeue_burst<T>(&mut self, objs: &mut [*mut T]) -> usize;
+
+    /// Dump the status of the ring to a file.
+    fn dump<S: AsRawFd>(&self, s: &S);
+
+    /// De-allocate all memory used by the ring.
+    fn free(&mut self);
+}
+
+impl Ring for RawRing {
+    fn name(&self) -> &str {
+        unsafe { CStr::from_ptr(self.name.as_ptr()).to_str().unwrap() }
+    }
+
+    fn count(&self) -> u32 {
+        unsafe { _rte_ring_count(self) }
+    }
+
+    fn free_count(&self) -> u32 {
+        unsafe { _rte_ring_free_count(self) }
+    }
+
+    fn is_full(&self) -> bool {
+        unsafe { _rte_ring_full(self) != 0 }
+    }
+
+    fn is_empty(&self) -> bool {
+        unsafe { _rte_ring_empty(self) != 0 }
+    }
+
+    fn enqueue_burst<T>(&mut self, objs: &[*mut T]) -> usize {
+        unsafe {
+            _rte_ring_enqueue_burst(self,
+                                    objs.as_ptr() as *const *mut c_void,
+                                    objs.len() as u32) as usize
+        }
+    }
+
+    fn dequeue_burst<T>(&mut self, objs: &mut [*mut T]) -> usize {
+        unsafe {
+            _rte_ring_dequeue_burst(self,
+                                    objs.as_mut_ptr() as *mut *mut c_void,
+                                    objs.len() as u32) as usize
+        }
+    }
+
+    fn dump<S: AsRawFd>(&self, s: &S) {
+        if let Ok(f) = cfile::open_stream(s, "w") {
+            unsafe {
+                ffi::rte_ring_dump(f.stream() as *mut ffi::FILE, self);
+            }
+        }
+    }
+
+    fn free(&mut self) {
+        unsafe { ffi::rte_ring_free(self) }
+    }
+}
+
+/// Create a new ring named name in memory.
+///
+/// The ring size is set to count, which must be a power of two.
+///
+pub fn create(name: &str,
+              count: u32,
+              socket_id: SocketId,
+              flags: RingFlags)
+              -> Result<RawRingPtr> {
+    let p = unsafe { ffi::rte_ring_create(try!(to_cptr!(name)), count, socket_id, flags.bits) };
+
+    rte_check!(p, NonNull)
+}
+
+/// Search a ring from its name
+pub fn lookup(name: &str) -> Result<RawRingPtr> {
+    let p = unsafe { ffi::rte_ring_lookup(try!(to_cptr!(name))) };
+
+    rte_check!(p, NonNull)
+}
+
+extern "C" {
+    fn _rte_ring_count(r: *const RawRing) -> libc::c_uint;
+
+    fn _rte_ring_free_count(r: *const RawRing) -> libc::c_uint;
+
+    fn _rte_ring_full(r: *const RawRing) -> libc::c_int;
+
+    fn _rte_ring_empty(r: *const RawRing) -> libc::c_int;
+
+    fn _rte_ring_enqueue_burst(r: *mut RawRing,
+                               obj_table: *const *mut c_void,
+                               n: libc::c_uint)
+                               -> libc::c_uint;
+
+    fn _rte_ring_dequeue_burst(r: *mut RawRing,
+                               obj_table: *mut *mut c_void,
+                               n: libc::c_uint)
+                               -> libc::c_uint;
+}
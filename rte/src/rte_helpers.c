@@ -1,13 +1,40 @@
 #include <stdio.h>
 #include <stdlib.h>
+#include <string.h>
 
 #include <rte_config.h>
 #include <rte_version.h>
 #include <rte_lcore.h>
 #include <rte_errno.h>
 #include <rte_spinlock.h>
+#include <rte_rwlock.h>
 #include <rte_cycles.h>
+#include <rte_ring.h>
+#include <rte_mempool.h>
 #include <rte_ethdev.h>
+#include <rte_cryptodev.h>
+#include <rte_meter.h>
+#include <rte_hash_crc.h>
+#include <rte_jhash.h>
+#include <rte_thash.h>
+#if defined(RTE_LIBRTE_EVENTDEV) && RTE_VERSION >= RTE_VERSION_NUM(17, 5, 0, 0)
+#include <rte_eventdev.h>
+#endif
+#if defined(RTE_LIBRTE_SECURITY) && RTE_VERSION >= RTE_VERSION_NUM(17, 11, 0, 0)
+#include <rte_security.h>
+#endif
+#if defined(RTE_LIBRTE_GRAPH) && RTE_VERSION >= RTE_VERSION_NUM(20, 5, 0, 0)
+#include <rte_graph_worker.h>
+#endif
+#if defined(RTE_LIBRTE_TM) && RTE_VERSION >= RTE_VERSION_NUM(17, 11, 0, 0)
+#include <rte_tm.h>
+#endif
+#if defined(RTE_LIBRTE_MTR) && RTE_VERSION >= RTE_VERSION_NUM(21, 5, 0, 0)
+#include <rte_mtr.h>
+#endif
+#if defined(RTE_LIBRTE_TRACE) && RTE_VERSION >= RTE_VERSION_NUM(20, 5, 0, 0)
+#include <rte_trace_point.h>
+#endif
 
 #include <cmdline_parse.h>
 #include <cmdline.h>
@@ -107,6 +134,56 @@ _rte_spinlock_recursive_trylock_tm(rte_spinlock_recursive_t *slr) {
     return rte_spinlock_recursive_trylock_tm(slr);
 }
 
+void
+_rte_rwlock_read_lock(rte_rwlock_t *rwl) {
+    rte_rwlock_read_lock(rwl);
+}
+
+void
+_rte_rwlock_read_unlock(rte_rwlock_t *rwl) {
+    rte_rwlock_read_unlock(rwl);
+}
+
+void
+_rte_rwlock_write_lock(rte_rwlock_t *rwl) {
+    rte_rwlock_write_lock(rwl);
+}
+
+void
+_rte_rwlock_write_unlock(rte_rwlock_t *rwl) {
+    rte_rwlock_write_unlock(rwl);
+}
+
+unsigned
+_rte_ring_count(const struct rte_ring *r) {
+    return rte_ring_count(r);
+}
+
+unsigned
+_rte_ring_free_count(const struct rte_ring *r) {
+    return rte_ring_free_count(r);
+}
+
+int
+_rte_ring_full(const struct rte_ring *r) {
+    return rte_ring_full(r);
+}
+
+int
+_rte_ring_empty(const struct rte_ring *r) {
+    return rte_ring_empty(r);
+}
+
+unsigned
+_rte_ring_enqueue_burst(struct rte_ring *r, void * const *obj_table, unsigned n) {
+    return rte_ring_enqueue_burst(r, obj_table, n);
+}
+
+unsigned
+_rte_ring_dequeue_burst(struct rte_ring *r, void **obj_table, unsigned n) {
+    return rte_ring_dequeue_burst(r, obj_table, n);
+}
+
 uint16_t
 _rte_eth_rx_burst(uint8_t port_id, uint16_t queue_id,
          struct rte_mbuf **rx_pkts, const uint16_t nb_pkts) {
@@ -119,6 +196,427 @@ _rte_eth_tx_burst(uint8_t port_id, uint16_t queue_id,
     return rte_eth_tx_burst(port_id, queue_id, tx_pkts, nb_pkts);
 }
 
+#if RTE_VERSION >= RTE_VERSION_NUM(17, 2, 0, 0)
+uint16_t
+_rte_eth_tx_prepare(uint8_t port_id, uint16_t queue_id,
+         struct rte_mbuf **tx_pkts, uint16_t nb_pkts) {
+    return rte_eth_tx_prepare(port_id, queue_id, tx_pkts, nb_pkts);
+}
+#endif
+
+int
+_rte_eth_dev_started(uint8_t port_id) {
+    if (!rte_eth_dev_is_valid_port(port_id))
+        return -ENODEV;
+
+    return rte_eth_devices[port_id].data->dev_started;
+}
+
+int
+_rte_eth_dev_mac_addrs(uint8_t port_id, struct ether_addr *addrs, uint32_t n) {
+    struct rte_eth_dev *dev;
+    struct rte_eth_dev_info info;
+    uint32_t i;
+
+    if (!rte_eth_dev_is_valid_port(port_id))
+        return -ENODEV;
+
+    dev = &rte_eth_devices[port_id];
+
+    if (dev->data->mac_addrs == NULL)
+        return 0;
+
+    rte_eth_dev_info_get(port_id, &info);
+
+    if (n > info.max_mac_addrs)
+        n = info.max_mac_addrs;
+
+    for (i = 0; i < n; i++)
+        ether_addr_copy(&dev->data->mac_addrs[i], &addrs[i]);
+
+    return n;
+}
+
+uint32_t
+_rte_eth_dev_dcb_capability(uint8_t port_id) {
+    return rte_eth_devices[port_id].data->dev_conf.dcb_capability_en;
+}
+
+int
+_rte_eth_dev_nb_rx_queues(uint8_t port_id) {
+    if (!rte_eth_dev_is_valid_port(port_id))
+        return -ENODEV;
+
+    return rte_eth_devices[port_id].data->nb_rx_queues;
+}
+
+int
+_rte_eth_dev_nb_tx_queues(uint8_t port_id) {
+    if (!rte_eth_dev_is_valid_port(port_id))
+        return -ENODEV;
+
+    return rte_eth_devices[port_id].data->nb_tx_queues;
+}
+
+#if RTE_VERSION >= RTE_VERSION_NUM(17, 5, 0, 0)
+int
+_rte_eth_dev_mtu_range(uint8_t port_id, uint16_t *min_mtu, uint16_t *max_mtu) {
+    struct rte_eth_dev_info dev_info;
+
+    if (!rte_eth_dev_is_valid_port(port_id))
+        return -ENODEV;
+
+    rte_eth_dev_info_get(port_id, &dev_info);
+
+    *min_mtu = dev_info.min_mtu;
+    *max_mtu = dev_info.max_mtu;
+
+    return 0;
+}
+#endif
+
+int
+_rte_mem_is_rte_addr(const void *addr) {
+#if RTE_VERSION >= RTE_VERSION_NUM(18, 5, 0, 0)
+    return rte_mem_virt2memseg(addr, NULL) != NULL;
+#else
+    const struct rte_memseg *ms = rte_eal_get_physmem_layout();
+    unsigned i;
+
+    for (i = 0; i < RTE_MAX_MEMSEG && ms[i].addr != NULL; i++) {
+        if (addr >= ms[i].addr && (const char *) addr < (const char *) ms[i].addr + ms[i].len)
+            return 1;
+    }
+
+    return 0;
+#endif
+}
+
+#if RTE_VERSION >= RTE_VERSION_NUM(16, 7, 0, 0)
+int
+_rte_mempool_ops_is_registered(const char *name) {
+    unsigned i;
+
+    for (i = 0; i < rte_mempool_ops_table.num_ops; i++) {
+        if (strcmp(rte_mempool_ops_table.ops[i].name, name) == 0)
+            return 1;
+    }
+
+    return 0;
+}
+
+unsigned
+_rte_mempool_ops_count(void) {
+    return rte_mempool_ops_table.num_ops;
+}
+
+const char *
+_rte_mempool_ops_get_name(unsigned idx) {
+    if (idx >= rte_mempool_ops_table.num_ops)
+        return NULL;
+
+    return rte_mempool_ops_table.ops[idx].name;
+}
+#endif
+
+uint16_t
+_rte_cryptodev_enqueue_burst(uint8_t dev_id, uint16_t qp_id,
+         struct rte_crypto_op **ops, uint16_t nb_ops) {
+    return rte_cryptodev_enqueue_burst(dev_id, qp_id, ops, nb_ops);
+}
+
+uint16_t
+_rte_cryptodev_dequeue_burst(uint8_t dev_id, uint16_t qp_id,
+         struct rte_crypto_op **ops, uint16_t nb_ops) {
+    return rte_cryptodev_dequeue_burst(dev_id, qp_id, ops, nb_ops);
+}
+
+struct rte_crypto_op *
+_rte_crypto_op_alloc(struct rte_mempool *mp, enum rte_crypto_op_type type, int sessionless) {
+    struct rte_crypto_op *op = rte_crypto_op_alloc(mp, type);
+
+    if (op != NULL && sessionless)
+        op->sess_type = RTE_CRYPTO_OP_SESSIONLESS;
+
+    return op;
+}
+
+unsigned
+_rte_crypto_op_bulk_alloc(struct rte_mempool *mp, enum rte_crypto_op_type type, int sessionless,
+         struct rte_crypto_op **ops, uint16_t nb_ops) {
+    unsigned i;
+
+    if (rte_crypto_op_bulk_alloc(mp, type, ops, nb_ops) != nb_ops)
+        return 0;
+
+    if (sessionless) {
+        for (i = 0; i < nb_ops; i++)
+            ops[i]->sess_type = RTE_CRYPTO_OP_SESSIONLESS;
+    }
+
+    return nb_ops;
+}
+
+void
+_rte_crypto_op_free(struct rte_crypto_op *op) {
+    rte_crypto_op_free(op);
+}
+
+#if defined(RTE_LIBRTE_EVENTDEV) && RTE_VERSION >= RTE_VERSION_NUM(17, 5, 0, 0)
+uint16_t
+_rte_event_enqueue_burst(uint8_t dev_id, uint8_t port_id,
+         const struct rte_event ev[], uint16_t nb_events) {
+    return rte_event_enqueue_burst(dev_id, port_id, ev, nb_events);
+}
+
+uint16_t
+_rte_event_dequeue_burst(uint8_t dev_id, uint8_t port_id,
+         struct rte_event ev[], uint16_t nb_events, uint64_t timeout_ticks) {
+    return rte_event_dequeue_burst(dev_id, port_id, ev, nb_events, timeout_ticks);
+}
+#endif
+
+enum rte_meter_color
+_rte_meter_srtcm_color_blind_check(struct rte_meter_srtcm *m, uint64_t time, uint32_t pkt_len) {
+    return rte_meter_srtcm_color_blind_check(m, time, pkt_len);
+}
+
+enum rte_meter_color
+_rte_meter_srtcm_color_aware_check(struct rte_meter_srtcm *m, uint64_t time, uint32_t pkt_len,
+         enum rte_meter_color pkt_color) {
+    return rte_meter_srtcm_color_aware_check(m, time, pkt_len, pkt_color);
+}
+
+enum rte_meter_color
+_rte_meter_trtcm_color_blind_check(struct rte_meter_trtcm *m, uint64_t time, uint32_t pkt_len) {
+    return rte_meter_trtcm_color_blind_check(m, time, pkt_len);
+}
+
+enum rte_meter_color
+_rte_meter_trtcm_color_aware_check(struct rte_meter_trtcm *m, uint64_t time, uint32_t pkt_len,
+         enum rte_meter_color pkt_color) {
+    return rte_meter_trtcm_color_aware_check(m, time, pkt_len, pkt_color);
+}
+
+#if defined(RTE_LIBRTE_SECURITY) && RTE_VERSION >= RTE_VERSION_NUM(17, 11, 0, 0)
+struct rte_security_session *
+_rte_security_session_create_ipsec(struct rte_security_ctx *instance,
+    enum rte_security_session_action_type action_type,
+    uint32_t spi,
+    uint32_t salt,
+    enum rte_security_ipsec_sa_direction direction,
+    enum rte_security_ipsec_sa_protocol proto,
+    enum rte_security_ipsec_sa_mode mode,
+    uint32_t tunnel_src,
+    uint32_t tunnel_dst,
+    struct rte_crypto_sym_xform *xform,
+    struct rte_mempool *mp) {
+    struct rte_security_session_conf conf;
+
+    memset(&conf, 0, sizeof(conf));
+
+    conf.action_type = action_type;
+    conf.protocol = RTE_SECURITY_PROTOCOL_IPSEC;
+    conf.ipsec.spi = spi;
+    conf.ipsec.salt = salt;
+    conf.ipsec.direction = direction;
+    conf.ipsec.proto = proto;
+    conf.ipsec.mode = mode;
+
+    if (mode == RTE_SECURITY_IPSEC_SA_MODE_TUNNEL) {
+        conf.ipsec.tunnel.type = RTE_SECURITY_IPSEC_TUNNEL_IPV4;
+        conf.ipsec.tunnel.ipv4.src_ip.s_addr = tunnel_src;
+        conf.ipsec.tunnel.ipv4.dst_ip.s_addr = tunnel_dst;
+        conf.ipsec.tunnel.ipv4.ttl = 64;
+    }
+
+    conf.crypto_xform = xform;
+
+    return rte_security_session_create(instance, &conf, mp);
+}
+
+struct rte_security_session *
+_rte_security_session_create_macsec(struct rte_security_ctx *instance,
+    enum rte_security_session_action_type action_type,
+    struct rte_crypto_sym_xform *xform,
+    struct rte_mempool *mp) {
+    struct rte_security_session_conf conf;
+
+    memset(&conf, 0, sizeof(conf));
+
+    conf.action_type = action_type;
+    conf.protocol = RTE_SECURITY_PROTOCOL_MACSEC;
+    conf.crypto_xform = xform;
+
+    return rte_security_session_create(instance, &conf, mp);
+}
+#endif
+
+uint64_t
+_rte_table_hash_crc(void *key, uint32_t key_size, uint64_t seed) {
+    return rte_hash_crc(key, key_size, (uint32_t) seed);
+}
+
+uint32_t
+_rte_jhash(const void *key, uint32_t length, uint32_t initval) {
+    return rte_jhash(key, length, initval);
+}
+
+uint32_t
+_rte_jhash_2words(uint32_t a, uint32_t b, uint32_t initval) {
+    return rte_jhash_2words(a, b, initval);
+}
+
+uint32_t
+_rte_jhash_3words(uint32_t a, uint32_t b, uint32_t c, uint32_t initval) {
+    return rte_jhash_3words(a, b, c, initval);
+}
+
+uint32_t
+_rte_hash_crc(const void *data, uint32_t data_len, uint32_t init_val) {
+    return rte_hash_crc(data, data_len, init_val);
+}
+
+uint32_t
+_rte_softrss(uint32_t *input_tuple, uint32_t input_len, const uint8_t *rss_key) {
+    return rte_softrss(input_tuple, input_len, rss_key);
+}
+
+#if defined(RTE_LIBRTE_GRAPH) && RTE_VERSION >= RTE_VERSION_NUM(20, 5, 0, 0)
+static uint64_t
+_rte_graph_total_objs(struct rte_graph *graph) {
+    struct rte_node *node;
+    rte_graph_off_t off;
+    rte_node_t count;
+    uint64_t total = 0;
+
+    rte_graph_foreach_node(count, off, graph, node) {
+        total += node->total_objs;
+    }
+
+    return total;
+}
+
+uint64_t
+_rte_graph_walk(struct rte_graph *graph) {
+    uint64_t total = _rte_graph_total_objs(graph);
+
+    rte_graph_walk(graph);
+
+    return _rte_graph_total_objs(graph) - total;
+}
+#endif
+
+#if defined(RTE_LIBRTE_TM) && RTE_VERSION >= RTE_VERSION_NUM(17, 11, 0, 0)
+int
+_rte_tm_shaper_profile_add(uint16_t port_id,
+    uint32_t shaper_profile_id,
+    uint64_t rate,
+    uint64_t size,
+    int packet_mode,
+    struct rte_tm_error *error) {
+    struct rte_tm_shaper_params params;
+
+    memset(&params, 0, sizeof(params));
+
+    params.peak.rate = rate;
+    params.peak.size = size;
+    params.packet_mode = packet_mode;
+
+    return rte_tm_shaper_profile_add(port_id, shaper_profile_id, &params, error);
+}
+
+int
+_rte_tm_node_add(uint16_t port_id,
+    uint32_t node_id,
+    uint32_t parent_node_id,
+    uint32_t priority,
+    uint32_t weight,
+    uint32_t level_id,
+    uint32_t shaper_profile_id,
+    uint32_t n_sp_priorities,
+    uint64_t stats_mask,
+    int leaf,
+    struct rte_tm_error *error) {
+    struct rte_tm_node_params params;
+
+    memset(&params, 0, sizeof(params));
+
+    params.shaper_profile_id = shaper_profile_id;
+    params.stats_mask = stats_mask;
+
+    if (leaf) {
+        params.leaf.cman = RTE_TM_CMAN_TAIL_DROP;
+        params.leaf.wred.wred_profile_id = RTE_TM_WRED_PROFILE_ID_NONE;
+    } else {
+        params.nonleaf.n_sp_priorities = n_sp_priorities;
+    }
+
+    return rte_tm_node_add(port_id, node_id, parent_node_id, priority, weight, level_id,
+        &params, error);
+}
+#endif
+
+#if defined(RTE_LIBRTE_MTR) && RTE_VERSION >= RTE_VERSION_NUM(21, 5, 0, 0)
+int
+_rte_mtr_meter_profile_add(uint16_t port_id,
+    uint32_t meter_profile_id,
+    enum rte_mtr_algorithm alg,
+    uint64_t cir,
+    uint64_t pir,
+    uint64_t cbs,
+    uint64_t ebs,
+    int packet_mode,
+    struct rte_mtr_error *error) {
+    struct rte_mtr_meter_profile profile;
+
+    memset(&profile, 0, sizeof(profile));
+
+    profile.alg = alg;
+    profile.packet_mode = packet_mode;
+
+    switch (alg) {
+    case RTE_MTR_SRTCM_RFC2697:
+        profile.srtcm_rfc2697.cir = cir;
+        profile.srtcm_rfc2697.cbs = cbs;
+        profile.srtcm_rfc2697.ebs = ebs;
+        break;
+    case RTE_MTR_TRTCM_RFC2698:
+        profile.trtcm_rfc2698.cir = cir;
+        profile.trtcm_rfc2698.pir = pir;
+        profile.trtcm_rfc2698.cbs = cbs;
+        profile.trtcm_rfc2698.pbs = ebs;
+        break;
+    default:
+        break;
+    }
+
+    return rte_mtr_meter_profile_add(port_id, meter_profile_id, &profile, error);
+}
+
+int
+_rte_mtr_create(uint16_t port_id,
+    uint32_t mtr_id,
+    uint32_t meter_profile_id,
+    uint32_t meter_policy_id,
+    int use_prev_mtr_color,
+    int meter_enable,
+    uint64_t stats_mask,
+    struct rte_mtr_error *error) {
+    struct rte_mtr_params params;
+
+    memset(&params, 0, sizeof(params));
+
+    params.meter_profile_id = meter_profile_id;
+    params.meter_policy_id = meter_policy_id;
+    params.use_prev_mtr_color = use_prev_mtr_color;
+    params.meter_enable = meter_enable;
+    params.stats_mask = stats_mask;
+
+    return rte_mtr_create(port_id, mtr_id, &params, 0, error);
+}
+#endif
+
 struct rte_eth_conf*
 _rte_eth_conf_new() {
     struct rte_eth_conf *conf = malloc(sizeof(struct rte_eth_conf));
@@ -167,6 +665,11 @@ _rte_eth_conf_set_rss_conf(struct rte_eth_conf *conf, uint8_t *rss_key, uint8_t
     conf->rx_adv_conf.rss_conf.rss_hf = rss_hf;
 }
 
+void
+_rte_eth_conf_set_fdir_conf(struct rte_eth_conf *conf, const struct rte_fdir_conf *fdir_conf) {
+    conf->fdir_conf = *fdir_conf;
+}
+
 void
 _rte_eth_conf_set_tx_mode(struct rte_eth_conf *conf,
     enum rte_eth_tx_mq_mode mq_mode,
@@ -185,6 +688,13 @@ _rte_eth_tx_buffer_size(size_t size) {
     return RTE_ETH_TX_BUFFER_SIZE(size);
 }
 
+#if RTE_VERSION >= RTE_VERSION_NUM(18, 5, 0, 0)
+unsigned
+_rte_pktmbuf_pool_init_objs(struct rte_mempool *mp) {
+    return rte_mempool_obj_iter(mp, rte_pktmbuf_init, NULL);
+}
+#endif
+
 struct rte_mbuf *
 _rte_pktmbuf_alloc(struct rte_mempool *mp) {
     return rte_pktmbuf_alloc(mp);
@@ -239,3 +749,12 @@ void
 _cmdline_write(const struct cmdline *cl, const char *str) {
     cmdline_printf(cl, str);
 }
+
+#if defined(RTE_LIBRTE_TRACE) && RTE_VERSION >= RTE_VERSION_NUM(20, 5, 0, 0)
+void
+_rte_trace_point_emit(rte_trace_point_t *tp, const void *data, uint32_t len) {
+    __rte_trace_point_emit_header_generic(tp);
+
+    memcpy(mem, data, len);
+}
+#endif
@@ -0,0 +1,455 @@
+use std::ptr;
+use std::net::Ipv4Addr;
+use std::os::raw::c_void;
+
+use libc;
+
+use errors::{Error, Result};
+use crypto::{AuthAlgo, CipherAlgo, CryptoDevId, CryptoSymXform};
+use ethdev::PortId;
+use mbuf;
+use mempool::RawMemoryPool;
+
+/// Security session action type
+#[repr(u32)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum SecurityAction {
+    /// No security actions
+    None = 0, // RTE_SECURITY_ACTION_TYPE_NONE
+    /// Crypto processing for security protocol is processed inline during transmission
+    InlineCrypto = 1, // RTE_SECURITY_ACTION_TYPE_INLINE_CRYPTO
+    /// All security protocol processing is performed inline during transmission
+    InlineProtocol = 2, // RTE_SECURITY_ACTION_TYPE_INLINE_PROTOCOL
+    /// All security protocol processing including crypto is performed on a lookaside accelerator
+    LookasideProtocol = 3, // RTE_SECURITY_ACTION_TYPE_LOOKASIDE_PROTOCOL
+}
+
+/// IPSEC SA direction
+#[repr(u32)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum IPsecDirection {
+    /// Encrypt and generate digest
+    Outbound = 0, // RTE_SECURITY_IPSEC_SA_DIR_EGRESS
+    /// Verify digest and decrypt
+    Inbound = 1, // RTE_SECURITY_IPSEC_SA_DIR_INGRESS
+}
+
+/// IPsec security association protocol
+#[repr(u32)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum IPsecProto {
+    Ah = 1, // RTE_SECURITY_IPSEC_SA_PROTO_AH
+    Esp = 2, // RTE_SECURITY_IPSEC_SA_PROTO_ESP
+}
+
+/// IPsec security association mode
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum IPsecMode {
+    /// IPsec Transport mode
+    Transport,
+    /// IPsec Tunnel mode with the IPv4 source and destination of the outer header
+    Tunnel(Ipv4Addr, Ipv4Addr),
+}
+
+const RTE_SECURITY_IPSEC_SA_MODE_TRANSPORT: u32 = 1;
+const RTE_SECURITY_IPSEC_SA_MODE_TUNNEL: u32 = 2;
+
+/// IPsec security association configuration
+#[derive(Copy, Clone, Debug)]
+pub struct IPsecConf<'a> {
+    /// SA security parameter index
+    pub spi: u32,
+    /// SA salt
+    pub salt: u32,
+    pub direction: IPsecDirection,
+    pub proto: IPsecProto,
+    pub mode: IPsecMode,
+    /// The cipher and auth algorithms with the key material, in the order of processing
+    pub xforms: &'a [CryptoSymXform<'a>],
+}
+
+/// Security protocol of the session
+#[derive(Copy, Clone, Debug)]
+pub enum SecurityProtocol<'a> {
+    IPsec(IPsecConf<'a>),
+    Macsec(&'a [CryptoSymXform<'a>]),
+}
+
+/// Security session configuration
+#[derive(Copy, Clone, Debug)]
+pub struct SecuritySessionConf<'a> {
+    pub action: SecurityAction,
+    pub protocol: SecurityProtocol<'a>,
+}
+
+/// The security context of a device which supports security offload.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SecurityCtx(*mut c_void);
+
+impl SecurityCtx {
+    /// Get the security context of the crypto device.
+    pub fn get(dev_id: CryptoDevId) -> Result<SecurityCtx> {
+        let ctx = unsafe { rte_cryptodev_get_sec_ctx(dev_id) };
+
+        if ctx.is_null() {
+            Err(Error::OsError(libc::ENOTSUP))
+        } else {
+            Ok(SecurityCtx(ctx))
+        }
+    }
+
+    pub fn from_raw(ctx: *mut c_void) -> SecurityCtx {
+        SecurityCtx(ctx)
+    }
+
+    /// Extract the raw pointer from an underlying object.
+    pub fn as_raw(&self) -> *mut c_void {
+        self.0
+    }
+}
+
+const RTE_CRYPTO_SYM_XFORM_AUTH: u32 = 1;
+const RTE_CRYPTO_SYM_XFORM_CIPHER: u32 = 2;
+const RTE_CRYPTO_SYM_XFORM_AEAD: u32 = 3;
+
+const RTE_CRYPTO_CIPHER_AES_CBC: u32 = 5;
+const RTE_CRYPTO_AUTH_AES_GMAC: u32 = 4;
+const RTE_CRYPTO_AUTH_SHA1_HMAC: u32 = 10;
+const RTE_CRYPTO_AUTH_SHA256_HMAC: u32 = 14;
+const RTE_CRYPTO_AEAD_AES_GCM: u32 = 2;
+
+const AES_CBC_IV_LENGTH: u16 = 16;
+const AES_GCM_IV_LENGTH: u16 = 12;
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+struct RawSecurityKey {
+    data: *mut libc::uint8_t,
+    length: libc::uint16_t,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+struct RawSecurityIv {
+    offset: libc::uint16_t,
+    length: libc::uint16_t,
+}
+
+/// The union of `rte_crypto_cipher_xform`, `rte_crypto_auth_xform` and `rte_crypto_aead_xform`,
+/// the cipher and auth transforms are prefixes of the aead transform.
+#[repr(C)]
+#[derive(Copy, Clone)]
+struct RawSecurityXformParams {
+    op: libc::uint32_t,
+    algo: libc::uint32_t,
+    key: RawSecurityKey,
+    iv: RawSecurityIv,
+    digest_length: libc::uint16_t,
+    aad_length: libc::uint16_t,
+}
+
+/// The `rte_crypto_sym_xform` of the security API, which differs from the one of `crypto`
+/// since the AEAD transforms and the IV offsets are added in DPDK 17.08.
+#[repr(C)]
+#[derive(Copy, Clone)]
+struct RawSecuritySymXform {
+    next: *mut RawSecuritySymXform,
+    type_: libc::uint32_t,
+    params: RawSecurityXformParams,
+}
+
+impl RawSecuritySymXform {
+    fn new(type_: u32, op: u32, algo: u32, key: &[u8], iv_len: u16) -> Self {
+        RawSecuritySymXform {
+            next: ptr::null_mut(),
+            type_: type_,
+            params: RawSecurityXformParams {
+                op: op,
+                algo: algo,
+                key: RawSecurityKey {
+                    data: key.as_ptr() as *mut _,
+                    length: key.len() as u16,
+                },
+                iv: RawSecurityIv {
+                    offset: 0,
+                    length: iv_len,
+                },
+                digest_length: 0,
+                aad_length: 0,
+            },
+        }
+    }
+
+    /// Convert the transforms to the raw transforms linked in the order of the slice.
+    ///
+    /// AES-GCM, expressed as an `AesGcm` cipher followed by an `AesGcm` auth transform,
+    /// is merged to an AEAD transform. The returned vector must be kept alive
+    /// while the head of the chain is in use.
+    ///
+    fn chain(xforms: &[CryptoSymXform]) -> Vec<RawSecuritySymXform> {
+        let mut chain = Vec::with_capacity(xforms.len());
+        let mut iter = xforms.iter().peekable();
+
+        while let Some(xform) = iter.next() {
+            let raw = match *xform {
+                CryptoSymXform::Cipher { op, algo: CipherAlgo::AesGcm, key } => {
+                    let mut aead = RawSecuritySymXform::new(RTE_CRYPTO_SYM_XFORM_AEAD,
+                                                            op as u32,
+                                                            RTE_CRYPTO_AEAD_AES_GCM,
+                                                            key,
+                                                            AES_GCM_IV_LENGTH);
+
+                    let auth = match iter.peek() {
+                        Some(&&CryptoSymXform::Auth { algo: AuthAlgo::AesGcm,
+                                                      digest_len,
+                                                      aad_len,
+                                                      .. }) => Some((digest_len, aad_len)),
+                        _ => None,
+                    };
+
+                    if let Some((digest_len, aad_len)) = auth {
+                        aead.params.digest_length = digest_len as u16;
+                        aead.params.aad_length = aad_len as u16;
+
+                        iter.next();
+                    }
+
+                    aead
+                }
+                CryptoSymXform::Cipher { op, algo: CipherAlgo::AesCbc, key } => {
+                    RawSecuritySymXform::new(RTE_CRYPTO_SYM_XFORM_CIPHER,
+                                             op as u32,
+                                             RTE_CRYPTO_CIPHER_AES_CBC,
+                                             key,
+                                             AES_CBC_IV_LENGTH)
+                }
+                CryptoSymXform::Auth { op, algo, key, digest_len, .. } => {
+                    let (algo, iv_len) = match algo {
+                        AuthAlgo::Sha1Hmac => (RTE_CRYPTO_AUTH_SHA1_HMAC, 0),
+                        AuthAlgo::Sha256Hmac => (RTE_CRYPTO_AUTH_SHA256_HMAC, 0),
+                        // AES-GCM without the cipher transform is authentication only
+                        AuthAlgo::AesGcm => (RTE_CRYPTO_AUTH_AES_GMAC, AES_GCM_IV_LENGTH),
+                    };
+                    let mut auth = RawSecuritySymXform::new(RTE_CRYPTO_SYM_XFORM_AUTH,
+                                                            op as u32,
+                                                            algo,
+                                                            key,
+                                                            iv_len);
+
+                    auth.params.digest_length = digest_len as u16;
+
+                    auth
+                }
+            };
+
+            chain.push(raw);
+        }
+
+        for i in 1..chain.len() {
+            let next = &mut chain[i] as *mut _;
+
+            chain[i - 1].next = next;
+        }
+
+        chain
+    }
+}
+
+/// Security session, destroyed when dropped.
+pub struct Session {
+    ctx: SecurityCtx,
+    sess: *mut c_void,
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        if let Err(err) = self.destroy() {
+            warn!("fail to destroy security session, {}", err);
+        }
+    }
+}
+
+impl Session {
+    /// Create a security session with the configuration, allocated from the mempool.
+    pub fn create(ctx: SecurityCtx,
+                  conf: &SecuritySessionConf,
+                  mempool: &mut RawMemoryPool)
+                  -> Result<Session> {
+        let sess = match conf.protocol {
+            SecurityProtocol::IPsec(ref ipsec) => {
+                let mut chain = RawSecuritySymXform::chain(ipsec.xforms);
+                let (mode, src, dst) = match ipsec.mode {
+                    IPsecMode::Transport => {
+                        (RTE_SECURITY_IPSEC_SA_MODE_TRANSPORT, 0, 0)
+                    }
+                    IPsecMode::Tunnel(src, dst) => {
+                        (RTE_SECURITY_IPSEC_SA_MODE_TUNNEL, ipv4_be(&src), ipv4_be(&dst))
+                    }
+                };
+
+                unsafe {
+                    _rte_security_session_create_ipsec(ctx.0,
+                                                       conf.action as u32,
+                                                       ipsec.spi,
+                                                       ipsec.salt,
+                                                       ipsec.direction as u32,
+                                                       ipsec.proto as u32,
+                                                       mode,
+                                                       src,
+                                                       dst,
+                                                       xform_head(&mut chain),
+                                                       mempool)
+                }
+            }
+            SecurityProtocol::Macsec(xforms) => {
+                let mut chain = RawSecuritySymXform::chain(xforms);
+
+                unsafe {
+                    _rte_security_session_create_macsec(ctx.0,
+                                                        conf.action as u32,
+                                                        xform_head(&mut chain),
+                                                        mempool)
+                }
+            }
+        };
+
+        rte_check!(sess, NonNull; ok => {
+            Session {
+                ctx: ctx,
+                sess: sess,
+            }
+        })
+    }
+
+    /// The security context of the session.
+    pub fn ctx(&self) -> SecurityCtx {
+        self.ctx
+    }
+
+    /// Extract the raw pointer from an underlying object.
+    pub fn as_raw(&self) -> *mut c_void {
+        self.sess
+    }
+
+    /// Free the security session back to its mempool.
+    pub fn destroy(&mut self) -> Result<()> {
+        if self.sess.is_null() {
+            return Ok(());
+        }
+
+        let ret = unsafe { rte_security_session_destroy(self.ctx.0, self.sess) };
+
+        rte_check!(ret; ok => {
+            self.sess = ptr::null_mut();
+        })
+    }
+}
+
+fn ipv4_be(addr: &Ipv4Addr) -> u32 {
+    let octets = addr.octets();
+
+    u32::from_be(((octets[0] as u32) << 24) | ((octets[1] as u32) << 16) |
+                 ((octets[2] as u32) << 8) | (octets[3] as u32))
+}
+
+fn xform_head(chain: &mut Vec<RawSecuritySymXform>) -> *mut RawSecuritySymXform {
+    if chain.is_empty() {
+        ptr::null_mut()
+    } else {
+        chain.as_mut_ptr()
+    }
+}
+
+pub trait SecurityMbuf {
+    /// Attach the security session to the mbuf for inline processing on transmit.
+    fn set_security_session(&mut self, session: &Session) -> Result<&mut Self>;
+}
+
+impl SecurityMbuf for mbuf::RawMbuf {
+    fn set_security_session(&mut self, session: &Session) -> Result<&mut Self> {
+        let ret = unsafe {
+            rte_security_set_pkt_metadata(session.ctx.0, session.sess, self, ptr::null_mut())
+        };
+
+        rte_check!(ret; ok => { self })
+    }
+}
+
+/// The inline security offload of an Ethernet device.
+pub trait EthSecurity {
+    /// Get the security context of the Ethernet device, `None` if it does not support
+    /// the security offload.
+    fn security_ctx(&self) -> Option<SecurityCtx>;
+
+    /// Create a security session in the security context of the Ethernet device.
+    fn create_security_session(&self,
+                               conf: &SecuritySessionConf,
+                               mempool: &mut RawMemoryPool)
+                               -> Result<Session>;
+
+    /// Attach the security session to the mbuf before it is transmitted on the Ethernet device.
+    fn set_pkt_security_session(&self, m: &mut mbuf::RawMbuf, session: &Session) -> Result<()>;
+}
+
+impl EthSecurity for PortId {
+    fn security_ctx(&self) -> Option<SecurityCtx> {
+        let ctx = unsafe { rte_eth_dev_get_sec_ctx(u8::from(*self) as u16) };
+
+        if ctx.is_null() {
+            None
+        } else {
+            Some(SecurityCtx(ctx))
+        }
+    }
+
+    fn create_security_session(&self,
+                               conf: &SecuritySessionConf,
+                               mempool: &mut RawMemoryPool)
+                               -> Result<Session> {
+        match self.security_ctx() {
+            Some(ctx) => Session::create(ctx, conf, mempool),
+            None => Err(Error::OsError(libc::ENOTSUP)),
+        }
+    }
+
+    fn set_pkt_security_session(&self, m: &mut mbuf::RawMbuf, session: &Session) -> Result<()> {
+        if self.security_ctx() != Some(session.ctx) {
+            return Err(Error::OsError(libc::EINVAL));
+        }
+
+        m.set_security_session(session).map(|_| ())
+    }
+}
+
+extern "C" {
+    fn rte_cryptodev_get_sec_ctx(dev_id: libc::uint8_t) -> *mut c_void;
+
+    fn rte_eth_dev_get_sec_ctx(port_id: libc::uint16_t) -> *mut c_void;
+
+    fn rte_security_session_destroy(instance: *mut c_void, sess: *mut c_void) -> libc::c_int;
+
+    fn rte_security_set_pkt_metadata(instance: *mut c_void,
+                                     sess: *mut c_void,
+                                     m: mbuf::RawMbufPtr,
+                                     params: *mut c_void)
+                                     -> libc::c_int;
+
+    fn _rte_security_session_create_ipsec(instance: *mut c_void,
+                                          action_type: libc::uint32_t,
+                                          spi: libc::uint32_t,
+                                          salt: libc::uint32_t,
+                                          direction: libc::uint32_t,
+                                          proto: libc::uint32_t,
+                                          mode: libc::uint32_t,
+                                          tunnel_src: libc::uint32_t,
+                                          tunnel_dst: libc::uint32_t,
+                                          xform: *mut RawSecuritySymXform,
+                                          mp: *mut RawMemoryPool)
+                                          -> *mut c_void;
+
+    fn _rte_security_session_create_macsec(instance: *mut c_void,
+                                           action_type: libc::uint32_t,
+                                           xform: *mut RawSecuritySymXform,
+                                           mp: *mut RawMemoryPool)
+                                           -> *mut c_void;
+}
@@ -0,0 +1,246 @@
+//! The locks of DPDK protecting the data, like `std::sync::Mutex` and `RwLock`.
+//!
+//! The locks spin without yielding to the OS, and are kept in a box,
+//! so they are not moved while they are shared with the DPDK data structures.
+
+use std::fmt;
+use std::cell::UnsafeCell;
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+
+use ffi;
+
+use spinlock::{LockImpl, RawSpinLock, SpinLockImpl};
+
+pub type RawRwLock = ffi::rte_rwlock_t;
+pub type RawRwLockPtr = *mut ffi::rte_rwlock_t;
+
+/// A spinlock protecting the data.
+pub struct RteSpinlock<T: ?Sized> {
+    lock: Box<UnsafeCell<RawSpinLock>>,
+    data: UnsafeCell<T>,
+}
+
+unsafe impl<T: ?Sized + Send> Send for RteSpinlock<T> {}
+unsafe impl<T: ?Sized + Send> Sync for RteSpinlock<T> {}
+
+/// The guard of a locked spinlock, unlocked when dropped.
+pub struct RteSpinlockGuard<'a, T: ?Sized + 'a> {
+    lock: &'a RteSpinlock<T>,
+    _marker: PhantomData<*const ()>,
+}
+
+// the guard only gives `&T` to the other threads
+unsafe impl<'a, T: ?Sized + Sync> Sync for RteSpinlockGuard<'a, T> {}
+
+impl<T> RteSpinlock<T> {
+    pub fn new(data: T) -> RteSpinlock<T> {
+        let lock = RteSpinlock {
+            lock: Box::new(UnsafeCell::new(Default::default())),
+            data: UnsafeCell::new(data),
+        };
+
+        SpinLockImpl::init(lock.lock.get());
+
+        lock
+    }
+
+    /// Consume the lock, returning the data.
+    pub fn into_inner(self) -> T {
+        self.data.into_inner()
+    }
+}
+
+impl<T: ?Sized> RteSpinlock<T> {
+    /// Take the spinlock, spinning until it is available.
+    pub fn lock(&self) -> RteSpinlockGuard<T> {
+        SpinLockImpl::lock(self.lock.get());
+
+        RteSpinlockGuard {
+            lock: self,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Try to take the spinlock without spinning.
+    pub fn try_lock(&self) -> Option<RteSpinlockGuard<T>> {
+        if SpinLockImpl::trylock(self.lock.get()) == 0 {
+            None
+        } else {
+            Some(RteSpinlockGuard {
+                lock: self,
+                _marker: PhantomData,
+            })
+        }
+    }
+
+    /// Test if the spinlock is taken.
+    pub fn is_locked(&self) -> bool {
+        SpinLockImpl::is_locked(self.lock.get()) != 0
+    }
+
+    /// The raw spinlock, which address is stable.
+    pub fn as_raw(&self) -> *mut RawSpinLock {
+        self.lock.get()
+    }
+
+    /// Mutably borrow the data, the lock is not needed since the borrow is exclusive.
+    pub fn get_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.data.get() }
+    }
+}
+
+impl<T: Default> Default for RteSpinlock<T> {
+    fn default() -> Self {
+        RteSpinlock::new(Default::default())
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for RteSpinlock<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.try_lock() {
+            Some(guard) => write!(f, "RteSpinlock {{ data: {:?} }}", &*guard),
+            None => write!(f, "RteSpinlock {{ <locked> }}"),
+        }
+    }
+}
+
+impl<'a, T: ?Sized> Deref for RteSpinlockGuard<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*self.lock.data.get() }
+    }
+}
+
+impl<'a, T: ?Sized> DerefMut for RteSpinlockGuard<'a, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.lock.data.get() }
+    }
+}
+
+impl<'a, T: ?Sized> Drop for RteSpinlockGuard<'a, T> {
+    fn drop(&mut self) {
+        SpinLockImpl::unlock(self.lock.lock.get());
+    }
+}
+
+/// A reader-writer lock protecting the data.
+///
+/// The lock is shared by any number of readers, or taken by a single writer.
+///
+pub struct RteRwlock<T: ?Sized> {
+    lock: Box<UnsafeCell<RawRwLock>>,
+    data: UnsafeCell<T>,
+}
+
+unsafe impl<T: ?Sized + Send> Send for RteRwlock<T> {}
+unsafe impl<T: ?Sized + Send + Sync> Sync for RteRwlock<T> {}
+
+/// The guard of a read locked lock, unlocked when dropped.
+pub struct RteReadGuard<'a, T: ?Sized + 'a> {
+    lock: &'a RteRwlock<T>,
+}
+
+/// The guard of a write locked lock, unlocked when dropped.
+pub struct RteWriteGuard<'a, T: ?Sized + 'a> {
+    lock: &'a RteRwlock<T>,
+    _marker: PhantomData<*const ()>,
+}
+
+// the guard only gives `&T` to the other threads
+unsafe impl<'a, T: ?Sized + Sync> Sync for RteWriteGuard<'a, T> {}
+
+impl<T> RteRwlock<T> {
+    pub fn new(data: T) -> RteRwlock<T> {
+        RteRwlock {
+            // the counter is zero when unlocked
+            lock: Box::new(UnsafeCell::new(Default::default())),
+            data: UnsafeCell::new(data),
+        }
+    }
+
+    /// Consume the lock, returning the data.
+    pub fn into_inner(self) -> T {
+        self.data.into_inner()
+    }
+}
+
+impl<T: ?Sized> RteRwlock<T> {
+    /// Take the lock for reading, spinning while a writer holds it.
+    pub fn read(&self) -> RteReadGuard<T> {
+        unsafe { _rte_rwlock_read_lock(self.lock.get()) }
+
+        RteReadGuard { lock: self }
+    }
+
+    /// Take the lock for writing, spinning while the readers or a writer hold it.
+    pub fn write(&self) -> RteWriteGuard<T> {
+        unsafe { _rte_rwlock_write_lock(self.lock.get()) }
+
+        RteWriteGuard {
+            lock: self,
+            _marker: PhantomData,
+        }
+    }
+
+    /// The raw lock, which address is stable.
+    pub fn as_raw(&self) -> RawRwLockPtr {
+        self.lock.get()
+    }
+
+    /// Mutably borrow the data, the lock is not needed since the borrow is exclusive.
+    pub fn get_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.data.get() }
+    }
+}
+
+impl<T: Default> Default for RteRwlock<T> {
+    fn default() -> Self {
+        RteRwlock::new(Default::default())
+    }
+}
+
+impl<'a, T: ?Sized> Deref for RteReadGuard<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*self.lock.data.get() }
+    }
+}
+
+impl<'a, T: ?Sized> Drop for RteReadGuard<'a, T> {
+    fn drop(&mut self) {
+        unsafe { _rte_rwlock_read_unlock(self.lock.lock.get()) }
+    }
+}
+
+impl<'a, T: ?Sized> Deref for RteWriteGuard<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*self.lock.data.get() }
+    }
+}
+
+impl<'a, T: ?Sized> DerefMut for RteWriteGuard<'a, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.lock.data.get() }
+    }
+}
+
+impl<'a, T: ?Sized> Drop for RteWriteGuard<'a, T> {
+    fn drop(&mut self) {
+        unsafe { _rte_rwlock_write_unlock(self.lock.lock.get()) }
+    }
+}
+
+extern "C" {
+    fn _rte_rwlock_read_lock(rwl: RawRwLockPtr);
+
+    fn _rte_rwlock_read_unlock(rwl: RawRwLockPtr);
+
+    fn _rte_rwlock_write_lock(rwl: RawRwLockPtr);
+
+    fn _rte_rwlock_write_unlock(rwl: RawRwLockPtr);
+}
@@ -0,0 +1,120 @@
+//! Commands of the telemetry socket, which is started by EAL.
+//!
+//! The telemetry clients send the command with its parameters, e.g. `/dpdk/ethdev/link,0`,
+//! and receive the output of the handler in a JSON object keyed by the command.
+//!
+//! The output of a handler is reported as a JSON string, the client should decode it
+//! again when the handler formats a JSON document.
+
+use std::ffi::{CStr, CString};
+use std::sync::Mutex;
+use std::collections::HashMap;
+use std::os::raw::c_char;
+
+use libc;
+
+use ffi;
+
+use errors::{Error, Result};
+use ethdev::{EthDevice, PortId};
+
+/// The command handler, which receives the parameters and returns the output.
+pub type TelemetryHandler = fn(&str) -> String;
+
+/// The command of the link state handler `ethdev_link()`.
+pub const ETHDEV_LINK_CMD: &'static str = "/dpdk/ethdev/link";
+
+enum RawTelData {}
+
+lazy_static! {
+    // The C callback only receives the command, so the handlers are kept per command.
+    static ref HANDLERS: Mutex<HashMap<String, TelemetryHandler>> = Mutex::new(HashMap::new());
+}
+
+/// Register a command of the telemetry socket.
+///
+/// The command must start with `/` and only contain alphanumeric characters,
+/// `_` and `/`, the command is registered only once.
+///
+pub fn register(cmd: &str, help: &str, handler: TelemetryHandler) -> Result<()> {
+    let name = try!(CString::new(cmd));
+    let help = try!(CString::new(help));
+
+    HANDLERS.lock().unwrap().insert(String::from(cmd), handler);
+
+    let ret = unsafe {
+        rte_telemetry_register_cmd(name.as_ptr(), Some(telemetry_handler), help.as_ptr())
+    };
+
+    rte_check!(ret; err => {
+        HANDLERS.lock().unwrap().remove(cmd);
+
+        Error::from_ret(ret)
+    })
+}
+
+extern "C" fn telemetry_handler(cmd: *const c_char,
+                                params: *const c_char,
+                                d: *mut RawTelData)
+                                -> libc::c_int {
+    let cmd = unsafe { CStr::from_ptr(cmd).to_string_lossy() };
+    let params = if params.is_null() {
+        String::new()
+    } else {
+        unsafe { CStr::from_ptr(params).to_string_lossy().into_owned() }
+    };
+
+    let handler = match HANDLERS.lock().unwrap().get(cmd.as_ref()) {
+        Some(handler) => *handler,
+        None => return -libc::EINVAL,
+    };
+
+    match CString::new(handler(&params)) {
+        Ok(output) => unsafe { rte_tel_data_string(d, output.as_ptr()) },
+        Err(_) => -libc::EINVAL,
+    }
+}
+
+/// The link state of the valid ports as a JSON object keyed by the port id.
+///
+/// A port id in the parameters selects a single port.
+///
+pub fn ethdev_link(params: &str) -> String {
+    let selected = params.trim().parse::<u8>().ok();
+
+    let links = (0..ffi::RTE_MAX_ETHPORTS as u8)
+        .filter(|&port_id| selected.map_or(true, |selected| selected == port_id))
+        .filter_map(|port_id| PortId::new(port_id).ok())
+        .map(|port_id| {
+            let link = port_id.link_nowait();
+
+            format!("\"{}\":{{\"up\":{},\"speed\":{},\"duplex\":\"{}\",\"autoneg\":{}}}",
+                    port_id,
+                    link.up,
+                    link.speed,
+                    if link.duplex { "full" } else { "half" },
+                    link.autoneg)
+        })
+        .collect::<Vec<String>>();
+
+    format!("{{{}}}", links.join(","))
+}
+
+/// Register `ethdev_link()` as the `/dpdk/ethdev/link` command.
+pub fn register_ethdev_link() -> Result<()> {
+    register(ETHDEV_LINK_CMD,
+             "Returns the link state of the ports. Parameters: int port_id (optional)",
+             ethdev_link)
+}
+
+extern "C" {
+    fn rte_telemetry_register_cmd(cmd: *const c_char,
+                                  f: Option<extern "C" fn(*const c_char,
+                                                          *const c_char,
+                                                          *mut RawTelData)
+                                                          -> libc::c_int>,
+                                  help: *const c_char)
+                                  -> libc::c_int;
+
+    fn rte_tel_data_string(d: *mut RawTelData, s: *const c_char) -> libc::c_int;
+}
@@ -2,6 +2,10 @@ extern crate env_logger;
 extern crate num_cpus;
 
 use std::mem;
+use std::ptr;
+use std::slice;
+use std::thread;
+use std::time::Duration;
 use std::sync::{Arc, Mutex};
 use std::os::raw::c_void;
 
@@ -13,6 +17,7 @@ use ffi;
 use super::*;
 use super::memory::AsMutRef;
 use super::mempool::{MemoryPool, MemoryPoolDebug};
+use super::ethdev::{EthDevice, TxBuffer};
 
 #[test]
 fn test_eal() {
@@ -22,11 +27,25 @@ fn test_eal() {
                                String::from("-c"),
                                format!("{:x}", (1 << num_cpus::get()) - 1),
                                String::from("--log-level"),
-                               String::from("8")])
+                               String::from("8"),
+                               String::from("--vdev"),
+                               String::from("eth_null0")])
                    .unwrap(),
-               4);
+               6);
+
+    match eal::Eal::init(&["test"]) {
+        Err(Error::OsError(errno)) => assert_eq!(errno, ::libc::EALREADY),
+        res => panic!("unexpected result {:?}", res.map(|_| ())),
+    }
 
     assert_eq!(eal::process_type(), eal::ProcType::Primary);
+    assert_eq!(eal::process_type(), eal::ProcessType::Primary);
+    assert!(eal::Eal::is_primary_proc());
+    assert_eq!(eal::Eal::version(), eal::version());
+
+    let (year, month, minor) = dpdk_version!();
+
+    assert!(eal::version().contains(&format!("{}.{:02}.{}", year, month, minor)));
     assert!(!eal::primary_proc_alive());
     assert!(eal::has_hugepages());
     assert_eq!(eal::socket_id(), 0);
@@ -40,6 +59,25 @@ fn test_eal() {
     test_mempool();
 
     test_mbuf();
+
+    test_ethdev();
+
+    test_meter();
+
+    test_crypto();
+
+    test_allocator();
+
+    test_delay();
+
+    test_memory();
+
+    test_memzone();
+
+    #[cfg(dpdk_ver_17_05)]
+    test_distributor();
+
+    test_sync();
 }
 
 fn test_config() {
@@ -139,6 +177,40 @@ fn test_launch() {
 
         assert_eq!(*data, num_cpus::get());
     }
+
+    let launched = Arc::new(Mutex::new(Vec::new()));
+    let handle = {
+        let launched = launched.clone();
+
+        launch::mp_launch(move || {
+                              let lcore_id = lcore::id().unwrap();
+
+                              launched.lock().unwrap().push(lcore_id);
+
+                              lcore_id as i32
+                          },
+                          true)
+            .unwrap()
+    };
+
+    let lcores = handle.lcores().to_vec();
+    let results = handle.wait();
+
+    assert_eq!(results.iter().map(|&(lcore_id, _)| lcore_id).collect::<Vec<_>>(),
+               lcores);
+    assert!(results.iter().all(|&(lcore_id, ret)| ret == lcore_id as i32));
+    assert_eq!(launched.lock().unwrap().len(), lcores.len());
+
+    // the value returned on the MASTER lcore is kept
+    let handle = launch::mp_launch(|| lcore::id().unwrap() as i32 + 1, false).unwrap();
+    let master = lcore::master();
+
+    assert_eq!(handle.master(), Some((master, master as i32 + 1)));
+
+    let results = handle.wait();
+
+    assert_eq!(results.len(), lcore::count());
+    assert!(results.iter().all(|&(lcore_id, ret)| ret == lcore_id as i32 + 1));
 }
 
 fn test_mempool() {
@@ -175,6 +247,14 @@ fn test_mempool() {
     assert!(p.is_full());
     assert!(!p.is_empty());
 
+    let stats = p.stats();
+
+    assert_eq!(stats.total_objects, 16);
+    assert_eq!(stats.available, 16);
+    assert_eq!(stats.in_use, 0);
+    assert_eq!(stats.cache_available, 0);
+    assert!(stats.per_lcore_cache.is_empty());
+
     p.audit();
 
     if log_enabled!(Debug) {
@@ -202,9 +282,28 @@ fn test_mempool() {
 
     assert_eq!(elements.len(), 4);
 
+    let mut objs: Vec<*mut c_void> = Vec::new();
+
+    assert_eq!(mempool::obj_iter(p, |obj| objs.push(obj)), 16);
+    assert_eq!(objs.len(), p.size as usize);
+
+    let mut n = 0;
+
+    assert_eq!(unsafe { mempool::obj_iter_cached(p, |_: &u8| n += 1) }, 16);
+    assert_eq!(n, p.size);
+
     let raw_ptr = p as mempool::RawMemoryPoolPtr;
 
-    assert_eq!(raw_ptr, mempool::lookup("test").unwrap());
+    assert_eq!(mempool::lookup("test"), Some(raw_ptr));
+    assert_eq!(mempool::lookup("no_such_pool"), None);
+    assert!(mempool::lookup_existing("no_such_pool").is_err());
+    assert_eq!(mempool::lookup_existing("test").unwrap() as mempool::RawMemoryPoolPtr,
+               raw_ptr);
+    assert_eq!(mempool::lookup_or_create("test", |_| unreachable!()).unwrap(),
+               raw_ptr);
+
+    #[cfg(dpdk_ver_16_07)]
+    test_mempool_ops();
 
     let mut pools: Vec<mempool::RawMemoryPoolPtr> = Vec::new();
 
@@ -224,7 +323,18 @@ fn test_mempool() {
     }
 }
 
+#[cfg(dpdk_ver_16_07)]
+fn test_mempool_ops() {
+    assert!(mempool::pool_ops_supported("ring_mp_mc"));
+    assert!(!mempool::pool_ops_supported("no_such_ops"));
+    assert!(mempool::registered_ops().iter().any(|ops| ops == "ring_mp_mc"));
+    assert!(mempool::has_ops("ring_mp_mc"));
+    assert!(!mempool::has_ops("no_such_ops"));
+}
+
 fn test_mbuf() {
+    use mbuf::{PktMbuf, PktMbufPool};
+
     const NB_MBUF: u32 = 1024;
     const CACHE_SIZE: u32 = 32;
     const PRIV_SIZE: u16 = 0;
@@ -259,4 +369,938 @@ fn test_mbuf() {
     assert!(!p.is_empty());
 
     p.audit();
+
+    #[cfg(dpdk_ver_18_05)]
+    test_pktmbuf_pool_by_ops();
+
+    match unsafe { mbuf::check_ptr(ptr::null_mut()) } {
+        Err(Error::NullPtr) => {}
+        res => panic!("unexpected result {:?}", res.map(|_| ())),
+    }
+
+    let m = p.alloc().as_mut_ref().unwrap();
+
+    unsafe { mbuf::assert_valid(m) };
+
+    m.set_user_data(0x12345678u32);
+
+    assert_eq!(m.user_data::<u32>(), 0x12345678);
+    assert_eq!(m.user_data::<u64>(), 0x12345678);
+    assert_eq!(m.private_data::<u64>(), None);
+
+    m.set_vlan_tci(0xa064);
+    m.set_outer_vlan_tci(100);
+
+    assert_eq!(m.vlan_tci(), 0xa064);
+    assert_eq!((m.vlan_id(), m.vlan_pcp()), (100, 5));
+    assert_eq!(m.outer_vlan_tci(), 100);
+    assert!(mbuf::OffloadFlags::from_bits_truncate(m.ol_flags)
+        .contains(mbuf::PKT_TX_VLAN_PKT | mbuf::PKT_TX_QINQ_PKT));
+
+    unsafe { ptr::write_bytes(m.append(4).unwrap(), 0xab, 4) };
+
+    assert_eq!(m.segments().count(), 1);
+
+    let s = format!("{:?}", m.debug());
+
+    assert!(s.contains("pkt_len: 4"));
+    assert!(s.contains("payload: ab ab ab ab"));
+    assert!(s.contains("next: None"));
+
+    m.free();
+
+    test_mpls(p);
+    test_vxlan(p);
+    test_gre(p);
+    test_arp(p);
+    test_icmp(p);
+    test_parse_headers(p);
+    test_reorder(p);
+}
+
+#[cfg(dpdk_ver_18_05)]
+fn test_pktmbuf_pool_by_ops() {
+    let ops_pool = mbuf::pktmbuf_pool_create_by_ops("ops_pool",
+                                                    64,
+                                                    0,
+                                                    0,
+                                                    mbuf::RTE_MBUF_DEFAULT_BUF_SIZE,
+                                                    eal::socket_id(),
+                                                    "ring_mp_mc")
+        .as_mut_ref()
+        .unwrap();
+
+    assert_eq!(ops_pool.name(), "ops_pool");
+    assert_eq!(ops_pool.size, 64);
+    assert!(mbuf::pktmbuf_pool_create_by_ops("bad_ops_pool",
+                                             64,
+                                             0,
+                                             0,
+                                             mbuf::RTE_MBUF_DEFAULT_BUF_SIZE,
+                                             eal::socket_id(),
+                                             "no_such_ops")
+        .is_err());
+}
+
+/// Allocate a zeroed Ethernet frame of `len` bytes with the IPv4 Ether type
+/// and the first byte of an IPv4 header.
+fn ipv4_frame(p: &mut mempool::RawMemoryPool, len: usize) -> &mut mbuf::RawMbuf {
+    use mbuf::{PktMbuf, PktMbufPool};
+
+    let m = unsafe { p.alloc().as_mut().unwrap() };
+    let data = m.append(len).unwrap();
+
+    unsafe {
+        ptr::write_bytes(data, 0, len);
+
+        *data.offset(12) = 0x08;
+        *data.offset(14) = 0x45;
+    }
+
+    m
+}
+
+fn test_mpls(p: &mut mempool::RawMemoryPool) {
+    use mbuf::PktMbuf;
+
+    let m = ipv4_frame(p, 34);
+
+    mbuf::mpls_push(m, 0x12345, 5, true, 64).unwrap();
+
+    assert_eq!(m.data_len, 38);
+
+    let data: *mut u8 = pktmbuf_mtod!(m as *mut mbuf::RawMbuf, *mut u8);
+
+    unsafe {
+        assert_eq!(slice::from_raw_parts(data.offset(12), 6),
+                   &[0x88, 0x47, 0x12, 0x34, 0x5b, 0x40]);
+    }
+
+    assert_eq!(mbuf::mpls_pop(m).unwrap(),
+               mbuf::MplsLabel::new(0x12345, 5, true, 64));
+
+    assert_eq!(m.data_len, 34);
+
+    let data: *mut u8 = pktmbuf_mtod!(m as *mut mbuf::RawMbuf, *mut u8);
+
+    unsafe {
+        assert_eq!(slice::from_raw_parts(data.offset(12), 3), &[0x08, 0x00, 0x45]);
+    }
+
+    assert!(mbuf::mpls_pop(m).is_err());
+
+    m.free();
+}
+
+fn test_vxlan(p: &mut mempool::RawMemoryPool) {
+    use mbuf::PktMbuf;
+
+    let m = ipv4_frame(p, 64);
+
+    unsafe {
+        *m.packet_type() = (mbuf::RTE_PTYPE_L2_ETHER | mbuf::RTE_PTYPE_L3_IPV4_EXT_UNKNOWN |
+                            mbuf::RTE_PTYPE_L4_TCP)
+            .bits;
+    }
+
+    assert!(mbuf::vxlan_encap(m, 1 << 24, 0x0a000001, 0x0a000002, 0, 4789).is_err());
+
+    mbuf::vxlan_encap(m, 0x123456, 0x0a000001, 0x0a000002, 0, mbuf::VXLAN_DEFAULT_PORT)
+        .unwrap();
+
+    assert_eq!(m.data_len, 114);
+    assert!(m.ol_flags & (mbuf::PKT_TX_OUTER_IP_CKSUM | mbuf::PKT_TX_OUTER_IPV4).bits != 0);
+
+    let data: *mut u8 = pktmbuf_mtod!(m as *mut mbuf::RawMbuf, *mut u8);
+
+    unsafe {
+        let hdr = slice::from_raw_parts(data, 50);
+
+        assert_eq!(&hdr[12..14], &[0x08, 0x00]);
+        assert_eq!(&hdr[16..18], &[0x00, 100]);
+        assert_eq!(&hdr[26..34], &[10, 0, 0, 1, 10, 0, 0, 2]);
+        assert!(hdr[34] >= 0xc0);
+        assert_eq!(&hdr[36..38], &[0x12, 0xb5]);
+        assert_eq!(&hdr[42..50], &[0x08, 0, 0, 0, 0x12, 0x34, 0x56, 0]);
+
+        assert_eq!(*m.packet_type(),
+                   (mbuf::RTE_PTYPE_L2_ETHER | mbuf::RTE_PTYPE_L3_IPV4 | mbuf::RTE_PTYPE_L4_UDP |
+                    mbuf::RTE_PTYPE_TUNNEL_VXLAN |
+                    mbuf::RTE_PTYPE_INNER_L2_ETHER |
+                    mbuf::RTE_PTYPE_INNER_L3_IPV4_EXT_UNKNOWN |
+                    mbuf::RTE_PTYPE_INNER_L4_TCP)
+                       .bits);
+    }
+
+    assert_eq!(mbuf::vxlan_decap(m).unwrap(), 0x123456);
+    assert_eq!(m.data_len, 64);
+    assert_eq!(unsafe { *m.packet_type() },
+               (mbuf::RTE_PTYPE_L2_ETHER | mbuf::RTE_PTYPE_L3_IPV4_EXT_UNKNOWN |
+                mbuf::RTE_PTYPE_L4_TCP)
+                   .bits);
+    assert_eq!(m.ol_flags & (mbuf::PKT_TX_OUTER_IP_CKSUM | mbuf::PKT_TX_OUTER_IPV4).bits,
+               0);
+
+    assert!(mbuf::vxlan_decap(m).is_err());
+
+    m.free();
+}
+
+fn test_gre(p: &mut mempool::RawMemoryPool) {
+    use mbuf::PktMbuf;
+
+    let m = ipv4_frame(p, 64);
+
+    unsafe {
+        *m.packet_type() = (mbuf::RTE_PTYPE_L2_ETHER | mbuf::RTE_PTYPE_L3_IPV4 |
+                            mbuf::RTE_PTYPE_L4_UDP)
+            .bits;
+    }
+
+    mbuf::gre_encap_with_checksum(m, Some(0x1234), Some(7), 0x0800, 0x0a000001, 0x0a000002)
+        .unwrap();
+
+    // the inner Ethernet header is reused, followed by IPv4 and GRE with checksum, key and sequence
+    assert_eq!(m.data_len, 64 + 20 + 16);
+
+    let data: *mut u8 = pktmbuf_mtod!(m as *mut mbuf::RawMbuf, *mut u8);
+
+    unsafe {
+        let hdr = slice::from_raw_parts(data, 51);
+
+        assert_eq!(&hdr[12..14], &[0x08, 0x00]);
+        assert_eq!(hdr[23], 47);
+        assert_eq!(&hdr[34..38], &[0xb0, 0x00, 0x08, 0x00]);
+        assert_eq!(&hdr[42..50], &[0, 0, 0x12, 0x34, 0, 0, 0, 7]);
+        assert_eq!(hdr[50], 0x45);
+
+        assert_eq!(*m.packet_type(),
+                   (mbuf::RTE_PTYPE_L2_ETHER | mbuf::RTE_PTYPE_L3_IPV4 |
+                    mbuf::RTE_PTYPE_TUNNEL_GRE |
+                    mbuf::RTE_PTYPE_INNER_L3_IPV4 | mbuf::RTE_PTYPE_INNER_L4_UDP)
+                       .bits);
+    }
+
+    let hdr = mbuf::gre_decap(m).unwrap();
+
+    assert_eq!(hdr.protocol_type, 0x0800);
+    assert!(hdr.checksum.is_some());
+    assert_eq!(hdr.key, Some(0x1234));
+    assert_eq!(hdr.seq, Some(7));
+    assert_eq!(hdr.len(), 16);
+
+    assert_eq!(m.data_len, 64);
+    assert_eq!(unsafe { *m.packet_type() },
+               (mbuf::RTE_PTYPE_L2_ETHER | mbuf::RTE_PTYPE_L3_IPV4 | mbuf::RTE_PTYPE_L4_UDP).bits);
+
+    let data: *mut u8 = pktmbuf_mtod!(m as *mut mbuf::RawMbuf, *mut u8);
+
+    unsafe {
+        assert_eq!(slice::from_raw_parts(data.offset(12), 3), &[0x08, 0x00, 0x45]);
+    }
+
+    mbuf::gre_encap(m, None, None, 0x6558, 0x0a000001, 0x0a000002).unwrap();
+
+    assert_eq!(m.data_len, 64 + 14 + 20 + 4);
+    assert_eq!(mbuf::gre_decap(m).unwrap().len(), 4);
+    assert_eq!(m.data_len, 64);
+
+    assert!(mbuf::gre_decap(m).is_err());
+
+    m.free();
+}
+
+fn test_arp(p: &mut mempool::RawMemoryPool) {
+    use mbuf::PktMbuf;
+
+    let our_hw = ether::EtherAddr::new(0x02, 0, 0, 0, 0, 1);
+    let peer_hw = ether::EtherAddr::new(0x02, 0, 0, 0, 0, 2);
+
+    let m = unsafe {
+        arp::ArpRequest::new(peer_hw, 0x0a000002, 0x0a000001).build(p).unwrap().as_mut().unwrap()
+    };
+
+    let reply = {
+        let req = arp::parse(m).unwrap();
+
+        assert!(req.is_request());
+        assert_eq!(req.sender_hw_addr(), peer_hw);
+        assert_eq!(req.sender_proto_addr(), 0x0a000002);
+        assert!(req.target_hw_addr().is_zero());
+        assert_eq!(req.target_proto_addr(), 0x0a000001);
+
+        arp::ArpReply::new(&req, our_hw)
+    };
+
+    m.free();
+
+    let m = unsafe { reply.build(p).unwrap().as_mut().unwrap() };
+
+    {
+        let eth = pktmbuf_mtod!(m as *mut mbuf::RawMbuf, *const ether::EtherHdr);
+
+        assert_eq!(ether::EtherAddr::from(unsafe { (*eth).d_addr }), peer_hw);
+
+        let rep = arp::parse(m).unwrap();
+
+        assert!(rep.is_reply());
+        assert_eq!(rep.sender_hw_addr(), our_hw);
+        assert_eq!(rep.sender_proto_addr(), 0x0a000001);
+        assert_eq!(rep.target_hw_addr(), peer_hw);
+        assert_eq!(rep.target_proto_addr(), 0x0a000002);
+    }
+
+    m.free();
+}
+
+fn test_icmp(p: &mut mempool::RawMemoryPool) {
+    use mbuf::PktMbuf;
+
+    let req = icmp::IcmpEchoRequest {
+        id: 0x1234,
+        seq: 1,
+        data: b"ping".to_vec(),
+    };
+
+    let m = unsafe { req.build(0x0a000001, 0x0a000002, p).unwrap().as_mut().unwrap() };
+
+    assert_eq!(m.data_len, 14 + 20 + 8 + 4);
+    assert!(m.ol_flags & (mbuf::PKT_TX_IPV4 | mbuf::PKT_TX_IP_CKSUM).bits != 0);
+
+    // a request is not a reply
+    assert_eq!(icmp::parse_echo_reply(m), None);
+
+    // turn the request into a reply, updating the checksum
+    unsafe {
+        let data = pktmbuf_mtod_offset!(m as *mut mbuf::RawMbuf, *mut u8, 14 + 20);
+        let icmp = slice::from_raw_parts_mut(data, 12);
+
+        assert_eq!(ip::raw_cksum(icmp), 0xFFFF);
+
+        icmp[0] = icmp::IP_ICMP_ECHO_REPLY;
+        icmp[2] = 0;
+        icmp[3] = 0;
+
+        let cksum = !ip::raw_cksum(icmp);
+
+        icmp[2] = (cksum >> 8) as u8;
+        icmp[3] = cksum as u8;
+    }
+
+    assert_eq!(icmp::parse_echo_reply(m),
+               Some(icmp::IcmpEchoReply {
+                   src_ip: 0x0a000001,
+                   dst_ip: 0x0a000002,
+                   id: 0x1234,
+                   seq: 1,
+                   data: b"ping".to_vec(),
+               }));
+
+    m.free();
+}
+
+fn test_parse_headers(p: &mut mempool::RawMemoryPool) {
+    use mbuf::{PktMbuf, PktMbufPool};
+
+    let m = unsafe { p.alloc().as_mut().unwrap() };
+
+    assert_eq!(m.eth_type(), None);
+    assert_eq!(m.ip_proto(), None);
+
+    // Ethernet, 802.1Q tag, IPv4 and UDP from port 12345 to 53
+    let pkt: Vec<u8> = [&[0u8; 12][..],
+                        &[0x81, 0x00, 0x00, 0x64, 0x08, 0x00],
+                        &[0x45, 0, 0, 28, 0, 0, 0, 0, 64, 17, 0, 0, 10, 0, 0, 1, 10, 0, 0, 2],
+                        &[0x30, 0x39, 0x00, 0x35, 0, 8, 0, 0]]
+        .concat();
+
+    unsafe {
+        ptr::copy_nonoverlapping(pkt.as_ptr(), m.append(pkt.len()).unwrap(), pkt.len());
+    }
+
+    assert_eq!(m.eth_type(), Some(0x8100));
+    assert_eq!(m.ip_proto(), Some(17));
+    assert_eq!(m.l4_ports(), Some((12345, 53)));
+    assert!(m.is_ipv4());
+    assert!(!m.is_ipv6());
+    assert!(m.is_udp());
+    assert!(!m.is_tcp());
+
+    m.trim(10).unwrap();
+
+    assert_eq!(m.ip_proto(), None);
+    assert_eq!(m.l4_ports(), None);
+
+    m.free();
+}
+
+fn test_reorder(p: &mut mempool::RawMemoryPool) {
+    use mbuf::{PktMbuf, PktMbufPool};
+
+    assert!(reorder::ReorderBuffer::create("test_reorder", eal::socket_id(), 100).is_err());
+
+    let mut b = reorder::ReorderBuffer::create("test_reorder", eal::socket_id(), 128).unwrap();
+
+    let mut mbufs = [ptr::null_mut(); 3];
+
+    p.alloc_bulk(&mut mbufs).unwrap();
+
+    for (m, seqn) in mbufs.iter().zip([0, 2, 1].iter()) {
+        unsafe { (**m).seqn = *seqn };
+
+        b.insert(*m).unwrap();
+    }
+
+    let mut drained = [ptr::null_mut(); 4];
+
+    assert_eq!(b.drain(&mut drained), 3);
+
+    let seqns: Vec<u32> = drained[..3].iter().map(|m| unsafe { (**m).seqn }).collect();
+
+    assert_eq!(seqns, vec![0, 1, 2]);
+
+    for m in &drained[..3] {
+        unsafe { (**m).free() };
+    }
+
+    b.reset();
+}
+
+fn test_ethdev() {
+    assert_eq!(ethdev::count(), 1);
+
+    let p = mbuf::pktmbuf_pool_create("eth_pool",
+                                      1024,
+                                      32,
+                                      0,
+                                      mbuf::RTE_MBUF_DEFAULT_BUF_SIZE,
+                                      eal::socket_id())
+        .as_mut_ref()
+        .unwrap();
+
+    let dev = ethdev::PortId::new(0).unwrap();
+
+    match ethdev::PortId::new(ffi::RTE_MAX_ETHPORTS as u8 - 1) {
+        Err(Error::InvalidPortId(port_id)) => assert_eq!(port_id, ffi::RTE_MAX_ETHPORTS as u8 - 1),
+        res => panic!("unexpected result {:?}", res),
+    }
+
+    let conf = ethdev::EthConf::default();
+
+    assert!(conf.rxmode.as_ref().map_or(false, |rxmode| rxmode.hw_ip_checksum));
+    assert!(conf.rxmode.as_ref().map_or(false, |rxmode| !rxmode.hw_vlan_filter));
+    assert!(conf.txmode.is_some());
+    assert!(ethdev::EthConf::zeroed().rxmode.is_none());
+
+    let fdir_conf = ffi::Struct_rte_fdir_conf::from(ethdev::FdirConf {
+        mode: ethdev::FdirMode::Perfect,
+        drop_queue: 127,
+        flex_conf: Some(ethdev::FdirFlexConf {
+            masks: vec![ethdev::FdirFlexMask { flow_type: 1, mask: [0xff; 16] }],
+            ..Default::default()
+        }),
+        ..Default::default()
+    });
+
+    assert_eq!(fdir_conf.mode as u32, ffi::Enum_rte_fdir_mode::RTE_FDIR_MODE_PERFECT as u32);
+    assert_eq!(fdir_conf.drop_queue, 127);
+    assert_eq!((fdir_conf.flex_conf.nb_payloads, fdir_conf.flex_conf.nb_flexmasks), (0, 1));
+
+    dev.configure(1, 1, &conf).unwrap();
+    dev.rx_queue_setup(0, 128, None, p).unwrap();
+    dev.tx_queue_setup(0, 512, None).unwrap();
+    dev.start().unwrap();
+
+    assert!(dev.wait_link_up(Duration::from_secs(1)).unwrap().up);
+
+    assert_eq!(dev.nb_rx_queues().unwrap(), 1);
+    assert_eq!(dev.nb_tx_queues().unwrap(), 1);
+    assert!(ethdev::PortId::new_unchecked(ffi::RTE_MAX_ETHPORTS as u8 - 1).nb_rx_queues().is_err());
+    assert!(ethdev::PortId::new_unchecked(ffi::RTE_MAX_ETHPORTS as u8 - 1)
+        .get_split_drop_en()
+        .is_err());
+    assert_eq!(dev.active_queue_counts().unwrap(), (1, 1));
+
+    match ethdev::PortId::new_unchecked(ffi::RTE_MAX_ETHPORTS as u8 - 1).active_queue_counts() {
+        Err(Error::InvalidPortId(port_id)) => assert_eq!(port_id, ffi::RTE_MAX_ETHPORTS as u8 - 1),
+        res => panic!("unexpected result {:?}", res),
+    }
+
+    assert!(dev.mac_addrs().unwrap().len() <= dev.info().max_mac_addrs as usize);
+
+    match ethdev::PortId::new_unchecked(ffi::RTE_MAX_ETHPORTS as u8 - 1).mac_addrs() {
+        Err(Error::InvalidPortId(port_id)) => assert_eq!(port_id, ffi::RTE_MAX_ETHPORTS as u8 - 1),
+        res => panic!("unexpected result {:?}", res),
+    }
+
+    assert!(dev.vlan_offload().unwrap().bits() & !0x7 == 0);
+
+    let (min_mtu, max_mtu) = (dev.min_mtu().unwrap(), dev.max_mtu().unwrap());
+
+    assert_eq!(min_mtu, ffi::ETHER_MIN_MTU as u16);
+    assert!(max_mtu >= dev.mtu().unwrap());
+
+    match dev.set_mtu_checked(0) {
+        Err(Error::InvalidMtu { requested, min, max }) => {
+            assert_eq!((requested, min, max), (0, min_mtu, max_mtu))
+        }
+        res => panic!("unexpected result {:?}", res.map(|_| ())),
+    }
+
+    assert!(ethdev::PortId::new_unchecked(ffi::RTE_MAX_ETHPORTS as u8 - 1).max_mtu().is_err());
+
+    assert!(dev.get_supported_speeds().is_ok());
+    assert_eq!(ethdev::ETH_LINK_SPEED_25G.to_mbps(), Some(25000));
+    assert!(ethdev::EthRssConf::symmetric().is_symmetric());
+    assert!(!ethdev::EthRssConf::default().is_symmetric());
+    assert_eq!((ethdev::ETH_LINK_SPEED_1G | ethdev::ETH_LINK_SPEED_FIXED).to_mbps(), None);
+    assert_eq!((ethdev::ETH_LINK_SPEED_10G | ethdev::ETH_LINK_SPEED_1G).speeds(),
+               vec![ethdev::ETH_LINK_SPEED_1G, ethdev::ETH_LINK_SPEED_10G]);
+    assert_eq!(ethdev::count_valid(), 1);
+    assert_eq!(ethdev::count_running(), 1);
+    assert_eq!(dev.iter_rx_queues().count(), 1);
+    assert_eq!(dev.iter_tx_queues().count(), 1);
+
+    #[cfg(dpdk_ver_18_02)]
+    test_ethdev_reset(dev);
+
+    assert!(dev.xstats().is_ok());
+
+    {
+        let mut rx = ethdev::RxHandler::new(dev, 0);
+        let tx = ethdev::TxHandler::new(dev, 0);
+
+        let mut pkts = rx.by_ref().take(4).collect::<Vec<mbuf::RawMbufPtr>>();
+
+        assert_eq!(pkts.len(), 4);
+        #[cfg(dpdk_ver_17_02)]
+        assert_eq!(dev.tx_prepare(0, &mut pkts), 4);
+        assert_eq!(tx.send(&mut pkts), 4);
+    }
+
+    let polled = Arc::new(Mutex::new(Vec::new()));
+    let poller = {
+        let polled = polled.clone();
+
+        ethdev::StatsPoller::new(vec![dev],
+                                 Duration::from_millis(10),
+                                 move |port, _| polled.lock().unwrap().push(port))
+    };
+
+    thread::sleep(Duration::from_millis(50));
+
+    drop(poller);
+
+    assert!(!polled.lock().unwrap().is_empty());
+    assert!(polled.lock().unwrap().iter().all(|port| *port == dev));
+
+    let changes = Arc::new(Mutex::new(Vec::new()));
+    let watcher = {
+        let changes = changes.clone();
+
+        ethdev::LinkWatcher::new(vec![dev],
+                                 Duration::from_millis(10),
+                                 move |port, link| changes.lock().unwrap().push((port, link)))
+    };
+
+    thread::sleep(Duration::from_millis(50));
+
+    assert_eq!(watcher.current_state(dev), Some(dev.link_nowait()));
+    assert_eq!(*changes.lock().unwrap(), vec![(dev, dev.link_nowait())]);
+
+    drop(watcher);
+
+    dev.stop_and_drain();
+
+    assert!(ethdev::LoopbackMode::DISABLED.validate("rte_em_pmd").is_ok());
+    assert!(ethdev::LoopbackMode::IXGBE_TX_RX.validate("rte_ixgbe_pmd").is_ok());
+    assert!(ethdev::LoopbackMode::IXGBE_TX_RX.validate("net_ixgbe").is_ok());
+    assert!(ethdev::LoopbackMode::PHY.validate("rte_ixgbe_pmd").is_err());
+    assert!(ethdev::LoopbackMode::IXGBE_TX_RX.validate("rte_ixgbevf_pmd").is_err());
+    assert!(ethdev::LoopbackMode::I40E_MAC.validate("rte_ixgbe_pmd").is_err());
+    assert!(ethdev::LoopbackMode::IXGBE_TX_RX.validate("net_i40e").is_err());
+    assert_eq!(ethdev::LoopbackMode::MAC.driver_value("net_ixgbe").unwrap(), 1);
+    assert_eq!(ethdev::LoopbackMode::MAC.driver_value("rte_i40e_pmd").unwrap(), 1);
+    assert_eq!(ethdev::LoopbackMode::DISABLED.driver_value("rte_em_pmd").unwrap(), 0);
+
+    match ethdev::LoopbackMode::MAC.validate("rte_em_pmd") {
+        Err(Error::UnsupportedLoopbackMode(driver, _)) => assert_eq!(driver, "rte_em_pmd"),
+        res => panic!("unexpected result {:?}", res),
+    }
+
+    let mut lpbk_conf = ethdev::EthConf::default();
+
+    assert!(dev.set_loopback_mode(&mut lpbk_conf, ethdev::LoopbackMode::IXGBE_TX_RX).is_err());
+    assert_eq!(lpbk_conf.lpbk_mode, 0);
+    assert!(dev.set_loopback_mode(&mut lpbk_conf, ethdev::LoopbackMode::DISABLED).is_ok());
+
+    assert!(ethdev::detach(dev).is_err());
+
+    let null_dev = ethdev::attach("eth_null1").unwrap();
+
+    assert_eq!(ethdev::port_name(null_dev).unwrap(), "eth_null1");
+    assert_eq!(ethdev::port_by_name("eth_null1").unwrap(), null_dev);
+    assert!(ethdev::port_by_name("eth_null9").is_err());
+
+    let unicast = ether::EtherAddr::new(0x18, 0x2b, 0x3c, 0x4d, 0x5e, 0x6f);
+
+    match null_dev.set_mc_addr_list(&[unicast]) {
+        Err(Error::OsError(errno)) => assert_eq!(errno, ::libc::EINVAL),
+        res => panic!("unexpected result {:?}", res.map(|_| ())),
+    }
+
+    #[cfg(dpdk_ver_17_02)]
+    test_ethdev_vf(null_dev);
+
+    assert!(null_dev.enable_qinq_stripping().is_err());
+
+    match null_dev.l2_tunnel_offload_set(ethdev::L2TunnelType::Standard802_1Q,
+                                         ethdev::ETH_L2_TUNNEL_INSERTION_MASK.bits,
+                                         true) {
+        Err(Error::OsError(errno)) => assert_eq!(errno, ::libc::ENOTSUP),
+        res => panic!("unexpected result {:?}", res.map(|_| ())),
+    }
+
+    match null_dev.l2_tunnel_offload_set(ethdev::L2TunnelType::ETag, 0x100, true) {
+        Err(Error::OsError(errno)) => assert_eq!(errno, ::libc::EINVAL),
+        res => panic!("unexpected result {:?}", res.map(|_| ())),
+    }
+
+    let prev = ethdev::VfStats { ipackets: 100, ibytes: 6400, ..Default::default() };
+    let cur = ethdev::VfStats { ipackets: 300, ibytes: 19200, ..Default::default() };
+    let delta = cur - prev;
+
+    assert_eq!(delta.ipackets, 200);
+    assert_eq!(prev + delta, cur);
+
+    let rates = ethdev::VfStats::rate(&delta, Duration::from_secs(2));
+
+    assert_eq!(rates.rx_pps, 100.0);
+    assert_eq!(rates.rx_bps, 51200.0);
+    assert_eq!(rates.tx_pps, 0.0);
+
+    let delta = ethdev::PortStats { ipackets: 200, ibytes: 12800, ..Default::default() };
+    let mut acc = ethdev::StatsAccumulator::new(2);
+
+    acc.push(delta, Duration::from_secs(1));
+    acc.push(delta, Duration::from_secs(1));
+    acc.push(delta, Duration::from_secs(2));
+
+    assert_eq!(acc.len(), 2);
+    assert_eq!(acc.total(), (delta + delta, Duration::from_secs(3)));
+    assert_eq!(acc.rates().tx_bps, 0.0);
+
+    let mut acc = ethdev::StatsAccumulator::new(2);
+    let before = ethdev::PortStats::from(dev.stats().unwrap());
+
+    // the first poll only takes the snapshot
+    acc.poll(&dev).unwrap();
+
+    assert!(acc.is_empty());
+
+    acc.poll(&dev).unwrap();
+
+    assert_eq!(acc.len(), 1);
+
+    // the counters of the port are not reset by the polls
+    let after = ethdev::PortStats::from(dev.stats().unwrap());
+
+    assert!(after.ipackets >= before.ipackets);
+    assert!(after.ipackets - before.ipackets >= acc.total().0.ipackets);
+
+    let results = ethdev::start_all(vec![null_dev]);
+
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].0, null_dev);
+    assert!(results[0].1.is_err());
+
+    assert_eq!(ethdev::count(), 2);
+
+    #[cfg(dpdk_ver_18_02)]
+    test_ethdev_owner(null_dev);
+
+    assert_eq!(ethdev::detach(null_dev).unwrap(), "eth_null1");
+    assert_eq!(ethdev::count(), 1);
+
+    // the ring device receives the packets it sent
+    let loop_dev = ethdev::attach("eth_ring0").unwrap();
+
+    loop_dev.configure(1, 1, &ethdev::EthConf::default()).unwrap();
+    loop_dev.rx_queue_setup(0, 128, None, p).unwrap();
+    loop_dev.tx_queue_setup(0, 512, None).unwrap();
+    loop_dev.start().unwrap();
+
+    assert_eq!(ethdev::loopback_test(loop_dev, p, 16).unwrap(), 16);
+
+    loop_dev.stop().close();
+
+    assert_eq!(ethdev::detach(loop_dev).unwrap(), "eth_ring0");
+
+    // the mempool of the null device is freed and can be created again for the same port
+    for _ in 0..2 {
+        let null_dev = ethdev::create_null_port(1).unwrap();
+
+        ethdev::destroy_null_port(null_dev).unwrap();
+    }
+
+    let mut buf = ethdev::alloc_buffer_owned(32, memory::SOCKET_ID_ANY).unwrap();
+
+    buf.set_err_handler(|pkts| assert!(pkts.is_empty())).unwrap();
+    buf.drop_err_packets().unwrap();
+}
+
+#[cfg(dpdk_ver_17_02)]
+fn test_ethdev_vf(dev: ethdev::PortId) {
+    match dev.set_vf_mac_addr(0, &[0; 6]) {
+        Err(Error::OsError(errno)) => assert_eq!(errno, ::libc::EINVAL),
+        res => panic!("unexpected result {:?}", res.map(|_| ())),
+    }
+
+    assert!(dev.get_vf_stats(0).is_err());
+
+    // the null device has no VF
+    match dev.set_vf_rx_vlan(0, 100, true) {
+        Err(Error::OsError(errno)) => assert_eq!(errno, ::libc::EINVAL),
+        res => panic!("unexpected result {:?}", res.map(|_| ())),
+    }
+}
+
+#[cfg(dpdk_ver_18_02)]
+fn test_ethdev_reset(dev: ethdev::PortId) {
+    match dev.reset() {
+        Err(Error::OsError(errno)) => assert_eq!(errno, ::libc::EBUSY),
+        res => panic!("unexpected result {:?}", res.map(|_| ())),
+    }
+
+    match ethdev::PortId::new_unchecked(ffi::RTE_MAX_ETHPORTS as u8 - 1).reset() {
+        Err(Error::InvalidPortId(port_id)) => assert_eq!(port_id, ffi::RTE_MAX_ETHPORTS as u8 - 1),
+        res => panic!("unexpected result {:?}", res.map(|_| ())),
+    }
+}
+
+#[cfg(dpdk_ver_18_02)]
+fn test_ethdev_owner(dev: ethdev::PortId) {
+    let owner_id = ethdev::owner_new().unwrap();
+
+    dev.owner_set(owner_id, "test").unwrap();
+
+    assert_eq!(dev.owner_get().unwrap(),
+               ethdev::EthDevOwner {
+                   id: owner_id,
+                   name: String::from("test"),
+               });
+    assert!(dev.owner_set(ethdev::owner_new().unwrap(), "other").is_err());
+
+    assert!(dev.owner_unset(ethdev::owner_new().unwrap()).is_err());
+
+    dev.owner_unset(owner_id).unwrap();
+
+    assert_eq!(dev.owner_get().unwrap().id, 0);
+}
+
+fn test_meter() {
+    assert!(meter::SrTcm::new(&meter::SrTcmProfile {
+                cir: 1000000,
+                cbs: 0,
+                ebs: 0,
+            })
+            .is_err());
+
+    let mut m = meter::SrTcm::new(&meter::SrTcmProfile {
+            cir: 1000000,
+            cbs: 2048,
+            ebs: 2048,
+        })
+        .unwrap();
+
+    let now = cycles::rdtsc();
+
+    assert_eq!(m.color(1024, now), meter::MeterColor::Green);
+    assert_eq!(m.color(1024, now), meter::MeterColor::Green);
+    assert_eq!(m.color(1024, now), meter::MeterColor::Yellow);
+    assert_eq!(m.color(1024, now), meter::MeterColor::Yellow);
+    assert_eq!(m.color(1024, now), meter::MeterColor::Red);
+
+    assert!(meter::TrTcm::new(&meter::TrTcmProfile {
+                cir: 2000000,
+                pir: 1000000,
+                cbs: 2048,
+                pbs: 2048,
+            })
+            .is_err());
+}
+
+fn test_allocator() {
+    use std::alloc::{GlobalAlloc, Layout};
+
+    let a = malloc::DpdkAllocator::socket_any();
+    let layout = Layout::from_size_align(8192, 4096).unwrap();
+
+    unsafe {
+        let p = a.alloc(layout);
+
+        assert!(!p.is_null());
+        assert_eq!(p as usize % 4096, 0);
+
+        let phys = malloc::virt2phy(p as *const c_void);
+
+        assert!(phys != 0);
+        assert_eq!(malloc::virt2phy(p.offset(4096) as *const c_void), phys + 4096);
+
+        let p = a.realloc(p, layout, 16384);
+
+        assert!(!p.is_null());
+        assert_eq!(p as usize % 4096, 0);
+
+        a.dealloc(p, Layout::from_size_align(16384, 4096).unwrap());
+    }
+}
+
+fn test_memory() {
+    let segs = memory::hugepages_info();
+
+    assert!(!segs.is_empty());
+    assert!(segs.iter().all(|seg| seg.len > 0 && seg.hugepage_sz > 0));
+
+    let total = segs.iter().map(|seg| seg.len as u64).sum::<u64>();
+
+    assert_eq!(memory::total_memory(memory::SOCKET_ID_ANY), total);
+    assert!(memory::free_memory(memory::SOCKET_ID_ANY) > 0);
+    assert!(memory::free_memory(memory::SOCKET_ID_ANY) <= total);
+}
+
+fn test_memzone() {
+    let mz = memzone::Memzone::reserve("test_mz",
+                                       4096,
+                                       memory::SOCKET_ID_ANY,
+                                       memzone::MemzoneFlags::empty())
+        .unwrap();
+
+    assert_eq!(mz.name(), "test_mz");
+    assert_eq!(mz.len(), 4096);
+    assert!(!mz.as_ptr::<u8>().is_null());
+    assert!(mz.iova() != 0);
+
+    {
+        let found = memzone::Memzone::lookup("test_mz").unwrap();
+
+        assert_eq!(found.as_raw(), mz.as_raw());
+    }
+
+    mz.free().unwrap();
+
+    assert!(memzone::Memzone::lookup("test_mz").is_none());
+}
+
+fn test_delay() {
+    let start = cycles::rdtsc();
+
+    delay::tsc(1000);
+
+    assert!(cycles::rdtsc() - start >= 1000);
+
+    let start = cycles::rdtsc();
+
+    delay::us(10);
+
+    assert!(cycles::rdtsc() - start >= cycles::get_tsc_hz() / 100_000);
+}
+
+#[cfg(dpdk_ver_17_05)]
+fn test_distributor() {
+    let d = distributor::Distributor::create("test_dist",
+                                             eal::socket_id(),
+                                             1,
+                                             distributor::DistributorType::Burst)
+        .unwrap();
+
+    assert!(!d.as_raw().is_null());
+    assert_eq!(d.process(&mut []), 0);
+    assert_eq!(d.flush(), 0);
+
+    let mut returns = [ptr::null_mut(); distributor::RTE_DIST_BURST_SIZE];
+
+    assert_eq!(d.returned_pkts(&mut returns), 0);
+
+    d.clear_returns();
+}
+
+fn test_sync() {
+    let counter = Arc::new(sync::RteSpinlock::new(0));
+
+    let handles: Vec<thread::JoinHandle<()>> = (0..4)
+        .map(|_| {
+            let counter = counter.clone();
+
+            thread::spawn(move || for _ in 0..1000 {
+                *counter.lock() += 1;
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    assert_eq!(*counter.lock(), 4000);
+
+    {
+        let _guard = counter.lock();
+
+        assert!(counter.is_locked());
+        assert!(counter.try_lock().is_none());
+    }
+
+    assert!(!counter.is_locked());
+    assert!(counter.try_lock().is_some());
+
+    let rwlock = sync::RteRwlock::new(vec![1, 2, 3]);
+
+    {
+        let r1 = rwlock.read();
+        let r2 = rwlock.read();
+
+        assert_eq!(r1.len() + r2.len(), 6);
+    }
+
+    rwlock.write().push(4);
+
+    assert_eq!(*rwlock.read(), vec![1, 2, 3, 4]);
+    assert_eq!(rwlock.into_inner(), vec![1, 2, 3, 4]);
+}
+
+fn test_crypto() {
+    let p = crypto::op_pool_create("crypto_op_pool",
+                                   64,
+                                   0,
+                                   16,
+                                   crypto::CryptoOpType::Symmetric,
+                                   eal::socket_id())
+        .as_mut_ref()
+        .unwrap();
+
+    assert_eq!(p.name(), "crypto_op_pool");
+    assert_eq!(p.size, 64);
+
+    let op = crypto::op_alloc(p, crypto::CryptoOpType::SymmetricSessionless).unwrap();
+
+    assert!(!op.is_null());
+    assert_eq!(p.count(), 63);
+
+    crypto::op_free(op);
+
+    let mut ops = [ptr::null_mut(); 8];
+
+    crypto::op_bulk_alloc(p, crypto::CryptoOpType::Symmetric, &mut ops).unwrap();
+
+    assert!(ops.iter().all(|op| !op.is_null()));
+    assert_eq!(p.count(), 56);
+
+    for op in &ops {
+        crypto::op_free(*op);
+    }
+
+    assert_eq!(p.count(), 64);
+    assert!(crypto::op_bulk_alloc(p, crypto::CryptoOpType::Symmetric, &mut [ptr::null_mut(); 65])
+        .is_err());
 }
@@ -0,0 +1,103 @@
+use libc;
+
+use errors::{Error, Result};
+
+/// The length of the RSS key used by most of the NICs.
+pub const RSS_KEY_LEN: usize = 40;
+
+/// Toeplitz hash key, used to compute the RSS hash in software.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ThashKey(Vec<u8>);
+
+impl ThashKey {
+    /// Create a key from the bytes.
+    pub fn new(key: &[u8]) -> ThashKey {
+        ThashKey(key.to_vec())
+    }
+
+    /// Generate a symmetric key, which hashes the swapped source and destination
+    /// of the addresses and ports to the same value.
+    ///
+    /// The key repeats a 16 bits pattern, so the fields of the same width
+    /// at an offset of 16 bits multiple contribute the same to the hash.
+    ///
+    pub fn new_symmetric(key_len: usize) -> ThashKey {
+        ThashKey((0..key_len).map(|i| if i % 2 == 0 { 0x6d } else { 0x5a }).collect())
+    }
+
+    /// The key as bytes, e.g. to configure `EthRssConf`.
+    pub fn bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Compute the Toeplitz hash of the tuple in the network byte order.
+    ///
+    /// The tuple is padded to a multiple of 4 bytes,
+    /// and the key must be at least 4 bytes longer than the padded tuple,
+    /// otherwise `InvalidRssKeyLen` is returned with the minimal length.
+    ///
+    pub fn compute(&self, data: &[u8]) -> Result<u32> {
+        let mut tuple: Vec<u32> = data.chunks(4)
+            .map(|chunk| {
+                chunk.iter()
+                    .chain([0u8; 4].iter())
+                    .take(4)
+                    .fold(0u32, |v, b| (v << 8) | (*b as u32))
+            })
+            .collect();
+
+        let min_key_len = tuple.len() * 4 + 4;
+
+        if self.0.len() < min_key_len {
+            return Err(Error::InvalidRssKeyLen(min_key_len, self.0.len()));
+        }
+
+        Ok(unsafe { _rte_softrss(tuple.as_mut_ptr(), tuple.len() as u32, self.0.as_ptr()) })
+    }
+
+    /// Compute the Toeplitz hash of the tuples.
+    pub fn compute_bulk(&self, tuples: &[&[u8]]) -> Result<Vec<u32>> {
+        tuples.iter().map(|tuple| self.compute(tuple)).collect()
+    }
+}
+
+extern "C" {
+    fn _rte_softrss(input_tuple: *mut libc::uint32_t,
+                    input_len: libc::uint32_t,
+                    rss_key: *const libc::uint8_t)
+                    -> libc::uint32_t;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_symmetric_key() {
+        let key = ThashKey::new_symmetric(RSS_KEY_LEN);
+
+        assert_eq!(key.bytes().len(), RSS_KEY_LEN);
+        assert_eq!(&key.bytes()[..4], &[0x6d, 0x5a, 0x6d, 0x5a]);
+
+        // src ip, dst ip, src port, dst port
+        let forward = [192, 168, 0, 1, 10, 0, 0, 2, 0x30, 0x39, 0x00, 0x50];
+        let reverse = [10, 0, 0, 2, 192, 168, 0, 1, 0x00, 0x50, 0x30, 0x39];
+
+        let hash = key.compute(&forward).unwrap();
+
+        assert_eq!(key.compute(&reverse).unwrap(), hash);
+        assert_eq!(key.compute_bulk(&[&forward, &reverse]).unwrap(), vec![hash, hash]);
+
+        match ThashKey::new_symmetric(12).compute(&forward) {
+            Err(Error::InvalidRssKeyLen(expected, actual)) => assert_eq!((expected, actual), (16, 12)),
+            res => panic!("unexpected result {:?}", res),
+        }
+
+        let key = ThashKey::new(&[0x6d, 0x5a, 0x56, 0xda, 0x25, 0x5b, 0x0e, 0xc2, 0x41, 0x67,
+                                  0x25, 0x3d, 0x43, 0xa3, 0x8f, 0xb0, 0xd0, 0xca, 0x2b, 0xcb,
+                                  0xae, 0x7b, 0x30, 0xb4, 0x77, 0xcb, 0x2d, 0xa3, 0x80, 0x30,
+                                  0xf2, 0x0c, 0x6a, 0x42, 0xb7, 0x3b, 0xbe, 0xac, 0x01, 0xfa]);
+
+        assert!(key.compute(&forward).unwrap() != key.compute(&reverse).unwrap());
+    }
+}
@@ -0,0 +1,216 @@
+//! Hierarchical traffic manager of the Ethernet devices.
+//!
+//! The leaf nodes of the hierarchy are the TX queues of the port, their node ids are
+//! the queue ids, the other nodes must use the ids above `RTE_MAX_QUEUES_PER_PORT`.
+
+use std::mem;
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_void};
+
+use libc;
+
+use ffi;
+
+use errors::{Error, Result};
+use ethdev::{EthDevice, PortId};
+
+/// The node id of the root node added with `TmNode::root()`.
+pub const TM_ROOT_NODE_ID: u32 = ffi::RTE_MAX_QUEUES_PER_PORT;
+
+/// Invalid node id, used as the parent id of the root node.
+pub const RTE_TM_NODE_ID_NULL: u32 = !0;
+
+/// The node is not attached to a shaper profile.
+pub const RTE_TM_SHAPER_PROFILE_ID_NONE: u32 = !0;
+
+/// The driver selects the hierarchy level of the node.
+const RTE_TM_NODE_LEVEL_ID_ANY: u32 = !0;
+
+#[repr(C)]
+struct RawTmError {
+    type_: libc::c_uint,
+    cause: *const c_void,
+    message: *const c_char,
+}
+
+impl Default for RawTmError {
+    fn default() -> Self {
+        unsafe { mem::zeroed() }
+    }
+}
+
+fn tm_check(port_id: PortId, ret: libc::c_int, err: &RawTmError) -> Result<()> {
+    if ret < 0 && !err.message.is_null() {
+        debug!("traffic manager of port {} failed, {}",
+               port_id,
+               unsafe { CStr::from_ptr(err.message).to_string_lossy() });
+    }
+
+    rte_check!(ret)
+}
+
+/// The parameters of a token bucket shaper.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ShaperParams {
+    /// The token bucket rate, in bytes or packets per second.
+    pub rate: u64,
+    /// The token bucket size, in bytes or packets.
+    pub burst_size: u64,
+    /// The rate and size are counted in packets instead of bytes.
+    pub packet_mode: bool,
+}
+
+/// The shaper profiles of the traffic manager.
+pub struct TmShaper;
+
+impl TmShaper {
+    /// Add a shaper profile which can be attached to the nodes of the hierarchy.
+    pub fn profile_add(port_id: PortId, profile_id: u32, params: &ShaperParams) -> Result<()> {
+        let mut err = RawTmError::default();
+
+        let ret = unsafe {
+            _rte_tm_shaper_profile_add(u8::from(port_id) as u16,
+                                       profile_id,
+                                       params.rate,
+                                       params.burst_size,
+                                       params.packet_mode as libc::c_int,
+                                       &mut err)
+        };
+
+        tm_check(port_id, ret, &err)
+    }
+
+    /// Delete a shaper profile which is not used by any node.
+    pub fn profile_delete(port_id: PortId, profile_id: u32) -> Result<()> {
+        let mut err = RawTmError::default();
+
+        let ret = unsafe {
+            rte_tm_shaper_profile_delete(u8::from(port_id) as u16, profile_id, &mut err)
+        };
+
+        tm_check(port_id, ret, &err)
+    }
+}
+
+/// The nodes of the traffic manager hierarchy.
+pub struct TmNode;
+
+impl TmNode {
+    /// Add the root node of the hierarchy, returns its node id.
+    pub fn root(port_id: PortId,
+                shaper_profile_id: u32,
+                n_sp_priorities: u32,
+                stats_mask: u64)
+                -> Result<u32> {
+        try!(Self::add(port_id,
+                       TM_ROOT_NODE_ID,
+                       RTE_TM_NODE_ID_NULL,
+                       0,
+                       1,
+                       shaper_profile_id,
+                       n_sp_priorities,
+                       stats_mask));
+
+        Ok(TM_ROOT_NODE_ID)
+    }
+
+    /// Add a node to the hierarchy, the node is a leaf if its id is a TX queue id.
+    ///
+    /// The `n_sp_priorities` strict priorities of the children are ignored for the leaf nodes.
+    ///
+    pub fn add(port_id: PortId,
+               node_id: u32,
+               parent_id: u32,
+               priority: u32,
+               weight: u32,
+               shaper_profile_id: u32,
+               n_sp_priorities: u32,
+               stats_mask: u64)
+               -> Result<()> {
+        if node_id == RTE_TM_NODE_ID_NULL || n_sp_priorities == 0 {
+            return Err(Error::OsError(libc::EINVAL));
+        }
+
+        let leaf = node_id < try!(port_id.nb_tx_queues()) as u32;
+        let mut err = RawTmError::default();
+
+        let ret = unsafe {
+            _rte_tm_node_add(u8::from(port_id) as u16,
+                             node_id,
+                             parent_id,
+                             priority,
+                             weight,
+                             RTE_TM_NODE_LEVEL_ID_ANY,
+                             shaper_profile_id,
+                             n_sp_priorities,
+                             stats_mask,
+                             leaf as libc::c_int,
+                             &mut err)
+        };
+
+        tm_check(port_id, ret, &err)
+    }
+
+    /// Delete a node without children from the hierarchy.
+    pub fn delete(port_id: PortId, node_id: u32) -> Result<()> {
+        let mut err = RawTmError::default();
+
+        let ret = unsafe { rte_tm_node_delete(u8::from(port_id) as u16, node_id, &mut err) };
+
+        tm_check(port_id, ret, &err)
+    }
+}
+
+/// Commit the hierarchy before the port is started.
+///
+/// The hierarchy is cleared on failure when `clear_on_fail` is set.
+///
+pub fn hierarchy_commit(port_id: PortId, clear_on_fail: bool) -> Result<()> {
+    let mut err = RawTmError::default();
+
+    let ret = unsafe {
+        rte_tm_hierarchy_commit(u8::from(port_id) as u16,
+                                clear_on_fail as libc::c_int,
+                                &mut err)
+    };
+
+    tm_check(port_id, ret, &err)
+}
+
+extern "C" {
+    fn rte_tm_shaper_profile_delete(port_id: libc::uint16_t,
+                                    shaper_profile_id: libc::uint32_t,
+                                    error: *mut RawTmError)
+                                    -> libc::c_int;
+
+    fn rte_tm_node_delete(port_id: libc::uint16_t,
+                          node_id: libc::uint32_t,
+                          error: *mut RawTmError)
+                          -> libc::c_int;
+
+    fn rte_tm_hierarchy_commit(port_id: libc::uint16_t,
+                               clear_on_fail: libc::c_int,
+                               error: *mut RawTmError)
+                               -> libc::c_int;
+
+    fn _rte_tm_shaper_profile_add(port_id: libc::uint16_t,
+                                  shaper_profile_id: libc::uint32_t,
+                                  rate: libc::uint64_t,
+                                  size: libc::uint64_t,
+                                  packet_mode: libc::c_int,
+                                  error: *mut RawTmError)
+                                  -> libc::c_int;
+
+    fn _rte_tm_node_add(port_id: libc::uint16_t,
+                        node_id: libc::uint32_t,
+                        parent_node_id: libc::uint32_t,
+                        priority: libc::uint32_t,
+                        weight: libc::uint32_t,
+                        level_id: libc::uint32_t,
+                        shaper_profile_id: libc::uint32_t,
+                        n_sp_priorities: libc::uint32_t,
+                        stats_mask: libc::uint64_t,
+                        leaf: libc::c_int,
+                        error: *mut RawTmError)
+                        -> libc::c_int;
+}
@@ -0,0 +1,258 @@
+//! Trace points recorded in the CTF trace buffers of DPDK.
+//!
+//! A trace point is defined as a static with `define_trace_point!`, registered once
+//! with `TracePoint::register()` and emitted with `trace_emit!`.
+//!
+//! ```no_run
+//! # #[macro_use] extern crate rte;
+//! define_trace_point!(RX_BURST, "app.rx.burst", port: u16, queue: u16, nb_pkts: u16);
+//!
+//! # fn main() {
+//! RX_BURST.register().unwrap();
+//! RX_BURST.enable().unwrap();
+//!
+//! trace_emit!(RX_BURST, 0u16, 0u16, 32u16);
+//! # }
+//! ```
+
+use std::mem;
+use std::ptr;
+use std::cell::{RefCell, UnsafeCell};
+use std::ffi::CString;
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::os::raw::{c_char, c_void};
+
+use libc;
+
+use errors::{Error, Result};
+
+/// The handle of a trace point, updated by DPDK when it is registered or enabled.
+pub type RawTracePoint = u64;
+
+/// The bit of the handle set when the trace point is enabled.
+const RTE_TRACE_POINT_ENABLE_MASK: u64 = 1 << 63;
+
+/// The maximum size of the fields of a trace point.
+const TRACE_POINT_MAX_PAYLOAD: usize = 64;
+
+/// A field type of the trace points, with its CTF type name.
+pub trait TraceField: Copy {
+    fn ctf_type() -> &'static str;
+}
+
+macro_rules! impl_trace_field {
+    ($($t:ty => $name:expr),*) => ($(
+        impl TraceField for $t {
+            fn ctf_type() -> &'static str {
+                $name
+            }
+        }
+    )*)
+}
+
+impl_trace_field!(u8 => "uint8_t", u16 => "uint16_t", u32 => "uint32_t", u64 => "uint64_t",
+                  i8 => "int8_t", i16 => "int16_t", i32 => "int32_t", i64 => "int64_t",
+                  f32 => "float", f64 => "double");
+
+/// The fields of a trace point, implemented for the tuples of `TraceField`.
+pub trait TraceArgs {
+    /// The CTF type names and sizes of the fields.
+    fn types() -> Vec<(&'static str, usize)>;
+
+    /// Write the fields to the buffer, returns the written size.
+    fn write(&self, buf: &mut [u8]) -> usize;
+}
+
+macro_rules! impl_trace_args {
+    ($($name:ident),*) => (
+        impl<$($name: TraceField),*> TraceArgs for ($($name,)*) {
+            fn types() -> Vec<(&'static str, usize)> {
+                vec![$(($name::ctf_type(), mem::size_of::<$name>())),*]
+            }
+
+            #[allow(non_snake_case, unused_mut, unused_variables)]
+            fn write(&self, buf: &mut [u8]) -> usize {
+                let ($(ref $name,)*) = *self;
+                let mut off = 0;
+
+                $(
+                    let sz = mem::size_of::<$name>();
+
+                    assert!(off + sz <= buf.len());
+
+                    unsafe { ptr::write_unaligned(buf[off..].as_mut_ptr() as *mut $name, *$name) };
+
+                    off += sz;
+                )*
+
+                off
+            }
+        }
+    )
+}
+
+impl_trace_args!();
+impl_trace_args!(A);
+impl_trace_args!(A, B);
+impl_trace_args!(A, B, C);
+impl_trace_args!(A, B, C, D);
+impl_trace_args!(A, B, C, D, E);
+impl_trace_args!(A, B, C, D, E, F);
+impl_trace_args!(A, B, C, D, E, F, G);
+impl_trace_args!(A, B, C, D, E, F, G, H);
+
+/// A trace point with the fields `T`, defined with `define_trace_point!`.
+pub struct TracePoint<T> {
+    handle: UnsafeCell<RawTracePoint>,
+    registered: AtomicBool,
+    name: &'static str,
+    fields: &'static [&'static str],
+    _marker: PhantomData<T>,
+}
+
+unsafe impl<T> Sync for TracePoint<T> {}
+
+impl<T> TracePoint<T> {
+    /// Create a trace point, the name must be NUL terminated since DPDK keeps the pointer.
+    #[doc(hidden)]
+    pub const fn new(name: &'static str, fields: &'static [&'static str]) -> TracePoint<T> {
+        TracePoint {
+            handle: UnsafeCell::new(0),
+            registered: AtomicBool::new(false),
+            name: name,
+            fields: fields,
+            _marker: PhantomData,
+        }
+    }
+}
+
+thread_local! {
+    // DPDK describes the fields in a callback without argument, on the registering thread.
+    static REGISTERING_FIELDS: RefCell<Vec<(usize, CString, CString)>> = RefCell::new(Vec::new());
+}
+
+extern "C" fn register_fields() {
+    REGISTERING_FIELDS.with(|fields| {
+        for &(sz, ref name, ref ty) in fields.borrow().iter() {
+            unsafe { __rte_trace_point_emit_field(sz, name.as_ptr(), ty.as_ptr()) }
+        }
+    })
+}
+
+impl<T: TraceArgs> TracePoint<T> {
+    /// The name of the trace point.
+    pub fn name(&self) -> &'static str {
+        self.name.split('\0').next().unwrap_or_default()
+    }
+
+    /// Register the trace point, it is registered only once.
+    pub fn register(&'static self) -> Result<()> {
+        let types = T::types();
+
+        if types.len() != self.fields.len() || !self.name.ends_with('\0') {
+            return Err(Error::OsError(libc::EINVAL));
+        }
+
+        if self.registered.swap(true, Ordering::SeqCst) {
+            return Ok(());
+        }
+
+        let fields = try!(self.fields
+            .iter()
+            .zip(types.iter())
+            .map(|(name, &(ty, sz))| Ok((sz, try!(CString::new(*name)), try!(CString::new(ty)))))
+            .collect::<Result<Vec<(usize, CString, CString)>>>());
+
+        REGISTERING_FIELDS.with(|registering| *registering.borrow_mut() = fields);
+
+        let ret = unsafe {
+            __rte_trace_point_register(self.handle.get(),
+                                       self.name.as_ptr() as *const c_char,
+                                       Some(register_fields))
+        };
+
+        REGISTERING_FIELDS.with(|registering| registering.borrow_mut().clear());
+
+        rte_check!(ret; err => {
+            self.registered.store(false, Ordering::SeqCst);
+
+            Error::from_ret(ret)
+        })
+    }
+
+    /// Enable the registered trace point.
+    pub fn enable(&self) -> Result<()> {
+        let ret = unsafe { rte_trace_point_enable(self.handle.get()) };
+
+        rte_check!(ret)
+    }
+
+    /// Disable the registered trace point.
+    pub fn disable(&self) -> Result<()> {
+        let ret = unsafe { rte_trace_point_disable(self.handle.get()) };
+
+        rte_check!(ret)
+    }
+
+    /// Test if the trace point is enabled.
+    pub fn is_enabled(&self) -> bool {
+        unsafe { rte_trace_point_is_enabled(self.handle.get()) }
+    }
+
+    /// Record the fields in the trace buffer of the current thread if the trace point is enabled.
+    #[inline]
+    pub fn emit(&self, args: T) {
+        let handle = unsafe { ptr::read_volatile(self.handle.get()) };
+
+        if handle & RTE_TRACE_POINT_ENABLE_MASK == 0 {
+            return;
+        }
+
+        let mut buf = [0u8; TRACE_POINT_MAX_PAYLOAD];
+        let len = args.write(&mut buf);
+
+        unsafe {
+            _rte_trace_point_emit(self.handle.get(), buf.as_ptr() as *const c_void, len as u32)
+        }
+    }
+}
+
+/// Define a static trace point with the name and the typed fields.
+#[macro_export]
+macro_rules! define_trace_point {
+    ($point:ident, $name:expr $(, $field:ident : $t:ty)*) => (
+        static $point: $crate::trace::TracePoint<($($t,)*)> =
+            $crate::trace::TracePoint::new(concat!($name, "\0"), &[$(stringify!($field)),*]);
+    );
+    ($point:ident, $name:expr $(, $field:ident : $t:ty)*,) => (
+        define_trace_point!($point, $name $(, $field: $t)*);
+    )
+}
+
+/// Emit a trace point, the arguments must match the types of its fields.
+#[macro_export]
+macro_rules! trace_emit {
+    ($point:expr $(, $arg:expr)*) => (
+        $point.emit(($($arg,)*))
+    )
+}
+
+extern "C" {
+    fn __rte_trace_point_register(handle: *mut RawTracePoint,
+                                  name: *const c_char,
+                                  register_fn: Option<extern "C" fn()>)
+                                  -> libc::c_int;
+
+    fn __rte_trace_point_emit_field(sz: libc::size_t,
+                                    field: *const c_char,
+                                    type_: *const c_char);
+
+    fn rte_trace_point_enable(handle: *mut RawTracePoint) -> libc::c_int;
+
+    fn rte_trace_point_disable(handle: *mut RawTracePoint) -> libc::c_int;
+
+    fn rte_trace_point_is_enabled(handle: *mut RawTracePoint) -> bool;
+
+    fn _rte_trace_point_emit(handle: *mut RawTracePoint, data: *const c_void, len: libc::uint32_t);
+}
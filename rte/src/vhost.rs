@@ -0,0 +1,241 @@
+use std::ptr;
+use std::slice;
+use std::ffi::CStr;
+use std::os::raw::c_void;
+
+use libc;
+
+use errors::Result;
+use mbuf;
+use mempool::RawMemoryPool;
+
+bitflags! {
+    pub flags VhostFlags: u64 {
+        /// Vhost-user acts as the client side of the unix socket.
+        const RTE_VHOST_USER_CLIENT             = 1 << 0,
+        /// Disable the reconnection in client mode.
+        const RTE_VHOST_USER_NO_RECONNECT       = 1 << 1,
+        /// Enable the dequeue zero copy.
+        const RTE_VHOST_USER_DEQUEUE_ZERO_COPY  = 1 << 2,
+        /// Enable the IOMMU support.
+        const RTE_VHOST_USER_IOMMU_SUPPORT      = 1 << 3,
+    }
+}
+
+/// The identifier of a vhost device.
+pub type VhostDeviceId = libc::c_int;
+
+/// Device and vring operations called by the vhost library.
+#[repr(C)]
+pub struct VhostDeviceOps {
+    /// Add device.
+    pub new_device: Option<extern "C" fn(vid: VhostDeviceId) -> libc::c_int>,
+    /// Remove device.
+    pub destroy_device: Option<extern "C" fn(vid: VhostDeviceId)>,
+    /// Triggered when a vring is enabled or disabled.
+    pub vring_state_changed: Option<extern "C" fn(vid: VhostDeviceId,
+                                                   queue_id: u16,
+                                                   enable: libc::c_int)
+                                                   -> libc::c_int>,
+    /// Triggered when the negotiated features are changed.
+    pub features_changed: Option<extern "C" fn(vid: VhostDeviceId, features: u64) -> libc::c_int>,
+    /// Triggered when a new connection is established.
+    pub new_connection: Option<extern "C" fn(vid: VhostDeviceId) -> libc::c_int>,
+    /// Triggered when a connection is closed.
+    pub destroy_connection: Option<extern "C" fn(vid: VhostDeviceId)>,
+    pub reserved: [*mut c_void; 2],
+}
+
+impl Default for VhostDeviceOps {
+    fn default() -> Self {
+        VhostDeviceOps {
+            new_device: None,
+            destroy_device: None,
+            vring_state_changed: None,
+            features_changed: None,
+            new_connection: None,
+            destroy_connection: None,
+            reserved: [ptr::null_mut(); 2],
+        }
+    }
+}
+
+/// Register a vhost driver, `path` could be different for multiple instances support.
+pub fn driver_register(path: &str, flags: VhostFlags) -> Result<()> {
+    let ret = unsafe { rte_vhost_driver_register(try!(to_cptr!(path)), flags.bits) };
+
+    rte_check!(ret)
+}
+
+/// Unregister a vhost driver and close the socket.
+pub fn driver_unregister(path: &str) -> Result<()> {
+    let ret = unsafe { rte_vhost_driver_unregister(try!(to_cptr!(path))) };
+
+    rte_check!(ret)
+}
+
+/// Register the device and vring operations of the vhost driver.
+pub fn callback_register(path: &str, ops: &'static VhostDeviceOps) -> Result<()> {
+    let ret = unsafe { rte_vhost_driver_callback_register(try!(to_cptr!(path)), ops) };
+
+    rte_check!(ret)
+}
+
+/// Start the vhost-user driver.
+///
+/// The device add/remove callbacks are invoked from the vhost-user session thread.
+///
+pub fn driver_start(path: &str) -> Result<()> {
+    let ret = unsafe { rte_vhost_driver_start(try!(to_cptr!(path))) };
+
+    rte_check!(ret)
+}
+
+/// A guest memory region which could be used for DMA by the backend.
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct VhostMemRegion {
+    pub guest_phys_addr: u64,
+    pub guest_user_addr: u64,
+    pub host_user_addr: u64,
+    pub size: u64,
+    pub mmap_addr: *mut c_void,
+    pub mmap_size: u64,
+    pub fd: libc::c_int,
+}
+
+#[repr(C)]
+struct RawVhostMemory {
+    nregions: u32,
+    regions: [VhostMemRegion; 0],
+}
+
+pub trait VhostDevice {
+    /// Get the name of the socket or the interface of the vhost device.
+    fn ifname(&self) -> Result<String>;
+
+    /// Get the virtio net features negotiated with the guest.
+    fn negotiated_features(&self) -> Result<u64>;
+
+    /// Get the number of vrings the device supports.
+    fn vring_num(&self) -> u16;
+
+    /// Get the guest memory regions of the vhost device.
+    fn mem_table(&self) -> Result<Vec<VhostMemRegion>>;
+
+    /// Receive a burst of packets sent by the guest from the virtqueue of the device.
+    ///
+    /// The packets are copied into the mbufs allocated from the mempool.
+    ///
+    fn rx_burst(&self,
+                virtqueue_id: u16,
+                mbuf_pool: &mut RawMemoryPool,
+                pkts: &mut [mbuf::RawMbufPtr])
+                -> u16;
+
+    /// Send a burst of packets to the guest through the virtqueue of the device.
+    ///
+    /// The packets are copied, the caller still owns the mbufs.
+    ///
+    fn tx_burst(&self, virtqueue_id: u16, pkts: &mut [mbuf::RawMbufPtr]) -> u16;
+}
+
+impl VhostDevice for VhostDeviceId {
+    fn ifname(&self) -> Result<String> {
+        let mut buf = [0 as libc::c_char; 256];
+
+        let ret = unsafe { rte_vhost_get_ifname(*self, buf.as_mut_ptr(), buf.len()) };
+
+        rte_check!(ret; ok => {
+            unsafe { CStr::from_ptr(buf.as_ptr()).to_string_lossy().into_owned() }
+        })
+    }
+
+    fn negotiated_features(&self) -> Result<u64> {
+        let mut features = 0;
+
+        let ret = unsafe { rte_vhost_get_negotiated_features(*self, &mut features) };
+
+        rte_check!(ret; ok => { features })
+    }
+
+    fn vring_num(&self) -> u16 {
+        unsafe { rte_vhost_get_vring_num(*self) }
+    }
+
+    fn mem_table(&self) -> Result<Vec<VhostMemRegion>> {
+        let mut mem: *mut RawVhostMemory = ptr::null_mut();
+
+        let ret = unsafe { rte_vhost_get_mem_table(*self, &mut mem) };
+
+        rte_check!(ret; ok => {
+            unsafe {
+                let regions = slice::from_raw_parts((*mem).regions.as_ptr(),
+                                                    (*mem).nregions as usize)
+                    .to_vec();
+
+                libc::free(mem as *mut c_void);
+
+                regions
+            }
+        })
+    }
+
+    fn rx_burst(&self,
+                virtqueue_id: u16,
+                mbuf_pool: &mut RawMemoryPool,
+                pkts: &mut [mbuf::RawMbufPtr])
+                -> u16 {
+        unsafe {
+            rte_vhost_dequeue_burst(*self,
+                                    virtqueue_id,
+                                    mbuf_pool,
+                                    pkts.as_mut_ptr(),
+                                    pkts.len() as u16)
+        }
+    }
+
+    fn tx_burst(&self, virtqueue_id: u16, pkts: &mut [mbuf::RawMbufPtr]) -> u16 {
+        unsafe {
+            rte_vhost_enqueue_burst(*self, virtqueue_id, pkts.as_mut_ptr(), pkts.len() as u16)
+        }
+    }
+}
+
+extern "C" {
+    fn rte_vhost_driver_register(path: *const libc::c_char, flags: libc::uint64_t) -> libc::c_int;
+
+    fn rte_vhost_driver_unregister(path: *const libc::c_char) -> libc::c_int;
+
+    fn rte_vhost_driver_callback_register(path: *const libc::c_char,
+                                          ops: *const VhostDeviceOps)
+                                          -> libc::c_int;
+
+    fn rte_vhost_driver_start(path: *const libc::c_char) -> libc::c_int;
+
+    fn rte_vhost_get_ifname(vid: libc::c_int,
+                            buf: *mut libc::c_char,
+                            len: libc::size_t)
+                            -> libc::c_int;
+
+    fn rte_vhost_get_negotiated_features(vid: libc::c_int,
+                                         features: *mut libc::uint64_t)
+                                         -> libc::c_int;
+
+    fn rte_vhost_get_vring_num(vid: libc::c_int) -> libc::uint16_t;
+
+    fn rte_vhost_get_mem_table(vid: libc::c_int, mem: *mut *mut RawVhostMemory) -> libc::c_int;
+
+    fn rte_vhost_enqueue_burst(vid: libc::c_int,
+                               queue_id: libc::uint16_t,
+                               pkts: *mut mbuf::RawMbufPtr,
+                               count: libc::uint16_t)
+                               -> libc::uint16_t;
+
+    fn rte_vhost_dequeue_burst(vid: libc::c_int,
+                               queue_id: libc::uint16_t,
+                               mbuf_pool: *mut RawMemoryPool,
+                               pkts: *mut mbuf::RawMbufPtr,
+                               count: libc::uint16_t)
+                               -> libc::uint16_t;
+}